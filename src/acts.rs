@@ -0,0 +1,176 @@
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+
+use crate::components::*;
+use crate::map_generator::generate_map;
+use crate::resources::*;
+
+pub const ACT_COUNT: usize = 3;
+
+// Number of normal battles per act drawn from the easy pool before switching to the hard pool
+pub const EASY_BATTLES_PER_ACT: usize = 3;
+
+// Fraction of missing HP restored when moving on to the next act
+pub const ACT_HEAL_RATIO: f32 = 0.75;
+
+pub struct ActDefinition {
+    pub name: &'static str,
+    pub easy_pool: Vec<Vec<EnemyKind>>,
+    pub hard_pool: Vec<Vec<EnemyKind>>,
+    pub elite_pool: Vec<Vec<EnemyKind>>,
+    pub boss_pool: Vec<Vec<EnemyKind>>,
+    pub backgrounds: &'static [&'static str],
+    pub boss_background: &'static str,
+}
+
+pub fn act_definition(act: usize) -> ActDefinition {
+    use EnemyKind::*;
+
+    match act {
+        1 => ActDefinition {
+            name: "The Outskirts",
+            easy_pool: vec![
+                vec![Goblin, Goblin],
+                vec![Orc],
+                vec![Goblin, Goblin, Goblin],
+            ],
+            hard_pool: vec![
+                vec![Orc, Goblin],
+                vec![GoblinShaman, Goblin],
+                vec![Goblin, Goblin, Goblin, Goblin],
+            ],
+            elite_pool: vec![vec![Orc, Orc], vec![GoblinShaman, Orc]],
+            boss_pool: vec![vec![DarkKnight]],
+            backgrounds: &[
+                "images/backgrounds/RuinedForest.jpg",
+                "images/backgrounds/Battlefield.jpg",
+            ],
+            boss_background: "images/backgrounds/Battlefield.jpg",
+        },
+        2 => ActDefinition {
+            name: "The Poison Caves",
+            easy_pool: vec![vec![GoblinShaman, Goblin], vec![Orc, Goblin]],
+            hard_pool: vec![
+                vec![Orc, Orc],
+                vec![GoblinShaman, Orc, Goblin],
+                vec![GoblinShaman, GoblinShaman],
+            ],
+            elite_pool: vec![vec![DarkKnight], vec![Orc, GoblinShaman, Orc]],
+            boss_pool: vec![vec![Orc, DarkKnight, GoblinShaman]],
+            backgrounds: &["images/backgrounds/PoisonCave.jpg"],
+            boss_background: "images/backgrounds/PoisonCave.jpg",
+        },
+        _ => ActDefinition {
+            name: "The Dragon's Lair",
+            easy_pool: vec![vec![Whelp, Whelp, Whelp], vec![Orc, GoblinShaman]],
+            hard_pool: vec![
+                vec![Whelp, Orc, Whelp],
+                vec![Orc, Orc, GoblinShaman],
+                vec![DarkKnight],
+            ],
+            elite_pool: vec![vec![DarkKnight, GoblinShaman], vec![DarkKnight, Whelp]],
+            boss_pool: vec![vec![Dragon]],
+            backgrounds: &[
+                "images/backgrounds/DragonLayer.jpg",
+                "images/backgrounds/Battlefield.jpg",
+            ],
+            boss_background: "images/backgrounds/DragonLayer.jpg",
+        },
+    }
+}
+
+// Picks the enemy lineup for a combat node. `battles_fought` counts normal battles this act,
+// including the one being set up.
+pub fn roll_encounter(act: usize, node_type: NodeType, battles_fought: usize) -> Vec<EnemyKind> {
+    let definition = act_definition(act);
+    let pool = match node_type {
+        NodeType::Boss => &definition.boss_pool,
+        NodeType::Elite => &definition.elite_pool,
+        _ if battles_fought <= EASY_BATTLES_PER_ACT => &definition.easy_pool,
+        _ => &definition.hard_pool,
+    };
+
+    pool.choose(&mut thread_rng()).cloned().unwrap_or_default()
+}
+
+pub fn roll_background(act: usize, node_type: NodeType) -> &'static str {
+    let definition = act_definition(act);
+    if node_type == NodeType::Boss {
+        return definition.boss_background;
+    }
+    definition
+        .backgrounds
+        .choose(&mut thread_rng())
+        .copied()
+        .unwrap_or("images/backgrounds/Battlefield.jpg")
+}
+
+pub fn default_rewards(node_type: NodeType, burning: bool) -> EncounterRewards {
+    match node_type {
+        NodeType::Elite => EncounterRewards {
+            gold: (50, 100),
+            cards: true,
+            // Elites always drop a relic, the burning elite an extra one
+            relics: if burning { 2 } else { 1 },
+            relic_choice: false,
+        },
+        // Act bosses offer a pick of three relics on top of a bigger purse
+        NodeType::Boss => EncounterRewards {
+            gold: (95, 105),
+            cards: true,
+            relics: 3,
+            relic_choice: true,
+        },
+        _ => EncounterRewards::default(),
+    }
+}
+
+// The fight waiting on a map node
+pub fn encounter_for_node(
+    act: usize,
+    node_type: NodeType,
+    burning: Option<BurningBuff>,
+    battles_fought: usize,
+) -> Encounter {
+    Encounter {
+        enemies: roll_encounter(act, node_type, battles_fought),
+        background: roll_background(act, node_type).to_string(),
+        burning,
+        rewards: default_rewards(node_type, burning.is_some()),
+    }
+}
+
+pub fn battles_fought_this_act(game_map: &GameMap) -> usize {
+    game_map
+        .visited_path
+        .iter()
+        .map(|(l, i)| &game_map.levels[*l][*i])
+        .filter(|node| node.resolved.unwrap_or(node.node_type) == NodeType::Battle)
+        .count()
+}
+
+pub fn is_final_act(run_state: &RunState) -> bool {
+    run_state.act >= ACT_COUNT
+}
+
+// Moves the run on to a fresh map for the next act and patches the player up
+pub fn start_next_act(run_state: &mut RunState, game_map: &mut GameMap, health: &mut Health) {
+    run_state.act += 1;
+
+    *game_map = GameMap {
+        levels: generate_map(),
+        current_node: None,
+        visited_path: Vec::new(),
+    };
+
+    let missing = health.max - health.current;
+    let healed = (missing as f32 * ACT_HEAL_RATIO) as i32;
+    health.current += healed;
+
+    println!(
+        "Act {}: {} begins. Recovered {} HP.",
+        run_state.act,
+        act_definition(run_state.act).name,
+        healed
+    );
+}
@@ -0,0 +1,128 @@
+use bevy::prelude::*;
+use rand::Rng;
+use rand::thread_rng;
+
+use super::{ClassDefinition, PlayerClass, deck_of};
+use crate::components::*;
+use crate::item_cards::*;
+use crate::item_relics::Relic;
+
+// Missing energy is paid for with HP
+pub const BLOOD_PRICE_PER_ENERGY: i32 = 3;
+// Fury: +1 attack damage for every this much missing HP
+pub const FURY_HP_STEP: i32 = 10;
+
+// HP it would cost to play a card right now
+pub fn blood_price(cost: i32, energy: &Energy) -> i32 {
+    (cost - energy.current).max(0) * BLOOD_PRICE_PER_ENERGY
+}
+
+pub fn fury_bonus(health: &Health) -> i32 {
+    (health.max - health.current).max(0) / FURY_HP_STEP
+}
+
+pub struct Berserker;
+
+impl PlayerClass for Berserker {
+    fn definition(&self) -> ClassDefinition {
+        ClassDefinition {
+            name: "The Berserker",
+            description: "Pays in blood when out of energy.\nHits harder the more wounded.",
+            max_hp: 70,
+            gold: 150,
+            starting_relics: vec![Relic::RedSkull],
+            art: "images/backgrounds/DragonLayer.jpg",
+            color: Color::srgb(0.4, 0.05, 0.05),
+        }
+    }
+
+    fn starter_deck(&self) -> Vec<Card> {
+        deck_of(&[
+            (4, strike),
+            (3, defend),
+            (2, reckless_swing),
+            (1, iron_hide),
+        ])
+    }
+
+    fn card_pool(&self) -> Vec<Card> {
+        vec![
+            strike(),
+            defend(),
+            reckless_swing(),
+            iron_hide(),
+            war_cry(),
+            frenzy(),
+            savage_blow(),
+            crimson_guard(),
+            last_stand(),
+        ]
+    }
+
+    fn random_card(&self) -> Card {
+        let mut rng = thread_rng();
+        let roll = rng.gen_range(0..100);
+        if roll < 60 {
+            // Common (60%)
+            let r = rng.gen_range(0..3);
+            match r {
+                0 => reckless_swing(),
+                1 => iron_hide(),
+                _ => war_cry(),
+            }
+        } else if roll < 90 {
+            // Rare (30%)
+            let r = rng.gen_range(0..3);
+            match r {
+                0 => frenzy(),
+                1 => savage_blow(),
+                _ => crimson_guard(),
+            }
+        } else {
+            // Legendary (10%)
+            last_stand()
+        }
+    }
+
+    fn pay_card_cost(
+        &self,
+        cost: i32,
+        energy: &mut Energy,
+        _mana: Option<&mut Mana>,
+        health: &mut Health,
+    ) -> Result<(), &'static str> {
+        let blood = blood_price(cost, energy);
+        // Paying can never be what kills you
+        if blood >= health.current {
+            return Err("Not enough HP to pay in blood!");
+        }
+        energy.current = (energy.current - cost).max(0);
+        health.current -= blood;
+        if blood > 0 {
+            println!("Paid {} HP in blood", blood);
+        }
+        Ok(())
+    }
+
+    fn bonus_damage(&self, health: &Health) -> i32 {
+        fury_bonus(health)
+    }
+
+    fn resource_text(
+        &self,
+        energy: &Energy,
+        _mana: Option<&Mana>,
+        health: &Health,
+    ) -> (String, Color) {
+        (
+            format!(
+                "Energy: {}/{} (then {} HP each)  Fury: +{}",
+                energy.current,
+                energy.max,
+                BLOOD_PRICE_PER_ENERGY,
+                fury_bonus(health)
+            ),
+            Color::srgb(1.0, 0.3, 0.3),
+        )
+    }
+}
@@ -0,0 +1,211 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use rand::Rng;
+use rand::thread_rng;
+
+use super::{ClassDefinition, PlayerClass, deck_of};
+use crate::components::*;
+use crate::item_cards::*;
+use crate::item_relics::Relic;
+use crate::states::GameState;
+
+// Pips always drawn on the combo meter; more appear if combo goes past this
+pub const COMBO_PIPS: u32 = 5;
+// Combo points the Metronome takes away at the end of each turn
+pub const COMBO_DECAY_PER_TURN: u32 = 2;
+
+// Combo only decays with the Metronome. Dueling Gloves keep 1 point of existing Combo from decaying.
+pub fn combo_after_turn(combo: u32, relics: &[Relic]) -> u32 {
+    let decayed = if relics.contains(&Relic::Metronome) {
+        combo.saturating_sub(COMBO_DECAY_PER_TURN)
+    } else {
+        combo
+    };
+    if relics.contains(&Relic::DuelingGloves) {
+        decayed.max(combo.min(1))
+    } else {
+        decayed
+    }
+}
+
+pub fn combo_gained(granted: u32, relics: &[Relic]) -> u32 {
+    if granted > 0 && relics.contains(&Relic::Metronome) {
+        granted + 1
+    } else {
+        granted
+    }
+}
+
+// Points a finisher spends, or None if there aren't enough to trigger it
+pub fn spend_combo(card: &Card, combo: &mut u32) -> Option<u32> {
+    if card.finisher == Finisher::None || *combo < card.finisher_combo_cost {
+        return None;
+    }
+    let spent = match card.finisher {
+        Finisher::DoubleDamage => card.finisher_combo_cost,
+        _ => *combo,
+    };
+    *combo -= spent;
+    Some(spent)
+}
+
+pub struct Duelist;
+
+impl PlayerClass for Duelist {
+    fn definition(&self) -> ClassDefinition {
+        ClassDefinition {
+            name: "The Duelist",
+            description: "A balanced warrior.",
+            max_hp: 50,
+            gold: 200,
+            starting_relics: vec![Relic::DuelingGloves],
+            art: "images/backgrounds/Battlefield.jpg",
+            color: Color::srgb(0.3, 0.1, 0.1),
+        }
+    }
+
+    fn starter_deck(&self) -> Vec<Card> {
+        deck_of(&[(5, strike), (2, bash), (3, defend)])
+    }
+
+    fn card_pool(&self) -> Vec<Card> {
+        vec![
+            strike(),
+            defend(),
+            bash(),
+            iron_wave(),
+            deadly_poison(),
+            heavy_blade(),
+            shrug_it_off(),
+            clothesline(),
+            quick_slash(),
+            bludgeon(),
+            poison_cloud(),
+            fortify(),
+            uppercut(),
+            flashbang(),
+            riposte(),
+            flurry(),
+            read_the_opening(),
+            second_wind(),
+            disarming_blow(),
+        ]
+    }
+
+    fn random_card(&self) -> Card {
+        let mut rng = thread_rng();
+        let roll = rng.gen_range(0..100);
+        if roll < 60 {
+            // Common (60%)
+            let r = rng.gen_range(0..5);
+            match r {
+                0 => iron_wave(),
+                1 => shrug_it_off(),
+                2 => riposte(),
+                3 => read_the_opening(),
+                _ => quick_slash(),
+            }
+        } else if roll < 90 {
+            // Rare (30%)
+            let r = rng.gen_range(0..9);
+            match r {
+                0 => deadly_poison(),
+                1 => clothesline(),
+                2 => poison_cloud(),
+                3 => fortify(),
+                4 => uppercut(),
+                5 => flurry(),
+                6 => second_wind(),
+                7 => disarming_blow(),
+                _ => flashbang(),
+            }
+        } else {
+            // Legendary (10%)
+            let r = rng.gen_range(0..2);
+            match r {
+                0 => heavy_blade(),
+                _ => bludgeon(),
+            }
+        }
+    }
+
+    fn insert_components(&self, player: &mut EntityCommands) {
+        player.insert(PlayerCombo { current: 0 });
+    }
+
+    fn spawn_hud(&self, hud: &mut ChildBuilder) {
+        hud.spawn((
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Row,
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(4.0),
+                    min_height: Val::Px(24.0),
+                    ..default()
+                },
+                ..default()
+            },
+            ComboMeter,
+        ));
+    }
+
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            update_combo_ui_system.run_if(in_state(GameState::Battle)),
+        )
+        .add_systems(OnExit(GameState::Battle), reset_combo_system);
+    }
+}
+
+// Rebuilds the pip meter whenever combo changes or the meter is first spawned
+pub fn update_combo_ui_system(
+    mut commands: Commands,
+    player_query: Query<Ref<PlayerCombo>, With<Player>>,
+    meter_query: Query<(Entity, Ref<ComboMeter>)>,
+) {
+    let Ok(combo) = player_query.get_single() else {
+        return;
+    };
+    for (meter, marker) in &meter_query {
+        if !combo.is_changed() && !marker.is_added() {
+            continue;
+        }
+        commands.entity(meter).despawn_descendants();
+        commands.entity(meter).with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Combo",
+                TextStyle {
+                    font: Handle::default(),
+                    font_size: 20.0,
+                    color: Color::srgb(1.0, 0.5, 0.0),
+                },
+            ));
+            for pip in 0..COMBO_PIPS.max(combo.current) {
+                let lit = pip < combo.current;
+                parent.spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(14.0),
+                        height: Val::Px(14.0),
+                        border: UiRect::all(Val::Px(1.0)),
+                        ..default()
+                    },
+                    background_color: if lit {
+                        Color::srgb(1.0, 0.5, 0.0).into()
+                    } else {
+                        Color::srgba(0.0, 0.0, 0.0, 0.0).into()
+                    },
+                    border_color: Color::srgb(1.0, 0.5, 0.0).into(),
+                    ..default()
+                });
+            }
+        });
+    }
+}
+
+// Combo never carries over from one fight to the next
+pub fn reset_combo_system(mut player_query: Query<&mut PlayerCombo, With<Player>>) {
+    for mut combo in &mut player_query {
+        combo.current = 0;
+    }
+}
@@ -0,0 +1,115 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+
+use crate::components::*;
+use crate::item_relics::Relic;
+
+pub mod berserker;
+pub mod duelist;
+pub mod spellweaver;
+
+pub const PLAYABLE_CLASSES: [CharacterClass; 3] = [
+    CharacterClass::Duelist,
+    CharacterClass::Spellweaver,
+    CharacterClass::Berserker,
+];
+
+pub struct ClassDefinition {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub max_hp: i32,
+    pub gold: i32,
+    pub starting_relics: Vec<Relic>,
+    pub art: &'static str,
+    pub color: Color,
+}
+
+// Everything that makes a class different lives behind this trait. The defaults
+// describe a plain energy class: refill to max each turn, pay costs in energy.
+pub trait PlayerClass: Send + Sync {
+    fn definition(&self) -> ClassDefinition;
+
+    fn starter_deck(&self) -> Vec<Card>;
+
+    // Cards that belong to the class (transforms, class lookups)
+    fn card_pool(&self) -> Vec<Card>;
+
+    // A card reward or shop card, rolled by rarity
+    fn random_card(&self) -> Card;
+
+    // Resource components carried on top of Energy
+    fn insert_components(&self, _player: &mut EntityCommands) {}
+
+    fn refresh_resources(&self, energy: &mut Energy, _mana: Option<&mut Mana>) {
+        energy.current = energy.max;
+    }
+
+    // Takes a card's cost from the class resource, or explains why it can't be paid
+    fn pay_card_cost(
+        &self,
+        cost: i32,
+        energy: &mut Energy,
+        _mana: Option<&mut Mana>,
+        _health: &mut Health,
+    ) -> Result<(), &'static str> {
+        if energy.current < cost {
+            return Err("Not enough energy!");
+        }
+        energy.current -= cost;
+        Ok(())
+    }
+
+    // Extra attack damage the class gets on top of the card
+    fn bonus_damage(&self, _health: &Health) -> i32 {
+        0
+    }
+
+    fn resource_text(
+        &self,
+        energy: &Energy,
+        _mana: Option<&Mana>,
+        _health: &Health,
+    ) -> (String, Color) {
+        (
+            format!("Energy: {}/{}", energy.current, energy.max),
+            Color::srgb(0.2, 0.8, 1.0),
+        )
+    }
+
+    // Extra widgets in the battle HUD, below the energy line
+    fn spawn_hud(&self, _hud: &mut ChildBuilder) {}
+
+    // Systems the class needs, e.g. to keep its HUD widgets up to date
+    fn build(&self, _app: &mut App) {}
+}
+
+pub fn player_class(class: CharacterClass) -> &'static dyn PlayerClass {
+    match class {
+        CharacterClass::Duelist => &duelist::Duelist,
+        CharacterClass::Spellweaver => &spellweaver::Spellweaver,
+        CharacterClass::Berserker => &berserker::Berserker,
+    }
+}
+
+// Builds a deck from (count, card) pairs
+fn deck_of(cards: &[(usize, fn() -> Card)]) -> Vec<Card> {
+    cards
+        .iter()
+        .flat_map(|(count, card)| (0..*count).map(|_| card()))
+        .collect()
+}
+
+// Damage a card gains from missing HP, independent of class
+pub fn missing_hp_damage(card: &Card, health: &Health) -> i32 {
+    (health.max - health.current).max(0) * card.missing_hp_scaling / 100
+}
+
+pub struct ClassesPlugin;
+
+impl Plugin for ClassesPlugin {
+    fn build(&self, app: &mut App) {
+        for class in PLAYABLE_CLASSES {
+            player_class(class).build(app);
+        }
+    }
+}
@@ -0,0 +1,437 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use rand::Rng;
+use rand::thread_rng;
+
+use super::{ClassDefinition, PlayerClass, deck_of};
+use crate::combat_math::{
+    ESSENCE_CAP, apply_spell_elements, card_base_block, card_base_damage, chain_damage,
+    chains_to_next_enemy, cleanse_debuffs, hits_all_enemies, player_attack_damage,
+    player_block_gain, shadow_lifesteal, spell_elements, vulnerable_damage,
+};
+use crate::components::*;
+use crate::enemies::element_affinity;
+use crate::item_cards::*;
+use crate::item_relics::Relic;
+use crate::resources::Spellbook;
+use crate::spell_recipes::{element_name, opposite_element, recipes_one_away};
+use crate::states::GameState;
+
+// Mana gained each turn. Mana is never capped.
+pub const SPELLWEAVER_MANA_PER_TURN: i32 = 2;
+
+// Arcane Focus: adds one random basic essence to a spell for free
+pub fn add_free_essence(spell: &mut ActiveSpell, rng: &mut impl Rng) {
+    let essences = [
+        fire_essence(),
+        ice_essence(),
+        wind_essence(),
+        stone_essence(),
+    ];
+    let essence = &essences[rng.gen_range(0..essences.len())];
+    spell.bonus_damage += essence.damage;
+    spell.bonus_block += essence.block;
+    spell.element = essence.element;
+    spell.essences.push(EssenceInfo {
+        element: essence.element,
+        damage: essence.damage,
+        block: essence.block,
+    });
+    println!("Arcane Focus: free {}!", essence.name);
+}
+
+pub struct Spellweaver;
+
+impl PlayerClass for Spellweaver {
+    fn definition(&self) -> ClassDefinition {
+        ClassDefinition {
+            name: "The Spellweaver",
+            description: "Master of the arcane.",
+            max_hp: 50,
+            gold: 200,
+            starting_relics: vec![Relic::ArcaneFocus],
+            art: "images/backgrounds/RuinedForest.jpg",
+            color: Color::srgb(0.1, 0.1, 0.4),
+        }
+    }
+
+    fn starter_deck(&self) -> Vec<Card> {
+        deck_of(&[
+            (3, fire_essence),
+            (2, wind_essence),
+            (3, magic_bolt),
+            (2, magic_shield),
+        ])
+    }
+
+    fn card_pool(&self) -> Vec<Card> {
+        vec![
+            magic_bolt(),
+            magic_shield(),
+            fire_essence(),
+            ice_essence(),
+            wind_essence(),
+            stone_essence(),
+            lightning_essence(),
+            shadow_essence(),
+            water_essence(),
+        ]
+    }
+
+    fn random_card(&self) -> Card {
+        // Simple distribution for Spellweaver for now
+        let r = thread_rng().gen_range(0..9);
+        match r {
+            0 => magic_bolt(),
+            1 => magic_shield(),
+            2 => fire_essence(),
+            3 => ice_essence(),
+            4 => stone_essence(),
+            5 => lightning_essence(),
+            6 => shadow_essence(),
+            7 => water_essence(),
+            _ => wind_essence(),
+        }
+    }
+
+    fn insert_components(&self, player: &mut EntityCommands) {
+        player.insert((Mana { current: 0 }, ActiveSpell::default()));
+    }
+
+    fn refresh_resources(&self, _energy: &mut Energy, mana: Option<&mut Mana>) {
+        if let Some(mana) = mana {
+            mana.current += SPELLWEAVER_MANA_PER_TURN;
+        }
+    }
+
+    fn pay_card_cost(
+        &self,
+        cost: i32,
+        _energy: &mut Energy,
+        mana: Option<&mut Mana>,
+        _health: &mut Health,
+    ) -> Result<(), &'static str> {
+        let Some(mana) = mana else {
+            return Err("No mana pool!");
+        };
+        if mana.current < cost {
+            return Err("Not enough mana!");
+        }
+        mana.current -= cost;
+        Ok(())
+    }
+
+    fn resource_text(
+        &self,
+        _energy: &Energy,
+        mana: Option<&Mana>,
+        _health: &Health,
+    ) -> (String, Color) {
+        (
+            format!("Mana: {}", mana.map_or(0, |mana| mana.current)),
+            Color::srgb(0.8, 0.4, 1.0),
+        )
+    }
+
+    fn spawn_hud(&self, hud: &mut ChildBuilder) {
+        hud.spawn((
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Row,
+                    align_items: AlignItems::Center,
+                    min_height: Val::Px(30.0),
+                    margin: UiRect::top(Val::Px(5.0)),
+                    ..default()
+                },
+                ..default()
+            },
+            PlayerSpellContainer,
+        ));
+        hud.spawn((
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: Handle::default(),
+                    font_size: 16.0,
+                    color: Color::srgb(0.8, 0.6, 1.0),
+                },
+            )
+            .with_style(Style {
+                max_width: Val::Px(320.0),
+                ..default()
+            }),
+            SpellPreviewText,
+        ));
+    }
+
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (update_spell_ui, update_spell_preview_system).run_if(in_state(GameState::Battle)),
+        );
+    }
+}
+
+pub struct PreviewTarget {
+    pub name: String,
+    pub kind: EnemyKind,
+    pub status: StatusStore,
+    pub selected: bool,
+}
+
+// What casting this card right now would do, worked out with the same functions
+// the real cast uses. Damage is shown before the reflex multiplier.
+pub fn spell_preview(
+    card: &Card,
+    spell: &ActiveSpell,
+    relics: &[Relic],
+    status: &StatusStore,
+    health: &Health,
+    targets: &[PreviewTarget],
+    book: &Spellbook,
+) -> String {
+    let elements = spell_elements(spell);
+    // Water cleanses before the damage is worked out, so Weak no longer applies
+    let mut status = status.clone();
+    let cleansed = elements.contains(&SpellElement::Water) && cleanse_debuffs(&mut status);
+    let status = &status;
+    let damage = player_attack_damage(
+        card_base_damage(card, health, Some(spell)),
+        CharacterClass::Spellweaver,
+        relics,
+        status,
+        health,
+    );
+    let block = card_base_block(card, Some(spell));
+    let block = if block > 0 {
+        player_block_gain(block, relics, status)
+    } else {
+        0
+    };
+
+    let all_enemies = hits_all_enemies(&elements);
+    let mut lines = vec![format!(
+        "{}: {} dmg{}{}",
+        card.name,
+        damage,
+        if all_enemies { " to ALL" } else { "" },
+        if block > 0 {
+            format!(", {} block", block)
+        } else {
+            String::new()
+        }
+    )];
+
+    lines.push(format!(
+        "Essences: {}/{}",
+        spell.essences.len(),
+        ESSENCE_CAP
+    ));
+    if cleansed {
+        lines.push("Cleanses your debuffs".to_string());
+    }
+
+    if damage > 0 {
+        let mut hit: Vec<(&PreviewTarget, i32, &str)> = targets
+            .iter()
+            .filter(|t| all_enemies || t.selected)
+            .map(|t| (t, damage, ""))
+            .collect();
+        if chains_to_next_enemy(&elements)
+            && let Some(next) = targets.iter().find(|t| !t.selected)
+        {
+            hit.push((next, chain_damage(damage), " (chain)"));
+        }
+
+        let mut lifesteal = 0;
+        for (target, damage, label) in hit {
+            let mut after = target.status.clone();
+            let outcome = apply_spell_elements(
+                damage,
+                &elements,
+                &element_affinity(target.kind),
+                &mut after,
+            );
+            let hit_damage = vulnerable_damage(outcome.damage, &after);
+            lifesteal += shadow_lifesteal(&elements, hit_damage);
+            let mut notes: Vec<String> = outcome.reactions.iter().map(|r| r.to_string()).collect();
+            if hit_damage > outcome.damage {
+                notes.push("Vulnerable".to_string());
+            }
+            for (name, before, now) in [
+                ("Burning", target.status.burning, after.burning),
+                ("Frozen", target.status.frozen, after.frozen),
+                ("Stun", target.status.stun, after.stun),
+                ("Weak", target.status.weak, after.weak),
+            ] {
+                if now > before {
+                    notes.push(format!("+{} {}", now - before, name));
+                }
+            }
+            lines.push(format!(
+                "  {}{}: {}{}",
+                target.name,
+                label,
+                hit_damage,
+                if notes.is_empty() {
+                    String::new()
+                } else {
+                    format!(" ({})", notes.join(", "))
+                }
+            ));
+        }
+        if lifesteal > 0 {
+            lines.push(format!("Heal up to {} HP", lifesteal));
+        }
+    }
+
+    // Essences that would cancel something already stacked can't complete a recipe
+    let next: Vec<String> = recipes_one_away(book, &spell.essence_history)
+        .into_iter()
+        .filter(|(element, _)| {
+            opposite_element(book, *element)
+                .is_none_or(|opposite| !spell.essences.iter().any(|e| e.element == opposite))
+        })
+        .map(|(element, recipe)| {
+            let name = if book.discovered.contains(&recipe.id) {
+                recipe.name.as_str()
+            } else {
+                "???"
+            };
+            format!("{} -> {}", element_name(element), name)
+        })
+        .collect();
+    if !next.is_empty() {
+        lines.push(format!("Next: {}", next.join(", ")));
+    }
+    lines.join("\n")
+}
+
+// Previews the hovered spell card, or the first one in hand
+pub fn update_spell_preview_system(
+    player_query: Query<(&ActiveSpell, &StatusStore, &RelicStore, &Health), With<Player>>,
+    hand_query: Query<(&Card, &Interaction)>,
+    enemy_query: Query<(&Enemy, &StatusStore, Has<Selected>), Without<Player>>,
+    spellbook: Res<Spellbook>,
+    mut text_query: Query<&mut Text, With<SpellPreviewText>>,
+) {
+    let Ok((spell, status, relics, health)) = player_query.get_single() else {
+        return;
+    };
+    let spell_cards = || hand_query.iter().filter(|(card, _)| card.is_spell_source);
+    let card = spell_cards()
+        .find(|(_, interaction)| **interaction == Interaction::Hovered)
+        .or_else(|| spell_cards().next())
+        .map(|(card, _)| card);
+
+    let preview = match card {
+        Some(card) => {
+            let targets: Vec<PreviewTarget> = enemy_query
+                .iter()
+                .map(|(enemy, status, selected)| PreviewTarget {
+                    name: format!("{:?}", enemy.kind),
+                    kind: enemy.kind,
+                    status: status.clone(),
+                    selected,
+                })
+                .collect();
+            spell_preview(
+                card,
+                spell,
+                &relics.relics,
+                status,
+                health,
+                &targets,
+                &spellbook,
+            )
+        }
+        None => String::new(),
+    };
+
+    for mut text in &mut text_query {
+        if text.sections[0].value != preview {
+            text.sections[0].value = preview.clone();
+        }
+    }
+}
+
+pub fn update_spell_ui(
+    mut commands: Commands,
+    player_query: Query<&ActiveSpell, (With<Player>, Changed<ActiveSpell>)>,
+    container_query: Query<Entity, With<PlayerSpellContainer>>,
+) {
+    if let Ok(active_spell) = player_query.get_single() {
+        if let Ok(container) = container_query.get_single() {
+            commands.entity(container).despawn_descendants();
+
+            commands.entity(container).with_children(|parent| {
+                for essence in &active_spell.essences {
+                    let (color, text_code) = match essence.element {
+                        SpellElement::Fire => (Color::srgb(0.8, 0.2, 0.2), "F"),
+                        SpellElement::Ice => (Color::srgb(0.2, 0.8, 1.0), "I"),
+                        SpellElement::Wind => (Color::srgb(0.8, 0.8, 0.8), "W"),
+                        SpellElement::Stone => (Color::srgb(0.5, 0.3, 0.1), "S"),
+                        SpellElement::Lightning => (Color::srgb(1.0, 0.9, 0.2), "L"),
+                        SpellElement::Shadow => (Color::srgb(0.4, 0.1, 0.5), "D"),
+                        SpellElement::Water => (Color::srgb(0.1, 0.3, 0.9), "A"),
+                        SpellElement::Neutral => (Color::srgb(0.5, 0.5, 0.5), "N"),
+                    };
+
+                    let mut tooltip_text = String::new();
+                    match essence.element {
+                        SpellElement::Fire => tooltip_text.push_str("Fire Essence\n"),
+                        SpellElement::Ice => tooltip_text.push_str("Ice Essence\n"),
+                        SpellElement::Wind => tooltip_text.push_str("Wind Essence\n"),
+                        SpellElement::Stone => tooltip_text.push_str("Stone Essence\n"),
+                        SpellElement::Lightning => {
+                            tooltip_text.push_str("Lightning Essence\nChains to another enemy\n")
+                        }
+                        SpellElement::Shadow => {
+                            tooltip_text.push_str("Shadow Essence\nApplies Weak, heals on hit\n")
+                        }
+                        SpellElement::Water => {
+                            tooltip_text.push_str("Water Essence\nCleanses your debuffs\n")
+                        }
+                        SpellElement::Neutral => tooltip_text.push_str("Essence\n"),
+                    }
+                    if essence.damage > 0 {
+                        tooltip_text.push_str(&format!("+{} Damage\n", essence.damage));
+                    }
+                    if essence.block > 0 {
+                        tooltip_text.push_str(&format!("+{} Block\n", essence.block));
+                    }
+
+                    parent
+                        .spawn((
+                            NodeBundle {
+                                style: Style {
+                                    width: Val::Px(24.0),
+                                    height: Val::Px(24.0),
+                                    margin: UiRect::right(Val::Px(4.0)),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    border: UiRect::all(Val::Px(1.0)),
+                                    ..default()
+                                },
+                                background_color: color.into(),
+                                border_color: Color::WHITE.into(),
+                                ..default()
+                            },
+                            Interaction::default(),
+                            Tooltip { text: tooltip_text },
+                        ))
+                        .with_children(|p| {
+                            p.spawn(TextBundle::from_section(
+                                text_code,
+                                TextStyle {
+                                    font: Handle::default(),
+                                    font_size: 16.0,
+                                    color: Color::WHITE,
+                                },
+                            ));
+                        });
+                }
+            });
+        }
+    }
+}
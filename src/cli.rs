@@ -1,6 +1,8 @@
+use crate::components::ReflexDirection;
 use bevy::prelude::*;
 use std::sync::mpsc::{Receiver, channel};
 use std::thread;
+use std::time::Instant;
 
 // --- CLi Events ---
 #[derive(Event, Debug, Clone)]
@@ -12,8 +14,21 @@ pub struct PlayCardRequest {
 #[derive(Event, Debug, Clone)]
 pub struct EndTurnRequest;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReflexInput {
+    Press,
+    Release,
+    Direction(ReflexDirection),
+}
+
 #[derive(Event, Debug, Clone)]
-pub struct TriggerReflexRequest;
+pub struct TriggerReflexRequest {
+    pub input: ReflexInput,
+    // When the input arrived, so the result doesn't depend on frame timing
+    pub pressed_at: Instant,
+    // Window position of the cursor, for aimed reflexes
+    pub cursor: Option<Vec2>,
+}
 
 #[derive(Event, Debug, Clone)]
 pub struct MapNodeSelectRequest {
@@ -82,6 +97,8 @@ pub fn cli_poll_system(
                     println!("play <hand_index> [target_index] : Play a card");
                     println!("end                              : End your turn");
                     println!("reflex                           : Trigger a reflex (spacebar)");
+                    println!("reflex <up|down|left|right>      : Answer a direction prompt");
+                    println!("reflex release                   : Release a charge meter");
                     println!("map <node_index>                 : Select a map node");
                 }
                 "play" => {
@@ -112,8 +129,20 @@ pub fn cli_poll_system(
                     println!(">> Action: End Turn");
                 }
                 "reflex" => {
-                    ev_reflex.send(TriggerReflexRequest);
-                    println!(">> Action: Trigger Reflex");
+                    let input = match parts.get(1).map(|arg| arg.to_lowercase()).as_deref() {
+                        Some("up") => ReflexInput::Direction(ReflexDirection::Up),
+                        Some("down") => ReflexInput::Direction(ReflexDirection::Down),
+                        Some("left") => ReflexInput::Direction(ReflexDirection::Left),
+                        Some("right") => ReflexInput::Direction(ReflexDirection::Right),
+                        Some("release") => ReflexInput::Release,
+                        _ => ReflexInput::Press,
+                    };
+                    ev_reflex.send(TriggerReflexRequest {
+                        input,
+                        pressed_at: Instant::now(),
+                        cursor: None,
+                    });
+                    println!(">> Action: Trigger Reflex ({:?})", input);
                 }
                 "map" => {
                     if parts.len() >= 2 {
@@ -0,0 +1,220 @@
+use crate::classes::{missing_hp_damage, player_class};
+use crate::components::*;
+use crate::enemies::ElementAffinity;
+use crate::item_relics::Relic;
+
+// Every element an essence can carry, in the order spells list them
+pub const ESSENCE_ELEMENTS: [SpellElement; 7] = [
+    SpellElement::Fire,
+    SpellElement::Ice,
+    SpellElement::Wind,
+    SpellElement::Stone,
+    SpellElement::Lightning,
+    SpellElement::Shadow,
+    SpellElement::Water,
+];
+
+// Stacking an essence past this many overloads the spell
+pub const ESSENCE_CAP: usize = 4;
+// HP lost when a spell overloads. Never kills; it leaves at least 1 HP.
+pub const OVERLOAD_BACKLASH: i32 = 6;
+// Share of a Lightning hit that arcs to one more enemy
+pub const LIGHTNING_CHAIN_RATIO: f32 = 0.5;
+// Share of Shadow damage to HP that heals the player
+pub const SHADOW_LIFESTEAL_RATIO: f32 = 0.25;
+pub const WEAKNESS_MULTIPLIER: f32 = 1.5;
+pub const VULNERABLE_MULTIPLIER: f32 = 1.5;
+pub const RESISTANCE_MULTIPLIER: f32 = 0.5;
+
+// Card damage before relics and statuses: missing-HP scaling plus any stacked spell
+pub fn card_base_damage(card: &Card, health: &Health, spell: Option<&ActiveSpell>) -> i32 {
+    let mut damage = card.damage;
+    if damage > 0 {
+        damage += missing_hp_damage(card, health);
+    }
+    if card.is_spell_source
+        && let Some(spell) = spell
+    {
+        damage += spell.bonus_damage;
+    }
+    damage
+}
+
+pub fn card_base_block(card: &Card, spell: Option<&ActiveSpell>) -> i32 {
+    match spell {
+        Some(spell) if card.is_spell_source => card.block + spell.bonus_block,
+        _ => card.block,
+    }
+}
+
+// Attack damage after the player's relics, class bonus, Strength and Weak
+pub fn player_attack_damage(
+    base: i32,
+    class: CharacterClass,
+    relics: &[Relic],
+    status: &StatusStore,
+    health: &Health,
+) -> i32 {
+    let mut damage = base;
+    if relics.contains(&Relic::Vajra) && damage > 0 {
+        damage += 1;
+    }
+    if damage > 0 {
+        damage += player_class(class).bonus_damage(health);
+        if relics.contains(&Relic::RedSkull) && health.current * 2 <= health.max {
+            damage += 3;
+        }
+    }
+    if status.strength > 0 && damage > 0 {
+        damage += status.strength;
+    }
+    if status.weak > 0 {
+        damage = (damage as f32 * 0.75) as i32;
+    }
+    damage
+}
+
+// A Vulnerable enemy takes half again as much from each hit
+pub fn vulnerable_damage(damage: i32, target: &StatusStore) -> i32 {
+    if damage > 0 && target.vulnerable > 0 {
+        (damage as f32 * VULNERABLE_MULTIPLIER) as i32
+    } else {
+        damage
+    }
+}
+
+// Block gained after Oddly Smooth Stone and Frail
+pub fn player_block_gain(base: i32, relics: &[Relic], status: &StatusStore) -> i32 {
+    let mut block = base;
+    if relics.contains(&Relic::OddlySmoothStone) {
+        block += 1;
+    }
+    if status.frail > 0 {
+        block = (block as f32 * 0.75) as i32;
+    }
+    block
+}
+
+// The distinct elements a stacked spell carries into its cast
+pub fn spell_elements(spell: &ActiveSpell) -> Vec<SpellElement> {
+    ESSENCE_ELEMENTS
+        .into_iter()
+        .filter(|element| spell.essences.iter().any(|e| e.element == *element))
+        .collect()
+}
+
+pub fn would_overload(spell: &ActiveSpell) -> bool {
+    spell.essences.len() >= ESSENCE_CAP
+}
+
+// Wind spells sweep every enemy instead of the selected one
+pub fn hits_all_enemies(elements: &[SpellElement]) -> bool {
+    elements.contains(&SpellElement::Wind)
+}
+
+// Lightning arcs from a single target to the next enemy in line
+pub fn chains_to_next_enemy(elements: &[SpellElement]) -> bool {
+    elements.contains(&SpellElement::Lightning) && !hits_all_enemies(elements)
+}
+
+pub fn chain_damage(damage: i32) -> i32 {
+    (damage as f32 * LIGHTNING_CHAIN_RATIO) as i32
+}
+
+pub fn shadow_lifesteal(elements: &[SpellElement], hp_damage: i32) -> i32 {
+    if elements.contains(&SpellElement::Shadow) {
+        (hp_damage as f32 * SHADOW_LIFESTEAL_RATIO) as i32
+    } else {
+        0
+    }
+}
+
+// Water washes the player's debuffs away before the spell resolves
+pub fn cleanse_debuffs(status: &mut StatusStore) -> bool {
+    let had_debuffs = status.poison > 0
+        || status.weak > 0
+        || status.vulnerable > 0
+        || status.frail > 0
+        || status.burning > 0
+        || status.frozen > 0;
+    status.poison = 0;
+    status.weak = 0;
+    status.vulnerable = 0;
+    status.frail = 0;
+    status.burning = 0;
+    status.frozen = 0;
+    had_debuffs
+}
+
+// The player's Vulnerable only matters on enemy turns, so it ticks once they are over.
+// Stacks applied during the turn last through the next one.
+pub fn tick_player_vulnerable(status: &mut StatusStore) {
+    if status.vulnerable_fresh {
+        status.vulnerable_fresh = false;
+    } else if status.vulnerable > 0 {
+        status.vulnerable -= 1;
+    }
+}
+
+pub struct HitOutcome {
+    pub damage: i32,
+    pub reactions: Vec<&'static str>,
+}
+
+// Elemental reactions on one target: weaknesses and resistances, clearing opposing
+// statuses, shattering frozen targets and threshold statuses. Returns the damage
+// each hit deals.
+pub fn apply_spell_elements(
+    damage: i32,
+    elements: &[SpellElement],
+    affinity: &ElementAffinity,
+    target: &mut StatusStore,
+) -> HitOutcome {
+    let has = |element| elements.contains(&element);
+    let mut outcome = HitOutcome {
+        damage,
+        reactions: Vec::new(),
+    };
+
+    // A spell that both exploits a weakness and hits a resistance cancels out
+    let weak = elements.iter().any(|e| affinity.weak.contains(e));
+    let resisted = elements.iter().any(|e| affinity.resist.contains(e));
+    if weak && !resisted {
+        outcome.damage = (outcome.damage as f32 * WEAKNESS_MULTIPLIER) as i32;
+        outcome.reactions.push("Super effective");
+    } else if resisted && !weak {
+        outcome.damage = (outcome.damage as f32 * RESISTANCE_MULTIPLIER) as i32;
+        outcome.reactions.push("Resisted");
+    }
+    if has(SpellElement::Shadow) && outcome.damage > 0 {
+        target.weak += 1;
+    }
+
+    if has(SpellElement::Ice) && target.burning > 0 {
+        target.burning = 0;
+        outcome.reactions.push("Extinguished");
+    }
+    if has(SpellElement::Fire) && target.frozen > 0 {
+        target.frozen = 0;
+        outcome.reactions.push("Melted");
+    }
+    if has(SpellElement::Stone) && target.frozen > 0 {
+        target.frozen = 0;
+        outcome.damage *= 2;
+        outcome.reactions.push("Shattered");
+    }
+
+    // Threshold Status checks
+    if outcome.damage > 10 {
+        if has(SpellElement::Fire) {
+            target.burning += 3;
+        }
+        if has(SpellElement::Ice) {
+            target.frozen += 2;
+        }
+        if has(SpellElement::Stone) {
+            target.stun += 1;
+        }
+    }
+    outcome
+}
@@ -1,10 +1,11 @@
 use bevy::prelude::*;
-use rand::Rng;
 use rand::seq::SliceRandom;
 use rand::thread_rng;
 
+use crate::classes;
 use crate::components::*;
 use crate::item_cards;
+use crate::map_generator::generate_map;
 use crate::resources::*;
 
 #[derive(Component)]
@@ -18,72 +19,44 @@ pub fn setup_camera(mut commands: Commands) {
 pub fn setup_game(
     mut commands: Commands,
     player_query: Query<Entity, With<Player>>,
-    run_state: Res<RunState>,
+    mut run_state: ResMut<RunState>,
+    mut spellbook: ResMut<Spellbook>,
 ) {
+    run_state.act = 1;
+    spellbook.discovered.clear();
+
     // Despawn existing player if any (for restart)
     for entity in &player_query {
         commands.entity(entity).despawn_recursive();
     }
 
-    let (starting_relics, starting_deck) = match run_state.character_class {
-        crate::components::CharacterClass::Duelist => {
-            let mut deck = Vec::new();
-            for _ in 0..5 {
-                deck.push(item_cards::strike());
-            }
-            for _ in 0..2 {
-                deck.push(item_cards::bash());
-            }
-            for _ in 0..3 {
-                deck.push(item_cards::defend());
-            }
-            (Vec::new(), deck)
-        }
-        crate::components::CharacterClass::Spellweaver => {
-            let mut deck = Vec::new();
-            for _ in 0..3 {
-                deck.push(item_cards::fire_essence());
-            }
-            for _ in 0..2 {
-                deck.push(item_cards::wind_essence());
-            }
-            for _ in 0..3 {
-                deck.push(item_cards::magic_bolt());
-            }
-            for _ in 0..2 {
-                deck.push(item_cards::magic_shield());
-            }
-            (Vec::new(), deck)
-        }
-    };
+    let class = classes::player_class(run_state.character_class);
+    let definition = class.definition();
 
     // Spawn Player (Persistent)
     let mut player_cmds = commands.spawn((
         Player,
         Health {
-            current: 50,
-            max: 50,
+            current: definition.max_hp,
+            max: definition.max_hp,
         },
         Energy { current: 3, max: 3 },
         Block { value: 0 },
         StatusStore::default(),
         RelicStore {
-            relics: starting_relics,
+            relics: definition.starting_relics,
         },
         PotionStore {
             potions: Vec::new(),
         },
-        Gold { amount: 200 },
+        Gold {
+            amount: definition.gold,
+        },
     ));
-
-    if run_state.character_class == CharacterClass::Spellweaver {
-        player_cmds.insert((Mana { current: 0 }, ActiveSpell::default()));
-    } else if run_state.character_class == CharacterClass::Duelist {
-        player_cmds.insert(PlayerCombo { current: 0 });
-    }
+    class.insert_components(&mut player_cmds);
 
     // Create Deck
-    let mut deck_cards = starting_deck;
+    let mut deck_cards = class.starter_deck();
 
     // Shuffle
     let mut rng = thread_rng();
@@ -92,128 +65,20 @@ pub fn setup_game(
     commands.insert_resource(Deck { cards: deck_cards });
     commands.insert_resource(DiscardPile::default());
 
-    // Generate Map
-    let mut levels = Vec::new();
-    let mut rng = thread_rng();
-
-    // Level 0: Start (3 Battle Nodes)
-    let mut start_nodes = Vec::new();
-    for _ in 0..3 {
-        start_nodes.push(MapNodeData {
-            node_type: NodeType::Battle,
-            next_indices: vec![],
-            visible: true,
-        });
-    }
-    levels.push(start_nodes);
-
-    // Levels 1-13: Random Encounters
-    for i in 1..=13 {
-        let mut nodes = Vec::new();
-        for _ in 0..3 {
-            // 3 nodes per level
-            let r = rng.gen_range(0..100);
-            let node_type = if i >= 2 && r < 20 {
-                NodeType::Elite
-            } else if r < 50 {
-                NodeType::Battle
-            } else if r < 70 {
-                NodeType::Event
-            } else if r < 85 {
-                NodeType::Shop
-            } else {
-                NodeType::Rest
-            };
-            nodes.push(MapNodeData {
-                node_type,
-                next_indices: vec![],
-                visible: false,
-            });
-        }
-        levels.push(nodes);
-    }
-
-    // Level 14: Boss
-    levels.push(vec![MapNodeData {
-        node_type: NodeType::Boss,
-        next_indices: vec![],
-        visible: false,
-    }]);
-
-    // Connect Levels
-    for i in 0..14 {
-        let current_len = levels[i].len();
-        let next_len = levels[i + 1].len();
-
-        for j in 0..current_len {
-            // Identify valid adjacent candidates in the next level
-            // A node at index j can connect to j-1, j, j+1 in the next level
-            let min_next = j.saturating_sub(1);
-            let max_next = (j + 1).min(next_len - 1);
-
-            let mut candidates: Vec<usize> = (min_next..=max_next).collect();
-
-            // If strict adjacency yields no candidates (e.g. due to width change), connect to closest
-            if candidates.is_empty() {
-                candidates.push(max_next);
-            }
-
-            // Ensure at least one connection
-            if let Some(&selected) = candidates.choose(&mut rng) {
-                levels[i][j].next_indices.push(selected);
-            }
-
-            // Chance to add more connections from valid candidates
-            for &candidate in &candidates {
-                if !levels[i][j].next_indices.contains(&candidate) {
-                    if rng.gen_bool(0.3) {
-                        // 30% chance to add extra path
-                        levels[i][j].next_indices.push(candidate);
-                    }
-                }
-            }
-            levels[i][j].next_indices.sort();
-            levels[i][j].next_indices.dedup();
-        }
-
-        // Ensure every node in next level has a parent (to prevent unreachable nodes)
-        for k in 0..next_len {
-            let mut has_parent = false;
-            for j in 0..current_len {
-                if levels[i][j].next_indices.contains(&k) {
-                    has_parent = true;
-                    break;
-                }
-            }
-
-            if !has_parent {
-                // Find a valid parent for node k
-                // Valid parents are those where k is adjacent (p-1 <= k <= p+1)
-                let min_parent = k.saturating_sub(1);
-                let max_parent = (k + 1).min(current_len - 1);
-                let mut valid_parents: Vec<usize> = (min_parent..=max_parent).collect();
-
-                // If strict adjacency yields no parents, use closest
-                if valid_parents.is_empty() {
-                    valid_parents.push(max_parent);
-                }
-
-                if let Some(&parent) = valid_parents.choose(&mut rng) {
-                    levels[i][parent].next_indices.push(k);
-                    levels[i][parent].next_indices.sort();
-                    levels[i][parent].next_indices.dedup();
-                }
-            }
-        }
-    }
-
     commands.insert_resource(GameMap {
-        levels,
+        levels: generate_map(),
         current_node: None,
         visited_path: Vec::new(),
     });
     commands.insert_resource(RewardStore::default());
     commands.insert_resource(ShopStore::default());
+    commands.insert_resource(ShopServices::default());
+    commands.insert_resource(RunStats::default());
+    commands.insert_resource(UnknownOdds::default());
+    commands.insert_resource(MapView::default());
+    commands.insert_resource(MapPlanner::default());
+    commands.insert_resource(EventHistory::default());
+    commands.remove_resource::<ActiveEvent>();
 }
 
 pub fn despawn_screen<T: Component>(
@@ -275,6 +140,10 @@ pub fn spawn_card_visual(
                     ..default()
                 })
                 .with_children(|parent| {
+                    // Status cards have no art
+                    if card.unplayable {
+                        return;
+                    }
                     parent.spawn(ImageBundle {
                         style: Style {
                             width: Val::Percent(200.0),
@@ -324,6 +193,9 @@ pub fn spawn_card_visual(
             if card.apply_stun > 0 {
                 desc.push_str("Stun Enemy\n");
             }
+            if card.unplayable {
+                desc.push_str("Unplayable\n");
+            }
 
             card_ui.spawn(
                 TextBundle::from_section(
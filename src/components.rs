@@ -1,22 +1,27 @@
 use crate::item_potions::Potion;
 use crate::item_relics::Relic;
 use bevy::prelude::*;
+use serde::Deserialize;
+use std::time::Instant;
 
 #[derive(Component)]
 pub struct Player;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
 pub enum CharacterClass {
     #[default]
     Duelist,
     Spellweaver,
+    Berserker,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
 pub enum EnemyKind {
     Goblin,
+    GoblinShaman,
     Orc,
     Dragon,
+    Whelp,
     DarkKnight,
 }
 
@@ -25,6 +30,31 @@ pub struct Enemy {
     pub kind: EnemyKind,
 }
 
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnemySlot(pub usize);
+
+// Summoned enemies flee once every non-minion enemy is defeated
+#[derive(Component)]
+pub struct Minion;
+
+// Extra buff given to the enemies of the one burning elite fight per map
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BurningBuff {
+    Vigor,    // +25% max HP
+    Strength, // Starts with Strength
+    Thorns,   // Damages the player when hit
+}
+
+#[derive(Component)]
+pub struct BurningElite {
+    pub buff: BurningBuff,
+}
+
+#[derive(Component)]
+pub struct Thorns {
+    pub amount: i32,
+}
+
 #[derive(Component)]
 pub struct Health {
     pub current: i32,
@@ -42,7 +72,7 @@ pub struct Mana {
     pub current: i32,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
 pub enum SpellElement {
     #[default]
     Neutral,
@@ -50,6 +80,9 @@ pub enum SpellElement {
     Ice,
     Wind,
     Stone,
+    Lightning,
+    Shadow,
+    Water,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -93,15 +126,32 @@ pub enum ReflexSuccess {
     Perfect,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ReflexVisualType {
+    #[default]
     ShrinkingRing,
     LinearSlider,
+    // One tap per hit, each on its own beat
+    MultiTap,
+    // Press the arrow key shown, on time
+    DirectionalPrompt,
+    // Hold to fill the meter, release inside the band
+    ChargeMeter,
+    // Click a target drifting across the screen
+    MovingTarget,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReflexDirection {
+    Up,
+    Down,
+    Left,
+    Right,
 }
 
 #[derive(Component)]
 pub struct ReflexState {
-    pub start_time: f32, // Unused conceptually if using timer, but good to have
+    pub started_at: Option<Instant>, // Stamped when settings are applied; inputs are timed against it
     pub timer: Timer,
     pub perfect_window_start: f32, // ratio 0.0 to 1.0 of the timer
     pub perfect_window_end: f32,
@@ -111,15 +161,27 @@ pub struct ReflexState {
     pub target_entity: Option<Entity>, // For the enemy being attacked
     pub base_damage: i32,              // Keep track of damage for resolution
     pub visual_type: ReflexVisualType,
+    pub good_margin: f32, // Ratio either side of the perfect window that still counts as Good
+    pub auto: bool,
+    pub cue_played: bool,
+    pub tolerance: f32,  // Difficulty scale for tap windows and target size
+    pub beats: Vec<f32>, // MultiTap: where each tap should land, as ratios of the timer
+    pub tap_results: Vec<ReflexSuccess>,
+    pub prompt: Option<ReflexDirection>,
+    pub charge_started_at: Option<Instant>,
 }
 
 #[derive(Component)]
 pub struct ReflexUI;
 
+// The highlighted perfect band behind a slider reflex
+#[derive(Component)]
+pub struct PerfectWindowIndicator;
+
 #[derive(Component)]
 pub struct ReflexMarker;
 
-#[derive(Component, Default)]
+#[derive(Component, Default, Clone)]
 pub struct StatusStore {
     pub poison: i32,
     pub weak: i32,
@@ -127,6 +189,9 @@ pub struct StatusStore {
     pub stun: i32,
     pub burning: i32,
     pub frozen: i32,
+    pub vulnerable: i32,
+    pub frail: i32,
+    pub vulnerable_fresh: bool, // Applied during this enemy turn, so its tick waits a turn
 }
 
 #[derive(Component)]
@@ -146,6 +211,19 @@ pub enum Rarity {
     Legendary,
 }
 
+// What a finisher does with the Combo points it spends. Double Damage spends
+// exactly the finisher cost; every other payoff spends all points and scales with them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Finisher {
+    None,
+    DoubleDamage,
+    BlockPerPoint(i32),
+    DrawPerPoint,
+    EnergyPerPoint,
+    MultiHit,
+    Stun,
+}
+
 #[derive(Component, Debug, Clone)]
 pub struct Card {
     pub name: String,
@@ -162,14 +240,27 @@ pub struct Card {
     pub element: SpellElement,
     pub combo_points_granted: u32,
     pub finisher_combo_cost: u32,
+    pub finisher: Finisher,
+    pub unplayable: bool,
+    pub missing_hp_scaling: i32,  // Percent of missing HP added to damage
+    pub reflex: ReflexVisualType, // Minigame played when this card attacks
 }
 
 #[derive(Component)]
 pub struct PendingPlayerAttack {
     pub targets: Vec<Entity>,
+    // Lightning's extra target, hit for a share of the damage
+    pub chain_target: Option<Entity>,
     pub damage: i32,
     pub card: Card,
     pub spell_elements: Vec<SpellElement>,
+    pub hits: u32,
+}
+
+// Cards to draw mid-turn, picked up by the battle's draw system
+#[derive(Component)]
+pub struct PendingDraw {
+    pub count: u32,
 }
 
 #[derive(Component)]
@@ -190,12 +281,76 @@ pub struct MapUI;
 #[derive(Component)]
 pub struct GameOverUI;
 
+#[derive(Component)]
+pub struct RunVictoryUI;
+
+#[derive(Component)]
+pub struct TreasureUI;
+
+#[derive(Component)]
+pub struct TreasureRelicButton {
+    pub relic: Relic,
+}
+
+#[derive(Component)]
+pub struct TreasureGoldButton {
+    pub amount: i32,
+}
+
+#[derive(Component)]
+pub struct LeaveTreasureButton;
+
 #[derive(Component)]
 pub struct MainMenuUI;
 
 #[derive(Component)]
 pub struct StartGameButton;
 
+#[derive(Component)]
+pub struct OpenSettingsButton;
+
+#[derive(Component)]
+pub struct SettingsUI;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReflexOption {
+    Difficulty,
+    AutoReflex,
+    SlowMotion,
+    VisualCue,
+    AudioCue,
+}
+
+#[derive(Component)]
+pub struct ReflexOptionButton {
+    pub option: ReflexOption,
+}
+
+// Text on an option button, rewritten whenever the settings change
+#[derive(Component)]
+pub struct ReflexOptionLabel {
+    pub option: ReflexOption,
+}
+
+#[derive(Component)]
+pub struct ReturnFromSettingsButton;
+
+#[derive(Component)]
+pub struct OpenCalibrationButton;
+
+#[derive(Component)]
+pub struct CalibrationUI;
+
+// Shrinks onto the target circle once per metronome beat
+#[derive(Component)]
+pub struct CalibrationRing;
+
+#[derive(Component)]
+pub struct CalibrationText;
+
+#[derive(Component)]
+pub struct ReturnFromCalibrationButton;
+
 #[derive(Component)]
 pub struct PlayerHealthText;
 
@@ -221,7 +376,7 @@ pub struct PlayerStatusText;
 pub struct EnemyStatusText;
 
 #[derive(Component)]
-pub struct PlayerComboText;
+pub struct ComboMeter;
 
 #[derive(Component)]
 pub struct PlayerRelicText;
@@ -258,6 +413,9 @@ pub struct HealButton;
 #[derive(Component)]
 pub struct UpgradeButton;
 
+#[derive(Component)]
+pub struct RestRemoveButton;
+
 #[derive(Component)]
 pub struct LeaveRestButton;
 
@@ -272,6 +430,38 @@ pub enum NodeType {
     Boss,
     Elite,
     Event,
+    Treasure,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteCriterion {
+    MostElites,
+    MostRests,
+    FewestBattles,
+    MostShops,
+}
+
+// Line between two map nodes; the entity is the pivot, its child is the drawn line
+#[derive(Component)]
+pub struct MapEdge {
+    pub from: (usize, usize),
+    pub to: (usize, usize),
+    pub color: Color,
+}
+
+#[derive(Component)]
+pub struct MapEdgeLine;
+
+#[derive(Component)]
+pub struct MapWaypointMarker {
+    pub level: usize,
+    pub index: usize,
+}
+
+#[derive(Component)]
+pub struct PlannerCriterionButton {
+    pub criterion: Option<RouteCriterion>,
 }
 
 #[derive(Component)]
@@ -296,6 +486,15 @@ pub struct ViewDeckButton;
 #[derive(Component)]
 pub struct ReturnFromDeckButton;
 
+#[derive(Component)]
+pub struct SpellbookUI;
+
+#[derive(Component)]
+pub struct ViewSpellbookButton;
+
+#[derive(Component)]
+pub struct ReturnFromSpellbookButton;
+
 #[derive(Component)]
 pub struct DiscardPileButton;
 
@@ -322,15 +521,44 @@ pub struct RemoveCardServiceButton {
 }
 
 #[derive(Component)]
-pub struct ShopRemoveUI;
+pub struct RestockShopButton {
+    pub cost: i32,
+}
+
+#[derive(Component)]
+pub struct HaggleButton;
+
+#[derive(Component)]
+pub struct SellRelicButton {
+    pub index: usize,
+    pub price: i32,
+}
+
+#[derive(Component)]
+pub struct SellPotionButton {
+    pub index: usize,
+    pub price: i32,
+}
+
+// Deck-editing operations shared by shops, events, rest sites and relics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum DeckService {
+    Remove,
+    Transform,
+    Duplicate,
+    Upgrade,
+}
+
+#[derive(Component)]
+pub struct CardPickerUI;
 
 #[derive(Component)]
-pub struct CardToRemoveButton {
+pub struct CardPickerButton {
     pub index: usize,
 }
 
 #[derive(Component)]
-pub struct CancelRemoveButton;
+pub struct CancelCardPickerButton;
 
 #[derive(Component)]
 pub struct RewardUI;
@@ -341,6 +569,11 @@ pub struct RewardGoldButton;
 #[derive(Component)]
 pub struct RewardCardButton;
 
+#[derive(Component)]
+pub struct RewardRelicButton {
+    pub relic: Relic,
+}
+
 #[derive(Component)]
 pub struct ProceedButton;
 
@@ -380,7 +613,7 @@ pub struct EventUI;
 
 #[derive(Component)]
 pub struct EventOptionButton {
-    pub effect_id: usize,
+    pub choice_index: usize,
 }
 
 #[derive(Component)]
@@ -397,7 +630,7 @@ pub struct RelicIcon {
 #[derive(Component)]
 pub struct SceneBackground;
 
-#[derive(Component, Debug, Clone)]
+#[derive(Component, Debug, Clone, Default)]
 pub struct NextEnemyMove {
     pub name: String,
     pub damage: i32,
@@ -406,6 +639,55 @@ pub struct NextEnemyMove {
     pub weak: i32,
     pub steal_gold: i32,
     pub is_charging: bool,
+    pub summon: Vec<EnemyKind>,
+    pub hits: i32, // Number of times `damage` is dealt, 0 and 1 both mean a single hit
+    pub strength: i32,
+    pub heal: i32,
+    pub heal_ally: i32,
+    pub block_allies: i32,
+    pub cleanse: bool,
+    pub vulnerable: i32,
+    pub frail: i32,
+    pub wounds: i32,
+    pub reflex: ReflexVisualType, // Minigame the player defends with
+}
+
+// Names of the moves this enemy has performed this battle, oldest first
+#[derive(Component, Debug, Clone, Default)]
+pub struct EnemyMoveHistory {
+    pub moves: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PhaseTrigger {
+    HealthBelow(f32), // Fraction of max HP
+    TurnReached(u32),
+}
+
+#[derive(Debug, Clone)]
+pub struct BossPhase {
+    pub name: String,
+    pub trigger: PhaseTrigger,
+    pub sprite: Option<String>, // Replacement art; the tint alone stands in without it
+    pub sprite_tint: Option<Color>,
+    pub background: Option<String>,
+    pub clear_debuffs: bool,
+    pub gain_block: i32,
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct BossPhases {
+    pub phases: Vec<BossPhase>,
+    pub current: usize, // 0 = opening phase, N = after the Nth transition
+    pub turns_taken: u32,
+}
+
+#[derive(Component)]
+pub struct EnemySprite;
+
+#[derive(Component)]
+pub struct PhaseBannerUi {
+    pub timer: Timer,
 }
 
 #[derive(Component)]
@@ -427,13 +709,15 @@ pub struct Selected;
 pub struct CharacterSelectUI;
 
 #[derive(Component)]
-pub struct SelectDuelistButton;
+pub struct SelectClassButton {
+    pub class: CharacterClass,
+}
 
 #[derive(Component)]
-pub struct SelectSpellweaverButton;
+pub struct PlayerSpellContainer;
 
 #[derive(Component)]
-pub struct PlayerSpellContainer;
+pub struct SpellPreviewText;
 
 #[derive(Component)]
 pub struct BonusSelectUI;
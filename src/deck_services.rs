@@ -0,0 +1,127 @@
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+
+use crate::classes::{PLAYABLE_CLASSES, player_class};
+use crate::components::*;
+use crate::item_cards::*;
+use crate::resources::Deck;
+
+pub const SHOP_REMOVAL_BASE_PRICE: i32 = 75;
+pub const SHOP_REMOVAL_PRICE_STEP: i32 = 25;
+
+// Every removal bought at a shop makes the next one pricier
+pub fn shop_removal_price(removals: u32) -> i32 {
+    SHOP_REMOVAL_BASE_PRICE + SHOP_REMOVAL_PRICE_STEP * removals as i32
+}
+
+pub fn deck_service_name(service: DeckService) -> &'static str {
+    match service {
+        DeckService::Remove => "Remove",
+        DeckService::Transform => "Transform",
+        DeckService::Duplicate => "Duplicate",
+        DeckService::Upgrade => "Upgrade",
+    }
+}
+
+pub fn class_card_pool(class: CharacterClass) -> Vec<Card> {
+    player_class(class).card_pool()
+}
+
+// Upgraded cards keep their base name with a trailing "+"
+fn base_name(card: &Card) -> &str {
+    card.name.trim_end_matches('+')
+}
+
+fn in_pool(card: &Card, class: CharacterClass) -> bool {
+    class_card_pool(class)
+        .iter()
+        .any(|pool_card| pool_card.name == base_name(card))
+}
+
+// The first class whose pool a card belongs to, None for status and colorless cards
+pub fn card_class(card: &Card) -> Option<CharacterClass> {
+    PLAYABLE_CLASSES
+        .into_iter()
+        .find(|class| in_pool(card, *class))
+}
+
+pub fn can_apply_service(service: DeckService, card: &Card) -> bool {
+    match service {
+        DeckService::Upgrade => !card.upgraded && !card.unplayable,
+        DeckService::Remove | DeckService::Transform | DeckService::Duplicate => true,
+    }
+}
+
+// Cards a transform can turn this one into: same class and rarity, never itself.
+// Shared basics and status cards transform into the player's class.
+pub fn transform_options(card: &Card, player_class: CharacterClass) -> Vec<Card> {
+    let class = if in_pool(card, player_class) {
+        player_class
+    } else {
+        card_class(card).unwrap_or(player_class)
+    };
+    let pool: Vec<Card> = class_card_pool(class)
+        .into_iter()
+        .filter(|option| option.name != base_name(card))
+        .collect();
+    let same_rarity: Vec<Card> = pool
+        .iter()
+        .filter(|option| option.rarity == card.rarity)
+        .cloned()
+        .collect();
+    if same_rarity.is_empty() {
+        pool
+    } else {
+        same_rarity
+    }
+}
+
+// Runs a service on one card of the deck. Returns a log line, or None if nothing changed.
+pub fn apply_deck_service(
+    deck: &mut Deck,
+    index: usize,
+    service: DeckService,
+    player_class: CharacterClass,
+) -> Option<String> {
+    let card = deck.cards.get(index)?;
+    if !can_apply_service(service, card) {
+        return None;
+    }
+
+    match service {
+        DeckService::Remove => {
+            let removed = deck.cards.remove(index);
+            Some(format!("Removed {} from deck.", removed.name))
+        }
+        DeckService::Transform => {
+            let new_card = transform_options(card, player_class)
+                .choose(&mut thread_rng())
+                .cloned()?;
+            let message = format!("Transformed {} into {}.", card.name, new_card.name);
+            deck.cards[index] = new_card;
+            Some(message)
+        }
+        DeckService::Duplicate => {
+            let copy = card.clone();
+            let message = format!("Duplicated {}.", copy.name);
+            deck.cards.push(copy);
+            Some(message)
+        }
+        DeckService::Upgrade => {
+            upgrade_card(&mut deck.cards[index]);
+            Some(format!("Upgraded {}.", deck.cards[index].name))
+        }
+    }
+}
+
+// Picks a random eligible card for services that are not chosen by the player
+pub fn random_service_target(deck: &Deck, service: DeckService) -> Option<usize> {
+    let candidates: Vec<usize> = deck
+        .cards
+        .iter()
+        .enumerate()
+        .filter(|(_, card)| can_apply_service(service, card))
+        .map(|(index, _)| index)
+        .collect();
+    candidates.choose(&mut thread_rng()).copied()
+}
@@ -1,33 +1,85 @@
 use bevy::prelude::*;
 use rand::{Rng, thread_rng};
 
+use crate::combat_math::tick_player_vulnerable;
 use crate::components::*;
+use crate::item_cards;
 use crate::item_relics::Relic;
+use crate::reflex::{multi_tap_beats, random_direction, spawn_reflex_challenge};
 use crate::resources::*;
+use crate::spell_recipes::element_name;
 use crate::states::*;
 
 pub fn update_enemy_tooltip_system(
     mut query: Query<
-        (&Enemy, &StatusStore, &NextEnemyMove, &mut Tooltip),
-        Or<(Added<Enemy>, Changed<StatusStore>, Changed<NextEnemyMove>)>,
+        (
+            Ref<Enemy>,
+            Ref<StatusStore>,
+            Ref<NextEnemyMove>,
+            &mut Tooltip,
+            Option<&BossPhases>,
+            Option<&BurningElite>,
+        ),
+        Without<Player>,
     >,
+    player_query: Query<Ref<StatusStore>, With<Player>>,
 ) {
-    for (_enemy, status, next_move, mut tooltip) in query.iter_mut() {
+    let Ok(player_status) = player_query.get_single() else {
+        return;
+    };
+    for (enemy, status, next_move, mut tooltip, phases, burning) in query.iter_mut() {
+        if !(enemy.is_added()
+            || status.is_changed()
+            || next_move.is_changed()
+            || player_status.is_changed())
+        {
+            continue;
+        }
+
+        let mut phase_line = match phases {
+            Some(phases) if phases.current > 0 => {
+                format!("Phase: {}\n", phases.phases[phases.current - 1].name)
+            }
+            _ => String::new(),
+        };
+        if let Some(burning) = burning {
+            phase_line.push_str(&format!(
+                "Burning: {}\n",
+                burning_buff_description(burning.buff)
+            ));
+        }
+        let affinity = element_affinity(enemy.kind);
+        let names = |elements: &[SpellElement]| {
+            elements
+                .iter()
+                .map(|element| element_name(*element))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        if !affinity.weak.is_empty() {
+            phase_line.push_str(&format!("Weak to: {}\n", names(&affinity.weak)));
+        }
+        if !affinity.resist.is_empty() {
+            phase_line.push_str(&format!("Resists: {}\n", names(&affinity.resist)));
+        }
+
         if status.stun > 0 {
-            tooltip.text = "Intent: Stunned\nCannot attack this turn.".to_string();
+            tooltip.text = format!("{}Intent: Stunned\nCannot attack this turn.", phase_line);
             continue;
         }
 
         let base_damage = next_move.damage;
         let move_name = &next_move.name;
+        let final_damage = enemy_attack_damage(&next_move, &status, &player_status);
+        let hits = enemy_hit_count(&next_move);
 
-        let mut final_damage = base_damage;
-        if status.weak > 0 && base_damage > 0 {
-            final_damage = (final_damage as f32 * 0.75) as i32;
-        }
-
-        let mut desc = format!("Intent: {}\n", move_name);
-        if base_damage > 0 {
+        let mut desc = format!("{}Intent: {}\n", phase_line, move_name);
+        if base_damage > 0 && hits > 1 {
+            desc.push_str(&format!(
+                "Damage: {}x{} (Base: {})\n",
+                final_damage, hits, base_damage
+            ));
+        } else if base_damage > 0 {
             desc.push_str(&format!(
                 "Damage: {} (Base: {})\n",
                 final_damage, base_damage
@@ -36,6 +88,36 @@ pub fn update_enemy_tooltip_system(
         if next_move.block > 0 {
             desc.push_str(&format!("Block: {}\n", next_move.block));
         }
+        if next_move.block_allies > 0 {
+            desc.push_str(&format!(
+                "All enemies gain {} Block\n",
+                next_move.block_allies
+            ));
+        }
+        if next_move.strength > 0 {
+            desc.push_str(&format!("Gain {} Strength\n", next_move.strength));
+        }
+        if next_move.heal > 0 {
+            desc.push_str(&format!("Heal {} HP\n", next_move.heal));
+        }
+        if next_move.heal_ally > 0 {
+            desc.push_str(&format!("Heal an ally for {} HP\n", next_move.heal_ally));
+        }
+        if next_move.cleanse {
+            desc.push_str("Remove all debuffs\n");
+        }
+        if next_move.vulnerable > 0 {
+            desc.push_str(&format!("Apply {} Vulnerable\n", next_move.vulnerable));
+        }
+        if next_move.frail > 0 {
+            desc.push_str(&format!("Apply {} Frail\n", next_move.frail));
+        }
+        if next_move.wounds > 0 {
+            desc.push_str(&format!(
+                "Add {} Wound(s) to your discard pile\n",
+                next_move.wounds
+            ));
+        }
         if next_move.poison > 0 {
             desc.push_str(&format!("Apply {} Poison\n", next_move.poison));
         }
@@ -48,6 +130,9 @@ pub fn update_enemy_tooltip_system(
         if next_move.is_charging {
             desc.push_str("Charging up...\n");
         }
+        if !next_move.summon.is_empty() {
+            desc.push_str(&format!("Summons {} ally\n", next_move.summon.len()));
+        }
 
         tooltip.text = desc;
     }
@@ -56,13 +141,14 @@ pub fn update_enemy_tooltip_system(
 pub fn enemy_turn_system(
     mut commands: Commands,
     mut next_turn_state: ResMut<NextState<TurnState>>,
-    enemy_query: Query<(Entity, &Health), (With<Enemy>, Without<Player>)>,
+    mut enemy_query: Query<(Entity, &Health, &mut StatusStore), (With<Enemy>, Without<Player>)>,
     mut queue_query: Query<(Entity, &mut EnemyQueue)>,
+    mut player_query: Query<&mut StatusStore, With<Player>>,
 ) {
     if queue_query.is_empty() {
         // Collect living enemies
         let mut queue = Vec::new();
-        for (entity, health) in enemy_query.iter() {
+        for (entity, health, _) in enemy_query.iter() {
             if health.current > 0 {
                 queue.push(entity);
             }
@@ -78,7 +164,7 @@ pub fn enemy_turn_system(
         let next_enemy = queue.0.remove(0);
 
         // Ensure enemy is still alive
-        if let Ok((_, health)) = enemy_query.get(next_enemy) {
+        if let Ok((_, health, _)) = enemy_query.get(next_enemy) {
             if health.current > 0 {
                 commands.entity(next_enemy).insert(AttackingEnemy);
                 // We keep the queue_entity alive to resume processing the remaining queue
@@ -90,6 +176,15 @@ pub fn enemy_turn_system(
 
     // Finished
     commands.entity(queue_entity).despawn_recursive();
+    if let Ok(mut player_status) = player_query.get_single_mut() {
+        tick_player_vulnerable(&mut player_status);
+    }
+    // Vulnerable the player put on enemies lasts through the player's next turn
+    for (_, _, mut status) in &mut enemy_query {
+        if status.vulnerable > 0 {
+            status.vulnerable -= 1;
+        }
+    }
     next_turn_state.set(TurnState::PlayerTurnStart);
     println!("Player's turn.");
 }
@@ -101,32 +196,48 @@ pub fn enemy_attack_animating_system(
     mut enemy_query: Query<
         (
             Entity,
-            &Enemy,
             &mut Block,
             &mut Health,
             &mut StatusStore,
-            &mut NextEnemyMove,
+            &NextEnemyMove,
+            &mut EnemyMoveHistory,
+            Option<&mut BossPhases>,
+            &EnemySlot,
         ),
         (With<AttackingEnemy>, Without<Player>),
     >,
+    mut other_enemies: Query<
+        (&mut Health, &mut Block, &EnemySlot, Has<Minion>),
+        (With<Enemy>, Without<AttackingEnemy>, Without<Player>),
+    >,
     mut player_query: Query<
         (Entity, &mut Health, &mut Block, &mut Gold, &mut StatusStore),
         (With<Player>, Without<Enemy>),
     >,
+    mut discard: ResMut<DiscardPile>,
     mut intent_text_query: Query<(&Parent, &mut Text), With<EnemyIntentText>>,
     mut flash_query: Query<&mut BackgroundColor, With<DamageFlashUi>>,
     mut reflex_query: Query<(Entity, Option<&Parent>, &mut ReflexState)>,
     window_query: Query<&Window>,
     time: Res<Time>,
+    asset_server: Res<AssetServer>,
 ) {
-    let (enemy_entity, enemy, mut enemy_block, mut enemy_health, mut enemy_status, mut next_move) =
-        if let Ok(e) = enemy_query.get_single_mut() {
-            e
-        } else {
-            // fallback if missing
-            next_turn_state.set(TurnState::EnemyTurn);
-            return;
-        };
+    let (
+        enemy_entity,
+        mut enemy_block,
+        mut enemy_health,
+        mut enemy_status,
+        next_move,
+        mut history,
+        mut boss_phases,
+        enemy_slot,
+    ) = if let Ok(e) = enemy_query.get_single_mut() {
+        e
+    } else {
+        // fallback if missing
+        next_turn_state.set(TurnState::EnemyTurn);
+        return;
+    };
 
     let (player_entity, mut player_health, mut player_block, mut player_gold, mut player_status) =
         if let Ok(p) = player_query.get_single_mut() {
@@ -158,9 +269,12 @@ pub fn enemy_attack_animating_system(
             // Dead before attack
             commands.entity(enemy_entity).despawn_recursive();
 
-            // Re-check victory
-            let enemies_remaining = enemy_query.iter().count() - 1; // Since this one is dying
-            if enemies_remaining == 0 {
+            // Re-check victory (minions don't keep the fight going on their own)
+            let leaders_remaining = other_enemies
+                .iter()
+                .filter(|(health, _, _, is_minion)| health.current > 0 && !is_minion)
+                .count();
+            if leaders_remaining == 0 {
                 next_game_state.set(GameState::Victory);
             } else {
                 next_turn_state.set(TurnState::EnemyTurn);
@@ -178,95 +292,108 @@ pub fn enemy_attack_animating_system(
                     text.sections[0].value = "Stunned!".to_string();
                 }
             }
+            if let Some(ref mut phases) = boss_phases {
+                phases.turns_taken += 1;
+            }
             commands.entity(enemy_entity).remove::<AttackingEnemy>();
             next_turn_state.set(TurnState::EnemyTurn);
             return;
         }
 
-        let mut final_damage = next_move.damage;
+        // Same calculation as the intent shown to the player
+        let final_damage = enemy_attack_damage(next_move, &enemy_status, &player_status);
+        let hits = enemy_hit_count(next_move);
         if enemy_status.weak > 0 && final_damage > 0 {
-            final_damage = (final_damage as f32 * 0.75) as i32;
             enemy_status.weak -= 1;
         }
-        if enemy_status.frozen > 0 && final_damage > 0 {
-            final_damage = (final_damage as f32 * 0.75) as i32;
-        }
 
         for (parent, mut text) in &mut intent_text_query {
             if parent.get() == enemy_entity {
-                text.sections[0].value = format!("{}! ({} dmg)", next_move.name, final_damage);
+                text.sections[0].value = if hits > 1 {
+                    format!("{}! ({}x{} dmg)", next_move.name, final_damage, hits)
+                } else {
+                    format!("{}! ({} dmg)", next_move.name, final_damage)
+                };
             }
         }
 
+        // Self and ally support resolves before the attack
         if next_move.block > 0 {
             enemy_block.value += next_move.block;
             println!("Enemy gained {} block", next_move.block);
         }
+        if next_move.strength > 0 {
+            enemy_status.strength += next_move.strength;
+            println!("Enemy gained {} strength", next_move.strength);
+        }
+        if next_move.heal > 0 {
+            enemy_health.current = (enemy_health.current + next_move.heal).min(enemy_health.max);
+            println!("Enemy healed {} HP", next_move.heal);
+        }
+        if next_move.cleanse {
+            enemy_status.poison = 0;
+            enemy_status.weak = 0;
+            enemy_status.burning = 0;
+            enemy_status.frozen = 0;
+            enemy_status.vulnerable = 0;
+            enemy_status.frail = 0;
+            println!("Enemy cleansed its debuffs");
+        }
+        if next_move.block_allies > 0 {
+            enemy_block.value += next_move.block_allies;
+            for (health, mut block, _, _) in other_enemies.iter_mut() {
+                if health.current > 0 {
+                    block.value += next_move.block_allies;
+                }
+            }
+            println!("All enemies gained {} block", next_move.block_allies);
+        }
+        if next_move.heal_ally > 0 {
+            let ratio = |health: &Health| health.current as f32 / health.max as f32;
+            if let Some((mut health, _, _, _)) = other_enemies
+                .iter_mut()
+                .filter(|(health, _, _, _)| health.current > 0 && health.current < health.max)
+                .min_by(|a, b| ratio(&a.0).total_cmp(&ratio(&b.0)))
+            {
+                health.current = (health.current + next_move.heal_ally).min(health.max);
+                println!("Enemy healed an ally for {} HP", next_move.heal_ally);
+            }
+        }
 
         if final_damage > 0 {
-            // Spawn Reflex State / UI
-            let duration = 1.0; // 1 second telegraph
-            commands
-                .spawn((
-                    ReflexState {
-                        start_time: 0.0,
-                        timer: Timer::from_seconds(duration, TimerMode::Once),
-                        perfect_window_start: 0.7,
-                        perfect_window_end: 0.9,
-                        result: None,
-                        is_defensive: true,
-                        source_entity: Some(enemy_entity),
-                        target_entity: Some(player_entity),
-                        base_damage: final_damage,
-                        visual_type: ReflexVisualType::ShrinkingRing,
-                    },
-                    BattleEntity,
-                    ReflexUI,
-                    NodeBundle {
-                        style: Style {
-                            position_type: PositionType::Absolute,
-                            left: Val::Px(cx - 150.0),
-                            bottom: Val::Px(cy - 150.0),
-                            width: Val::Px(300.0),
-                            height: Val::Px(300.0),
-                            border: UiRect::all(Val::Px(4.0)),
-                            justify_content: JustifyContent::Center,
-                            align_items: AlignItems::Center,
-                            ..default()
-                        },
-                        background_color: Color::srgba(0.0, 0.0, 0.0, 0.0).into(),
-                        border_color: Color::WHITE.into(),
-                        z_index: ZIndex::Global(1000),
-                        ..default()
-                    },
-                ))
-                .with_children(|parent| {
-                    // Target inner ring (static)
-                    parent.spawn(NodeBundle {
-                        style: Style {
-                            width: Val::Px(90.0), // 30% of 300, matching the 0.7 timer end roughly
-                            height: Val::Px(90.0),
-                            border: UiRect::all(Val::Px(4.0)),
-                            ..default()
-                        },
-                        border_color: Color::srgba(0.0, 1.0, 0.0, 0.8).into(),
-                        ..default()
-                    });
-                });
+            spawn_defensive_reflex(
+                &mut commands,
+                cx,
+                cy,
+                enemy_entity,
+                player_entity,
+                final_damage,
+                hits,
+                next_move.reflex,
+            );
         } else {
             // No damage attack, skip reflex directly to resolution
-            if next_move.poison > 0 {
-                player_status.poison += next_move.poison;
-            }
-            if next_move.weak > 0 {
-                player_status.weak += next_move.weak;
-            }
-            if next_move.steal_gold > 0 {
-                let stolen = std::cmp::min(player_gold.amount, next_move.steal_gold);
-                player_gold.amount -= stolen;
+            apply_enemy_move_to_player(
+                next_move,
+                &mut player_status,
+                &mut player_gold,
+                &mut discard,
+            );
+
+            if !next_move.summon.is_empty() {
+                let mut occupied: Vec<usize> = other_enemies
+                    .iter()
+                    .filter(|(health, _, _, _)| health.current > 0)
+                    .map(|(_, _, slot, _)| slot.0)
+                    .collect();
+                occupied.push(enemy_slot.0);
+                summon_enemies(&mut commands, &asset_server, &next_move.summon, &occupied);
             }
 
-            *next_move = generate_enemy_move(enemy.kind, next_move.is_charging);
+            if let Some(ref mut phases) = boss_phases {
+                phases.turns_taken += 1;
+            }
+            history.moves.push(next_move.name.clone());
             commands.entity(enemy_entity).remove::<AttackingEnemy>();
             next_turn_state.set(TurnState::EnemyTurn);
         }
@@ -279,22 +406,13 @@ pub fn enemy_attack_animating_system(
 
     if reflex.timer.finished() || reflex.result.is_some() {
         // Resolution!
-        let mut final_damage = reflex.base_damage;
-        let mut is_perfect = false;
+        let final_damage = defended_damage(&reflex);
+        let is_perfect = reflex.result == Some(ReflexSuccess::Perfect);
 
         match reflex.result {
-            Some(ReflexSuccess::Perfect) => {
-                println!("PERFECT BLOCK!");
-                final_damage = (final_damage as f32 * 0.1) as i32; // Take 10% damage
-                is_perfect = true;
-            }
-            Some(ReflexSuccess::Good) => {
-                println!("GOOD BLOCK!");
-                final_damage = (final_damage as f32 * 0.5) as i32; // Take 50% damage
-            }
-            _ => {
-                println!("MISSED BLOCK!");
-            }
+            Some(ReflexSuccess::Perfect) => println!("PERFECT BLOCK!"),
+            Some(ReflexSuccess::Good) => println!("GOOD BLOCK!"),
+            _ => println!("MISSED BLOCK!"),
         }
 
         let block_damage = std::cmp::min(final_damage, player_block.value);
@@ -341,28 +459,38 @@ pub fn enemy_attack_animating_system(
             }
         }
 
-        if next_move.poison > 0 {
-            player_status.poison += next_move.poison;
-        }
-        if next_move.weak > 0 {
-            player_status.weak += next_move.weak;
+        if let Some(parent) = reflex_parent {
+            commands.entity(parent.get()).despawn_recursive();
+        } else {
+            commands.entity(reflex_ent).despawn_recursive();
         }
-        if next_move.steal_gold > 0 {
-            let stolen = std::cmp::min(player_gold.amount, next_move.steal_gold);
-            player_gold.amount -= stolen;
+
+        apply_enemy_move_to_player(
+            next_move,
+            &mut player_status,
+            &mut player_gold,
+            &mut discard,
+        );
+
+        if !next_move.summon.is_empty() {
+            let mut occupied: Vec<usize> = other_enemies
+                .iter()
+                .filter(|(health, _, _, _)| health.current > 0)
+                .map(|(_, _, slot, _)| slot.0)
+                .collect();
+            occupied.push(enemy_slot.0);
+            summon_enemies(&mut commands, &asset_server, &next_move.summon, &occupied);
         }
 
-        *next_move = generate_enemy_move(enemy.kind, next_move.is_charging);
+        if let Some(ref mut phases) = boss_phases {
+            phases.turns_taken += 1;
+        }
+        history.moves.push(next_move.name.clone());
 
         if enemy_status.frozen > 0 {
             enemy_status.frozen -= 1;
         }
 
-        if let Some(parent) = reflex_parent {
-            commands.entity(parent.get()).despawn_recursive();
-        } else {
-            commands.entity(reflex_ent).despawn_recursive();
-        }
         commands.entity(enemy_entity).remove::<AttackingEnemy>();
 
         if player_health.current <= 0 {
@@ -373,144 +501,988 @@ pub fn enemy_attack_animating_system(
     }
 }
 
-// Helper to generate moves
-pub fn generate_enemy_move(kind: EnemyKind, prev_was_charging: bool) -> NextEnemyMove {
-    if prev_was_charging {
-        return NextEnemyMove {
-            name: "Fire Breath".to_string(),
-            damage: 50,
-            block: 0,
-            poison: 0,
-            weak: 0,
-            steal_gold: 0,
-            is_charging: false,
-        };
+fn blocked_hit(damage: i32, result: Option<ReflexSuccess>) -> i32 {
+    match result {
+        Some(ReflexSuccess::Perfect) => (damage as f32 * 0.1) as i32, // Take 10% damage
+        Some(ReflexSuccess::Good) => (damage as f32 * 0.5) as i32,    // Take 50% damage
+        _ => damage,
     }
+}
 
-    let mut rng = thread_rng();
-    let roll = rng.gen_range(0..100);
-
-    match kind {
-        EnemyKind::Goblin => {
-            if roll < 60 {
-                NextEnemyMove {
-                    name: "Stabs".to_string(),
-                    damage: 5,
-                    block: 0,
-                    poison: 0,
-                    weak: 0,
-                    steal_gold: 0,
-                    is_charging: false,
-                }
-            } else {
-                NextEnemyMove {
-                    name: "Thieve".to_string(),
-                    damage: 3,
-                    block: 0,
-                    poison: 0,
-                    weak: 0,
-                    steal_gold: 10,
-                    is_charging: false,
+// Damage that gets through a defensive reflex. Multi-hit attacks judge each hit on its own beat,
+// and beats the player never tapped land in full.
+pub fn defended_damage(reflex: &ReflexState) -> i32 {
+    if reflex.visual_type != ReflexVisualType::MultiTap {
+        return blocked_hit(reflex.base_damage, reflex.result);
+    }
+    (0..reflex.beats.len())
+        .map(|beat| blocked_hit(reflex.base_damage, reflex.tap_results.get(beat).copied()))
+        .sum()
+}
+
+// Debuffs, theft and junk cards land once the whole attack has resolved
+pub fn apply_enemy_move_to_player(
+    next_move: &NextEnemyMove,
+    player_status: &mut StatusStore,
+    player_gold: &mut Gold,
+    discard: &mut DiscardPile,
+) {
+    if next_move.poison > 0 {
+        player_status.poison += next_move.poison;
+    }
+    if next_move.weak > 0 {
+        player_status.weak += next_move.weak;
+    }
+    if next_move.vulnerable > 0 {
+        player_status.vulnerable += next_move.vulnerable;
+        player_status.vulnerable_fresh = true;
+    }
+    if next_move.frail > 0 {
+        player_status.frail += next_move.frail;
+    }
+    if next_move.steal_gold > 0 {
+        let stolen = std::cmp::min(player_gold.amount, next_move.steal_gold);
+        player_gold.amount -= stolen;
+    }
+    for _ in 0..next_move.wounds {
+        discard.cards.push(item_cards::wound());
+    }
+    if next_move.wounds > 0 {
+        println!("{} Wound(s) added to your discard pile", next_move.wounds);
+    }
+}
+
+fn spawn_defensive_reflex(
+    commands: &mut Commands,
+    cx: f32,
+    cy: f32,
+    enemy_entity: Entity,
+    player_entity: Entity,
+    damage: i32,
+    hits: i32,
+    visual_type: ReflexVisualType,
+) {
+    // Multi-hit attacks are defended beat by beat in a single reflex
+    let visual_type = match visual_type {
+        ReflexVisualType::MultiTap if hits < 2 => ReflexVisualType::ShrinkingRing,
+        reflex => reflex,
+    };
+    let duration = if visual_type == ReflexVisualType::MultiTap {
+        1.6
+    } else {
+        1.0 // 1 second telegraph
+    };
+    let reflex = ReflexState {
+        started_at: None,
+        timer: Timer::from_seconds(duration, TimerMode::Once),
+        perfect_window_start: 0.7,
+        perfect_window_end: 0.9,
+        result: None,
+        is_defensive: true,
+        source_entity: Some(enemy_entity),
+        target_entity: Some(player_entity),
+        base_damage: damage,
+        visual_type,
+        good_margin: 0.1,
+        auto: false,
+        cue_played: false,
+        tolerance: 1.0,
+        beats: if visual_type == ReflexVisualType::MultiTap {
+            multi_tap_beats(hits as u32)
+        } else {
+            Vec::new()
+        },
+        tap_results: Vec::new(),
+        prompt: if visual_type == ReflexVisualType::DirectionalPrompt {
+            Some(random_direction(&mut thread_rng()))
+        } else {
+            None
+        },
+        charge_started_at: None,
+    };
+    if visual_type != ReflexVisualType::ShrinkingRing {
+        spawn_reflex_challenge(commands, Vec2::new(cx * 2.0, cy * 2.0), reflex);
+        return;
+    }
+    commands
+        .spawn((
+            reflex,
+            BattleEntity,
+            ReflexUI,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(cx - 150.0),
+                    bottom: Val::Px(cy - 150.0),
+                    width: Val::Px(300.0),
+                    height: Val::Px(300.0),
+                    border: UiRect::all(Val::Px(4.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.0).into(),
+                border_color: Color::WHITE.into(),
+                z_index: ZIndex::Global(1000),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            // Target inner ring (static)
+            parent.spawn(NodeBundle {
+                style: Style {
+                    width: Val::Px(90.0), // 30% of 300, matching the 0.7 timer end roughly
+                    height: Val::Px(90.0),
+                    border: UiRect::all(Val::Px(4.0)),
+                    ..default()
+                },
+                border_color: Color::srgba(0.0, 1.0, 0.0, 0.8).into(),
+                ..default()
+            });
+        });
+}
+
+pub const MAX_ENEMY_SLOTS: usize = 4;
+
+pub struct EnemyStats {
+    pub hp: i32,
+    pub name: &'static str,
+    pub sprite: &'static str,
+    pub tint: Color,
+    pub sprite_width: Option<f32>,
+}
+
+pub fn enemy_stats(kind: EnemyKind) -> EnemyStats {
+    let (hp, name, sprite) = match kind {
+        EnemyKind::Goblin => (20, "Goblin", "images/enemies/Goblin.png"),
+        EnemyKind::GoblinShaman => (24, "Goblin Shaman", "images/enemies/Goblin.png"),
+        EnemyKind::Orc => (40, "Orc", "images/enemies/Orc.png"),
+        EnemyKind::Dragon => (150, "Dragon", "images/enemies/Dragon.png"),
+        EnemyKind::Whelp => (14, "Whelp", "images/enemies/Dragon.png"),
+        EnemyKind::DarkKnight => (80, "Dark Knight", "images/enemies/DarkKnight.png"),
+    };
+
+    // Variants reuse existing art with a tint and/or a smaller sprite
+    let (tint, sprite_width) = match kind {
+        EnemyKind::GoblinShaman => (Color::srgb(0.6, 0.8, 1.0), None),
+        EnemyKind::Whelp => (Color::srgb(1.0, 0.8, 0.6), Some(120.0)),
+        _ => (Color::WHITE, None),
+    };
+
+    EnemyStats {
+        hp,
+        name,
+        sprite,
+        tint,
+        sprite_width,
+    }
+}
+
+// Spell elements an enemy takes extra or reduced damage from
+pub struct ElementAffinity {
+    pub weak: Vec<SpellElement>,
+    pub resist: Vec<SpellElement>,
+}
+
+pub fn element_affinity(kind: EnemyKind) -> ElementAffinity {
+    let (weak, resist) = match kind {
+        EnemyKind::Goblin => (vec![SpellElement::Fire], vec![]),
+        EnemyKind::GoblinShaman => (vec![SpellElement::Shadow], vec![SpellElement::Lightning]),
+        EnemyKind::Orc => (vec![SpellElement::Ice], vec![SpellElement::Stone]),
+        EnemyKind::Dragon => (
+            vec![SpellElement::Ice, SpellElement::Water],
+            vec![SpellElement::Fire],
+        ),
+        EnemyKind::Whelp => (vec![SpellElement::Water], vec![SpellElement::Fire]),
+        EnemyKind::DarkKnight => (vec![SpellElement::Lightning], vec![SpellElement::Shadow]),
+    };
+    ElementAffinity { weak, resist }
+}
+
+// Slots fill from the right edge of the screen towards the player
+pub fn enemy_slot_offset(slot: usize) -> f32 {
+    50.0 + (slot as f32 * 250.0)
+}
+
+pub fn free_enemy_slots(occupied: &[usize]) -> Vec<usize> {
+    (0..MAX_ENEMY_SLOTS)
+        .filter(|slot| !occupied.contains(slot))
+        .collect()
+}
+
+pub fn spawn_enemy(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    kind: EnemyKind,
+    slot: usize,
+    initial_weak: i32,
+) -> Entity {
+    let stats = enemy_stats(kind);
+    // The real intent is planned at the start of the player's turn
+    let initial_move = NextEnemyMove {
+        name: "Planning...".to_string(),
+        ..default()
+    };
+
+    let mut entity_cmds = commands.spawn((
+        Enemy { kind },
+        EnemySlot(slot),
+        Health {
+            current: stats.hp,
+            max: stats.hp,
+        },
+        Block { value: 0 },
+        StatusStore {
+            weak: initial_weak,
+            ..default()
+        },
+        initial_move,
+        EnemyMoveHistory::default(),
+        BattleEntity,
+        Interaction::default(), // Allow clicking
+        Tooltip {
+            text: String::new(),
+        },
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(50.0),
+                right: Val::Px(enemy_slot_offset(slot)),
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            background_color: Color::srgba(0.0, 0.0, 0.0, 0.0).into(),
+            ..default()
+        },
+    ));
+
+    if let Some(phases) = boss_phases_for(kind) {
+        entity_cmds.insert(phases);
+    }
+
+    entity_cmds.with_children(|parent| {
+        parent.spawn((
+            ImageBundle {
+                style: Style {
+                    width: stats.sprite_width.map_or(Val::Auto, Val::Px),
+                    height: Val::Auto,
+                    margin: UiRect::bottom(Val::Px(10.0)),
+                    ..default()
+                },
+                image: UiImage {
+                    color: stats.tint,
+                    ..asset_server.load(stats.sprite).into()
+                },
+                ..default()
+            },
+            EnemySprite,
+        ));
+
+        parent.spawn(TextBundle::from_section(
+            stats.name,
+            TextStyle {
+                font: Handle::default(),
+                font_size: 30.0,
+                color: Color::WHITE,
+            },
+        ));
+
+        // Health & Block Row
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Row,
+                    justify_content: JustifyContent::Center,
+                    column_gap: Val::Px(10.0),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|row| {
+                row.spawn((
+                    TextBundle::from_section(
+                        format!("HP: {}/{}", stats.hp, stats.hp),
+                        TextStyle {
+                            font: Handle::default(),
+                            font_size: 20.0,
+                            color: Color::srgb(0.9, 0.3, 0.3),
+                        },
+                    ),
+                    EnemyHealthText,
+                ));
+                row.spawn((
+                    TextBundle::from_section(
+                        "Block: 0",
+                        TextStyle {
+                            font: Handle::default(),
+                            font_size: 20.0,
+                            color: Color::srgb(0.5, 0.5, 1.0),
+                        },
+                    ),
+                    EnemyBlockText,
+                ));
+            });
+
+        // Status
+        parent.spawn((
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Row,
+                    align_items: AlignItems::Center,
+                    min_height: Val::Px(20.0),
+                    margin: UiRect::top(Val::Px(5.0)),
+                    ..default()
+                },
+                ..default()
+            },
+            EnemyStatusText,
+        ));
+
+        parent.spawn((
+            TextBundle::from_section(
+                "Planning...",
+                TextStyle {
+                    font: Handle::default(),
+                    font_size: 20.0,
+                    color: Color::srgb(1.0, 1.0, 0.0),
+                },
+            ),
+            EnemyIntentText,
+        ));
+    });
+
+    entity_cmds.id()
+}
+
+pub const BURNING_HP_MULTIPLIER: f32 = 1.25;
+pub const BURNING_STRENGTH: i32 = 3;
+pub const BURNING_THORNS: i32 = 3;
+
+pub fn burning_buff_description(buff: BurningBuff) -> String {
+    match buff {
+        BurningBuff::Vigor => "+25% Max HP".to_string(),
+        BurningBuff::Strength => format!("Starts with {} Strength", BURNING_STRENGTH),
+        BurningBuff::Thorns => format!("{} Thorns", BURNING_THORNS),
+    }
+}
+
+// Powers up an enemy fighting on the burning elite node
+pub fn apply_burning_buff(
+    commands: &mut Commands,
+    entity: Entity,
+    kind: EnemyKind,
+    buff: BurningBuff,
+    initial_weak: i32,
+) {
+    let mut entity_cmds = commands.entity(entity);
+    entity_cmds.insert(BurningElite { buff });
+
+    match buff {
+        BurningBuff::Vigor => {
+            let hp = (enemy_stats(kind).hp as f32 * BURNING_HP_MULTIPLIER) as i32;
+            entity_cmds.insert(Health {
+                current: hp,
+                max: hp,
+            });
+        }
+        BurningBuff::Strength => {
+            entity_cmds.insert(StatusStore {
+                weak: initial_weak,
+                strength: BURNING_STRENGTH,
+                ..default()
+            });
+        }
+        BurningBuff::Thorns => {
+            entity_cmds.insert(Thorns {
+                amount: BURNING_THORNS,
+            });
+        }
+    }
+}
+
+// Spawns summoned minions into free slots. They first act on the next enemy turn and are
+// given their opening intent with everyone else's when the player's turn starts.
+pub fn summon_enemies(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    kinds: &[EnemyKind],
+    occupied: &[usize],
+) -> Vec<Entity> {
+    let mut spawned = Vec::new();
+    let free_slots = free_enemy_slots(occupied);
+
+    for (kind, slot) in kinds.iter().zip(free_slots) {
+        let entity = spawn_enemy(commands, asset_server, *kind, slot, 0);
+        commands.entity(entity).insert(Minion);
+        println!("{:?} joins the fight!", kind);
+        spawned.push(entity);
+    }
+
+    if spawned.len() < kinds.len() {
+        println!("No room for more enemies!");
+    }
+
+    spawned
+}
+
+// Phase scripts for multi-phase encounters. Phases are entered in order, each changing the
+// boss's art (or re-tinting it until phase art exists) and the backdrop.
+pub fn boss_phases_for(kind: EnemyKind) -> Option<BossPhases> {
+    let phases = match kind {
+        EnemyKind::Dragon => vec![
+            BossPhase {
+                name: "Enraged".to_string(),
+                trigger: PhaseTrigger::HealthBelow(0.5),
+                sprite: None,
+                sprite_tint: Some(Color::srgb(1.0, 0.55, 0.4)),
+                background: Some("images/backgrounds/Battlefield.jpg".to_string()),
+                clear_debuffs: true,
+                gain_block: 20,
+            },
+            BossPhase {
+                name: "Last Flight".to_string(),
+                trigger: PhaseTrigger::HealthBelow(0.2),
+                sprite: None,
+                sprite_tint: Some(Color::srgb(1.0, 0.25, 0.2)),
+                background: Some("images/backgrounds/DragonLayer.jpg".to_string()),
+                clear_debuffs: false,
+                gain_block: 0,
+            },
+        ],
+        EnemyKind::DarkKnight => vec![BossPhase {
+            name: "Unholy Resolve".to_string(),
+            trigger: PhaseTrigger::TurnReached(3),
+            sprite: None,
+            sprite_tint: Some(Color::srgb(0.7, 0.45, 1.0)),
+            background: Some("images/backgrounds/PoisonCave.jpg".to_string()),
+            clear_debuffs: true,
+            gain_block: 15,
+        }],
+        _ => return None,
+    };
+
+    Some(BossPhases {
+        phases,
+        current: 0,
+        turns_taken: 0,
+    })
+}
+
+pub fn boss_phase_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut boss_query: Query<
+        (
+            Entity,
+            &Enemy,
+            &Health,
+            &mut Block,
+            &mut StatusStore,
+            &mut NextEnemyMove,
+            &EnemyMoveHistory,
+            &mut BossPhases,
+            &Children,
+        ),
+        (Without<AttackingEnemy>, Without<Player>),
+    >,
+    ally_query: Query<(Entity, &Health), With<Enemy>>,
+    player_query: Query<(&Block, &StatusStore), With<Player>>,
+    mut sprite_query: Query<&mut UiImage, With<EnemySprite>>,
+    mut background_query: Query<&mut Handle<Image>, (With<SceneBackground>, With<BattleEntity>)>,
+) {
+    for (
+        entity,
+        enemy,
+        health,
+        mut block,
+        mut status,
+        mut next_move,
+        history,
+        mut phases,
+        children,
+    ) in boss_query.iter_mut()
+    {
+        if health.current <= 0 || phases.current >= phases.phases.len() {
+            continue;
+        }
+
+        let phase = phases.phases[phases.current].clone();
+        let triggered = match phase.trigger {
+            PhaseTrigger::HealthBelow(ratio) => (health.current as f32) < health.max as f32 * ratio,
+            PhaseTrigger::TurnReached(turn) => phases.turns_taken >= turn,
+        };
+        if !triggered {
+            continue;
+        }
+
+        phases.current += 1;
+        println!("{:?} enters phase: {}", enemy.kind, phase.name);
+
+        if phase.clear_debuffs {
+            status.poison = 0;
+            status.weak = 0;
+            status.stun = 0;
+            status.burning = 0;
+            status.frozen = 0;
+            status.vulnerable = 0;
+            status.frail = 0;
+        }
+        if phase.gain_block > 0 {
+            block.value += phase.gain_block;
+        }
+
+        for &child in children.iter() {
+            let Ok(mut image) = sprite_query.get_mut(child) else {
+                continue;
+            };
+            match (&phase.sprite, phase.sprite_tint) {
+                (Some(sprite), tint) => {
+                    image.texture = asset_server.load(sprite.clone());
+                    image.color = tint.unwrap_or(Color::WHITE);
                 }
+                (None, Some(tint)) => image.color = tint,
+                (None, None) => {}
             }
         }
-        EnemyKind::Orc => {
-            if roll < 50 {
-                NextEnemyMove {
-                    name: "Smashes".to_string(),
-                    damage: 12,
-                    block: 0,
-                    poison: 0,
-                    weak: 0,
-                    steal_gold: 0,
-                    is_charging: false,
-                }
-            } else if roll < 80 {
-                NextEnemyMove {
-                    name: "Heavy Blow".to_string(),
-                    damage: 18,
-                    block: 0,
-                    poison: 0,
-                    weak: 0,
-                    steal_gold: 0,
-                    is_charging: false,
-                }
-            } else {
-                NextEnemyMove {
-                    name: "Defends".to_string(),
-                    damage: 0,
-                    block: 15,
-                    poison: 0,
-                    weak: 0,
-                    steal_gold: 0,
-                    is_charging: false,
-                }
+
+        if let Some(background) = &phase.background {
+            for mut handle in &mut background_query {
+                *handle = asset_server.load(background.clone());
             }
         }
-        EnemyKind::DarkKnight => {
-            if roll < 40 {
-                NextEnemyMove {
-                    name: "Executes".to_string(),
-                    damage: 18,
-                    block: 0,
-                    poison: 0,
-                    weak: 0,
-                    steal_gold: 0,
-                    is_charging: false,
-                }
-            } else if roll < 70 {
-                NextEnemyMove {
-                    name: "Obliterates".to_string(),
-                    damage: 25,
-                    block: 0,
-                    poison: 0,
-                    weak: 0,
-                    steal_gold: 0,
-                    is_charging: false,
-                }
+
+        // The move table changes with the phase, so re-plan the intent
+        let (player_block, player_weak) = player_query
+            .get_single()
+            .map_or((0, 0), |(block, status)| (block.value, status.weak));
+        let ctx = EnemyAiContext {
+            hp_ratio: health.current as f32 / health.max as f32,
+            phase: phases.current,
+            player_block,
+            player_weak,
+            free_slots: MAX_ENEMY_SLOTS.saturating_sub(ally_query.iter().count()),
+            ally_hp_ratio: lowest_ally_hp_ratio(entity, ally_query.iter()),
+            self_debuffed: is_debuffed(&status),
+        };
+        *next_move = plan_enemy_move(enemy.kind, &history.moves, &ctx);
+
+        // Phase Banner
+        commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        width: Val::Percent(100.0),
+                        top: Val::Percent(35.0),
+                        padding: UiRect::all(Val::Px(20.0)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: Color::srgba(0.0, 0.0, 0.0, 0.7).into(),
+                    z_index: ZIndex::Global(500),
+                    ..default()
+                },
+                PhaseBannerUi {
+                    timer: Timer::from_seconds(2.0, TimerMode::Once),
+                },
+                BattleEntity,
+            ))
+            .with_children(|p| {
+                p.spawn(TextBundle::from_section(
+                    format!("{:?} - {}", enemy.kind, phase.name).to_uppercase(),
+                    TextStyle {
+                        font: Handle::default(),
+                        font_size: 48.0,
+                        color: Color::srgb(1.0, 0.3, 0.2),
+                    },
+                ));
+            });
+    }
+}
+
+pub fn update_phase_banner_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut PhaseBannerUi)>,
+) {
+    for (entity, mut banner) in &mut query {
+        banner.timer.tick(time.delta());
+        if banner.timer.finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+// Every move an enemy can perform, looked up by name from the AI patterns
+pub fn enemy_move(name: &str) -> NextEnemyMove {
+    let mut next_move = NextEnemyMove {
+        name: name.to_string(),
+        ..default()
+    };
+    match name {
+        "Stabs" => next_move.damage = 5,
+        "Thieve" => {
+            next_move.damage = 3;
+            next_move.steal_gold = 10;
+        }
+        "Call Reinforcements" => next_move.summon = vec![EnemyKind::Goblin],
+        "Hex" => next_move.weak = 2,
+        "Zap" => next_move.damage = 6,
+        "Nips" => next_move.damage = 4,
+        "Ember Spit" => {
+            next_move.damage = 3;
+            next_move.poison = 1;
+        }
+        "Smashes" => next_move.damage = 12,
+        "Heavy Blow" => next_move.damage = 18,
+        "Defends" => next_move.block = 15,
+        "Executes" => next_move.damage = 18,
+        "Obliterates" => next_move.damage = 25,
+        "Dark Magic" => {
+            next_move.damage = 10;
+            next_move.poison = 3;
+        }
+        "Soul Rend" => {
+            next_move.damage = 14;
+            next_move.weak = 2;
+        }
+        "Dark Aegis" => {
+            next_move.damage = 8;
+            next_move.block = 20;
+        }
+        "Incinerates" => next_move.damage = 25,
+        "Roar" => {
+            next_move.damage = 15;
+            next_move.weak = 2;
+            next_move.vulnerable = 1;
+        }
+        "Deep Breath" => next_move.is_charging = true,
+        "Fire Breath" => next_move.damage = 50,
+        "Inferno" => next_move.damage = 30,
+        "Tail Sweep" => {
+            next_move.damage = 12;
+            next_move.block = 12;
+            next_move.weak = 1;
+        }
+        "Call the Brood" => next_move.summon = vec![EnemyKind::Whelp, EnemyKind::Whelp],
+        "Flurry" => {
+            next_move.damage = 3;
+            next_move.hits = 3;
+        }
+        "Mend" => next_move.heal_ally = 10,
+        "Warding Chant" => next_move.block_allies = 8,
+        "Bellow" => next_move.strength = 3,
+        "Cursed Blade" => {
+            next_move.damage = 8;
+            next_move.wounds = 2;
+        }
+        "Crippling Strike" => {
+            next_move.damage = 10;
+            next_move.frail = 2;
+        }
+        "Dark Pact" => {
+            next_move.cleanse = true;
+            next_move.strength = 2;
+        }
+        "Rake" => {
+            next_move.damage = 6;
+            next_move.hits = 3;
+        }
+        "Devour" => {
+            next_move.damage = 20;
+            next_move.heal = 20;
+        }
+        _ => {
+            // A typo in a move list would otherwise be a silent wasted turn
+            debug_assert!(false, "Unknown enemy move: {}", name);
+            println!("Unknown enemy move: {}", name);
+        }
+    }
+    // Heavy blows are dodged with an arrow key rather than braced on the ring
+    if matches!(
+        name,
+        "Smashes" | "Heavy Blow" | "Executes" | "Obliterates" | "Fire Breath" | "Incinerates"
+    ) {
+        next_move.reflex = ReflexVisualType::DirectionalPrompt;
+    }
+    // Flurries are defended with one tap per hit
+    if next_move.hits > 1 {
+        next_move.reflex = ReflexVisualType::MultiTap;
+    }
+    next_move
+}
+
+// Battle state an enemy looks at when planning its next intent
+#[derive(Debug, Clone)]
+pub struct EnemyAiContext {
+    pub hp_ratio: f32,
+    pub phase: usize,
+    pub player_block: i32,
+    pub player_weak: i32,
+    pub free_slots: usize,
+    pub ally_hp_ratio: f32, // Most wounded living ally, 1.0 when alone
+    pub self_debuffed: bool,
+}
+
+pub enum AiPattern {
+    // Moves in a fixed order, one per turn
+    Cycle(&'static [&'static str]),
+    // Weighted roll that never picks the same move more than `max_repeats` times in a row
+    Weighted {
+        moves: &'static [(&'static str, u32)],
+        max_repeats: usize,
+    },
+}
+
+pub fn ai_pattern(kind: EnemyKind, phase: usize) -> AiPattern {
+    match (kind, phase) {
+        (EnemyKind::Goblin, _) => AiPattern::Weighted {
+            moves: &[("Stabs", 45), ("Thieve", 30), ("Flurry", 25)],
+            max_repeats: 2,
+        },
+        (EnemyKind::GoblinShaman, _) => {
+            AiPattern::Cycle(&["Hex", "Call Reinforcements", "Zap", "Warding Chant"])
+        }
+        (EnemyKind::Whelp, _) => AiPattern::Cycle(&["Nips", "Nips", "Ember Spit"]),
+        (EnemyKind::Orc, _) => AiPattern::Weighted {
+            moves: &[
+                ("Smashes", 45),
+                ("Heavy Blow", 25),
+                ("Defends", 15),
+                ("Bellow", 15),
+            ],
+            max_repeats: 2,
+        },
+        (EnemyKind::DarkKnight, 0) => {
+            AiPattern::Cycle(&["Executes", "Dark Magic", "Cursed Blade", "Obliterates"])
+        }
+        (EnemyKind::DarkKnight, _) => AiPattern::Weighted {
+            moves: &[
+                ("Obliterates", 40),
+                ("Dark Aegis", 30),
+                ("Crippling Strike", 30),
+            ],
+            max_repeats: 2,
+        },
+        (EnemyKind::Dragon, 0) => AiPattern::Cycle(&["Incinerates", "Rake", "Roar", "Deep Breath"]),
+        (EnemyKind::Dragon, 1) => AiPattern::Cycle(&["Inferno", "Tail Sweep", "Deep Breath"]),
+        (EnemyKind::Dragon, _) => AiPattern::Cycle(&["Inferno", "Devour", "Deep Breath"]),
+    }
+}
+
+// Situational overrides that take priority over the pattern
+fn conditional_move(
+    kind: EnemyKind,
+    history: &[String],
+    ctx: &EnemyAiContext,
+) -> Option<&'static str> {
+    let last = history.last().map(|m| m.as_str());
+    let used_recently =
+        |name: &str, turns: usize| history.iter().rev().take(turns).any(|m| m == name);
+
+    match (kind, ctx.phase) {
+        (EnemyKind::GoblinShaman, _) if ctx.ally_hp_ratio < 0.5 && last != Some("Mend") => {
+            Some("Mend")
+        }
+        (EnemyKind::DarkKnight, _) if ctx.self_debuffed && !used_recently("Dark Pact", 3) => {
+            Some("Dark Pact")
+        }
+        (EnemyKind::Orc, _) if ctx.hp_ratio < 0.4 && last != Some("Defends") => Some("Defends"),
+        (EnemyKind::DarkKnight, 0) if ctx.player_block >= 10 && last != Some("Dark Magic") => {
+            // Poison ignores all that block
+            Some("Dark Magic")
+        }
+        (EnemyKind::DarkKnight, _) if ctx.phase >= 1 => {
+            if ctx.player_weak == 0 && last != Some("Soul Rend") {
+                Some("Soul Rend")
+            } else if ctx.hp_ratio < 0.3 && last != Some("Dark Aegis") {
+                Some("Dark Aegis")
             } else {
-                NextEnemyMove {
-                    name: "Dark Magic".to_string(),
-                    damage: 10,
-                    block: 0,
-                    poison: 3,
-                    weak: 0,
-                    steal_gold: 0,
-                    is_charging: false,
-                }
+                None
             }
         }
-        EnemyKind::Dragon => {
-            if roll < 40 {
-                NextEnemyMove {
-                    name: "Incinerates".to_string(),
-                    damage: 25,
-                    block: 0,
-                    poison: 0,
-                    weak: 0,
-                    steal_gold: 0,
-                    is_charging: false,
-                }
-            } else if roll < 70 {
-                NextEnemyMove {
-                    name: "Roar".to_string(),
-                    damage: 15,
-                    block: 0,
-                    poison: 0,
-                    weak: 2,
-                    steal_gold: 0,
-                    is_charging: false,
-                }
-            } else {
-                NextEnemyMove {
-                    name: "Deep Breath".to_string(),
-                    damage: 0,
-                    block: 0,
-                    poison: 0,
-                    weak: 0,
-                    steal_gold: 0,
-                    is_charging: true,
-                }
+        (EnemyKind::Dragon, phase)
+            if phase >= 1 && ctx.free_slots >= 2 && !used_recently("Call the Brood", 4) =>
+        {
+            Some("Call the Brood")
+        }
+        _ => None,
+    }
+}
+
+// Swaps out cycle moves that would be wasted in the current situation
+fn adjust_cycle_move(name: &'static str, ctx: &EnemyAiContext) -> &'static str {
+    match name {
+        "Hex" if ctx.player_weak > 0 => "Zap",
+        "Roar" if ctx.player_weak > 0 => "Incinerates",
+        "Call Reinforcements" if ctx.free_slots == 0 => "Zap",
+        _ => name,
+    }
+}
+
+fn weighted_pick(
+    moves: &[(&'static str, u32)],
+    history: &[String],
+    max_repeats: usize,
+) -> &'static str {
+    let repeated = |name: &str| {
+        history.len() >= max_repeats && history.iter().rev().take(max_repeats).all(|m| m == name)
+    };
+    let allowed: Vec<&(&'static str, u32)> =
+        moves.iter().filter(|(name, _)| !repeated(name)).collect();
+
+    let total: u32 = allowed.iter().map(|(_, weight)| weight).sum();
+    let mut roll = thread_rng().gen_range(0..total.max(1));
+    for (name, weight) in &allowed {
+        if roll < *weight {
+            return name;
+        }
+        roll -= weight;
+    }
+    moves[0].0
+}
+
+pub fn plan_enemy_move(kind: EnemyKind, history: &[String], ctx: &EnemyAiContext) -> NextEnemyMove {
+    // A charged breath is always released on the following turn
+    if history.last().map(|m| m.as_str()) == Some("Deep Breath") {
+        return enemy_move("Fire Breath");
+    }
+
+    if let Some(name) = conditional_move(kind, history, ctx) {
+        return enemy_move(name);
+    }
+
+    let name = match ai_pattern(kind, ctx.phase) {
+        AiPattern::Cycle(moves) => adjust_cycle_move(moves[history.len() % moves.len()], ctx),
+        AiPattern::Weighted { moves, max_repeats } => weighted_pick(moves, history, max_repeats),
+    };
+    enemy_move(name)
+}
+
+// Damage per hit the player will actually face from this move, shown in the intent
+pub fn enemy_attack_damage(
+    next_move: &NextEnemyMove,
+    status: &StatusStore,
+    target: &StatusStore,
+) -> i32 {
+    let mut damage = next_move.damage;
+    if damage > 0 {
+        damage += status.strength;
+    }
+    if damage > 0 && status.weak > 0 {
+        damage = (damage as f32 * 0.75) as i32;
+    }
+    if damage > 0 && status.frozen > 0 {
+        damage = (damage as f32 * 0.75) as i32;
+    }
+    if damage > 0 && target.vulnerable > 0 {
+        damage = (damage as f32 * 1.5) as i32;
+    }
+    damage.max(0)
+}
+
+pub fn enemy_hit_count(next_move: &NextEnemyMove) -> i32 {
+    next_move.hits.max(1)
+}
+
+pub fn is_debuffed(status: &StatusStore) -> bool {
+    status.poison > 0
+        || status.weak > 0
+        || status.burning > 0
+        || status.frozen > 0
+        || status.vulnerable > 0
+        || status.frail > 0
+}
+
+// Lowest HP ratio among the other living enemies
+pub fn lowest_ally_hp_ratio<'a>(
+    entity: Entity,
+    allies: impl Iterator<Item = (Entity, &'a Health)>,
+) -> f32 {
+    allies
+        .filter(|(ally, health)| *ally != entity && health.current > 0)
+        .map(|(_, health)| health.current as f32 / health.max as f32)
+        .fold(1.0, f32::min)
+}
+
+pub fn plan_enemy_intents_system(
+    mut enemy_query: Query<
+        (
+            Entity,
+            &Enemy,
+            &Health,
+            &StatusStore,
+            &EnemyMoveHistory,
+            Option<&BossPhases>,
+            &mut NextEnemyMove,
+        ),
+        Without<Player>,
+    >,
+    ally_query: Query<(Entity, &Health), With<Enemy>>,
+    player_query: Query<(&Block, &StatusStore), With<Player>>,
+) {
+    let (player_block, player_weak) = player_query
+        .get_single()
+        .map_or((0, 0), |(block, status)| (block.value, status.weak));
+    let free_slots = MAX_ENEMY_SLOTS.saturating_sub(ally_query.iter().count());
+
+    for (entity, enemy, health, status, history, phases, mut next_move) in enemy_query.iter_mut() {
+        if health.current <= 0 {
+            continue;
+        }
+        let ctx = EnemyAiContext {
+            hp_ratio: health.current as f32 / health.max as f32,
+            phase: phases.map_or(0, |p| p.current),
+            player_block,
+            player_weak,
+            free_slots,
+            ally_hp_ratio: lowest_ally_hp_ratio(entity, ally_query.iter()),
+            self_debuffed: is_debuffed(status),
+        };
+        *next_move = plan_enemy_move(enemy.kind, &history.moves, &ctx);
+    }
+}
+
+pub fn intent_summary(
+    next_move: &NextEnemyMove,
+    status: &StatusStore,
+    target: &StatusStore,
+) -> String {
+    if status.stun > 0 {
+        return "Stunned".to_string();
+    }
+    let damage = enemy_attack_damage(next_move, status, target);
+    let hits = enemy_hit_count(next_move);
+    if damage > 0 && hits > 1 {
+        format!("{} ({}x{})", next_move.name, damage, hits)
+    } else if damage > 0 {
+        format!("{} ({})", next_move.name, damage)
+    } else {
+        next_move.name.clone()
+    }
+}
+
+pub fn update_enemy_intent_text_system(
+    enemy_query: Query<
+        (Entity, Ref<NextEnemyMove>, Ref<StatusStore>),
+        (With<Enemy>, Without<AttackingEnemy>),
+    >,
+    player_query: Query<Ref<StatusStore>, (With<Player>, Without<Enemy>)>,
+    mut intent_text_query: Query<(&Parent, &mut Text), With<EnemyIntentText>>,
+) {
+    let Ok(player_status) = player_query.get_single() else {
+        return;
+    };
+    for (entity, next_move, status) in enemy_query.iter() {
+        if !(next_move.is_changed() || status.is_changed() || player_status.is_changed()) {
+            continue;
+        }
+        for (parent, mut text) in &mut intent_text_query {
+            if parent.get() == entity {
+                text.sections[0].value = intent_summary(&next_move, &status, &player_status);
             }
         }
     }
@@ -0,0 +1,348 @@
+use std::collections::HashMap;
+
+use rand::seq::SliceRandom;
+use rand::{Rng, thread_rng};
+use serde::Deserialize;
+
+use crate::acts::encounter_for_node;
+use crate::components::*;
+use crate::deck_services::{apply_deck_service, can_apply_service};
+use crate::item_cards::card_by_name;
+use crate::item_potions::{Potion, all_potions, get_potion_name};
+use crate::item_relics::{Relic, droppable_relics, get_relic_name};
+use crate::resources::{Deck, Encounter, EncounterRewards, EventHistory, EventLibrary};
+
+const EVENT_DATA: &str = include_str!("../assets/data/events.json");
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct EventDefinition {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub acts: Vec<usize>, // Empty means every act
+    #[serde(default)]
+    pub once_per_run: bool,
+    pub start: String,
+    pub pages: HashMap<String, EventPage>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct EventPage {
+    pub text: String,
+    #[serde(default)]
+    pub image: Option<String>,
+    pub choices: Vec<EventChoice>,
+}
+
+// A choice applies its outcomes, then either rolls one of `random` or moves on to `next`.
+// A choice with neither leaves the event.
+#[derive(Deserialize, Debug, Clone)]
+pub struct EventChoice {
+    pub text: String,
+    #[serde(default)]
+    pub requires: Vec<Requirement>,
+    #[serde(default)]
+    pub outcomes: Vec<Outcome>,
+    #[serde(default)]
+    pub random: Vec<RandomOutcome>,
+    #[serde(default)]
+    pub next: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct RandomOutcome {
+    pub weight: u32,
+    #[serde(default)]
+    pub outcomes: Vec<Outcome>,
+    #[serde(default)]
+    pub next: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Requirement {
+    MinGold { amount: i32 },
+    MinHp { amount: i32 },
+    HasRelic { relic: Relic },
+    HasCard { card: String },
+    Class { class: CharacterClass },
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Outcome {
+    Heal { amount: i32 },
+    Damage { amount: i32 },
+    MaxHp { amount: i32 },
+    Gold { amount: i32 },
+    AddCard { card: String },
+    RemoveCard { card: Option<String> }, // Random card if no name is given
+    UpgradeCard { card: Option<String> }, // Random upgradable card if no name is given
+    TransformCard { card: Option<String> }, // Random card if no name is given
+    GainRelic { relic: Option<Relic> },  // Random unowned relic if none is given
+    GainPotion { potion: Option<Potion> }, // Random potion if none is given
+    ChooseCard { service: DeckService }, // Opens the card picker
+    Fight(EventFight),
+}
+
+// A battle started by an event. Without a lineup the act's normal encounter pool is used.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct EventFight {
+    pub enemies: Vec<EnemyKind>,
+    pub background: Option<String>,
+    pub rewards: Option<EncounterRewards>,
+}
+
+pub fn load_event_library() -> EventLibrary {
+    let events: Vec<EventDefinition> = match serde_json::from_str(EVENT_DATA) {
+        Ok(events) => events,
+        Err(error) => {
+            println!("Failed to parse events: {}", error);
+            Vec::new()
+        }
+    };
+
+    let events = events
+        .into_iter()
+        .filter(|event| match validate_event(event) {
+            Ok(()) => true,
+            Err(error) => {
+                println!("Skipping event {}: {}", event.id, error);
+                false
+            }
+        })
+        .collect();
+    EventLibrary { events }
+}
+
+// Checks that every page link points to a page and every named card exists
+pub fn validate_event(event: &EventDefinition) -> Result<(), String> {
+    if !event.pages.contains_key(&event.start) {
+        return Err(format!("missing start page {}", event.start));
+    }
+    for (page_id, page) in &event.pages {
+        if page.choices.is_empty() {
+            return Err(format!("page {} has no choices", page_id));
+        }
+        for choice in &page.choices {
+            let links = choice.next.iter().chain(
+                choice
+                    .random
+                    .iter()
+                    .filter_map(|random| random.next.as_ref()),
+            );
+            for link in links {
+                if !event.pages.contains_key(link) {
+                    return Err(format!("page {} links to missing page {}", page_id, link));
+                }
+            }
+            let outcomes = choice
+                .outcomes
+                .iter()
+                .chain(choice.random.iter().flat_map(|random| &random.outcomes));
+            for outcome in outcomes {
+                if let Outcome::AddCard { card }
+                | Outcome::RemoveCard { card: Some(card) }
+                | Outcome::UpgradeCard { card: Some(card) }
+                | Outcome::TransformCard { card: Some(card) } = outcome
+                    && card_by_name(card).is_none()
+                {
+                    return Err(format!("unknown card {}", card));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// Gold and HP a choice charges, split from its other outcomes. Choices that open the card
+// picker only pay these once a card is actually picked.
+pub fn split_picker_costs(outcomes: &[Outcome]) -> (Vec<Outcome>, i32, i32) {
+    let mut rest = Vec::new();
+    let (mut gold, mut hp) = (0, 0);
+    for outcome in outcomes {
+        match outcome {
+            Outcome::Gold { amount } if *amount < 0 => gold -= amount,
+            Outcome::Damage { amount } => hp += amount,
+            _ => rest.push(outcome.clone()),
+        }
+    }
+    (rest, gold, hp)
+}
+
+// Picks an event for the act, skipping once-per-run events that were already seen
+pub fn pick_event<'a>(
+    library: &'a EventLibrary,
+    act: usize,
+    history: &EventHistory,
+) -> Option<&'a EventDefinition> {
+    let pool: Vec<&EventDefinition> = library
+        .events
+        .iter()
+        .filter(|event| event.acts.is_empty() || event.acts.contains(&act))
+        .filter(|event| !(event.once_per_run && history.seen.contains(&event.id)))
+        .collect();
+    pool.choose(&mut thread_rng()).copied()
+}
+
+// `roll` is in 0..total weight
+pub fn pick_random_outcome(random: &[RandomOutcome], roll: u32) -> Option<&RandomOutcome> {
+    let mut roll = roll;
+    for option in random {
+        if roll < option.weight {
+            return Some(option);
+        }
+        roll -= option.weight;
+    }
+    random.last()
+}
+
+pub fn roll_random_outcome(random: &[RandomOutcome]) -> Option<&RandomOutcome> {
+    let total: u32 = random.iter().map(|option| option.weight).sum();
+    if total == 0 {
+        return random.first();
+    }
+    pick_random_outcome(random, thread_rng().gen_range(0..total))
+}
+
+pub fn fight_encounter(fight: &EventFight, act: usize, battles_fought: usize) -> Encounter {
+    let mut encounter = encounter_for_node(act, NodeType::Battle, None, battles_fought);
+    if !fight.enemies.is_empty() {
+        encounter.enemies = fight.enemies.clone();
+    }
+    if let Some(background) = &fight.background {
+        encounter.background = background.clone();
+    }
+    if let Some(rewards) = &fight.rewards {
+        encounter.rewards = rewards.clone();
+    }
+    encounter
+}
+
+pub fn requirement_text(requirement: &Requirement) -> String {
+    match requirement {
+        Requirement::MinGold { amount } => format!("{} Gold", amount),
+        Requirement::MinHp { amount } => format!("{} HP", amount),
+        Requirement::HasRelic { relic } => get_relic_name(relic),
+        Requirement::HasCard { card } => card.clone(),
+        Requirement::Class { class } => format!("{:?}", class),
+    }
+}
+
+pub fn meets_requirement(
+    requirement: &Requirement,
+    class: CharacterClass,
+    health: &Health,
+    gold: &Gold,
+    deck: &Deck,
+    relics: &RelicStore,
+) -> bool {
+    match requirement {
+        Requirement::MinGold { amount } => gold.amount >= *amount,
+        Requirement::MinHp { amount } => health.current >= *amount,
+        Requirement::HasRelic { relic } => relics.relics.contains(relic),
+        Requirement::HasCard { card } => deck.cards.iter().any(|c| c.name == *card),
+        Requirement::Class { class: required } => class == *required,
+    }
+}
+
+// Everything on the player an event can read or change
+pub struct EventContext<'a> {
+    pub class: CharacterClass,
+    pub health: &'a mut Health,
+    pub gold: &'a mut Gold,
+    pub deck: &'a mut Deck,
+    pub relics: &'a mut RelicStore,
+    pub potions: &'a mut PotionStore,
+}
+
+impl EventContext<'_> {
+    pub fn meets_all(&self, requirements: &[Requirement]) -> bool {
+        requirements.iter().all(|requirement| {
+            meets_requirement(
+                requirement,
+                self.class,
+                self.health,
+                self.gold,
+                self.deck,
+                self.relics,
+            )
+        })
+    }
+
+    fn find_card(&self, name: &Option<String>, filter: impl Fn(&Card) -> bool) -> Option<usize> {
+        let candidates: Vec<usize> = self
+            .deck
+            .cards
+            .iter()
+            .enumerate()
+            .filter(|(_, card)| name.as_ref().is_none_or(|name| card.name == *name))
+            .filter(|(_, card)| filter(card))
+            .map(|(index, _)| index)
+            .collect();
+        candidates.choose(&mut thread_rng()).copied()
+    }
+
+    fn apply_service(&mut self, name: &Option<String>, service: DeckService) {
+        if let Some(index) = self.find_card(name, |card| can_apply_service(service, card))
+            && let Some(message) = apply_deck_service(self.deck, index, service, self.class)
+        {
+            println!("{}", message);
+        }
+    }
+
+    // Fight and card picker outcomes are left to the caller
+    pub fn apply(&mut self, outcome: &Outcome) {
+        match outcome {
+            Outcome::Heal { amount } => {
+                self.health.current = (self.health.current + amount).min(self.health.max);
+                println!("Healed {} HP", amount);
+            }
+            Outcome::Damage { amount } => {
+                self.health.current -= amount;
+                println!("Lost {} HP", amount);
+            }
+            Outcome::MaxHp { amount } => {
+                self.health.max += amount;
+                self.health.current = (self.health.current + (*amount).max(0)).min(self.health.max);
+                println!("Max HP changed by {}", amount);
+            }
+            Outcome::Gold { amount } => {
+                self.gold.amount = (self.gold.amount + amount).max(0);
+                println!("Gold changed by {}", amount);
+            }
+            Outcome::AddCard { card } => {
+                if let Some(card) = card_by_name(card) {
+                    println!("Added {} to deck", card.name);
+                    self.deck.cards.push(card);
+                }
+            }
+            Outcome::RemoveCard { card } => self.apply_service(card, DeckService::Remove),
+            Outcome::UpgradeCard { card } => self.apply_service(card, DeckService::Upgrade),
+            Outcome::TransformCard { card } => self.apply_service(card, DeckService::Transform),
+            Outcome::GainRelic { relic } => {
+                let relic = relic.or_else(|| {
+                    droppable_relics()
+                        .into_iter()
+                        .filter(|r| !self.relics.relics.contains(r))
+                        .collect::<Vec<_>>()
+                        .choose(&mut thread_rng())
+                        .copied()
+                });
+                if let Some(relic) = relic {
+                    println!("Obtained {}", get_relic_name(&relic));
+                    self.relics.relics.push(relic);
+                }
+            }
+            Outcome::GainPotion { potion } => {
+                let potion = potion.or_else(|| all_potions().choose(&mut thread_rng()).copied());
+                if let Some(potion) = potion {
+                    println!("Obtained {}", get_potion_name(&potion));
+                    self.potions.potions.push(potion);
+                }
+            }
+            Outcome::ChooseCard { .. } | Outcome::Fight(_) => {}
+        }
+    }
+}
@@ -1,9 +1,10 @@
-use crate::components::{Card, CharacterClass, Rarity, SpellElement};
+use crate::components::{Card, CharacterClass, Finisher, Rarity, ReflexVisualType, SpellElement};
 use bevy::prelude::*;
-use rand::{Rng, thread_rng};
 
 pub fn get_card_visuals(card: &Card) -> (Color, Color) {
-    let bg_color = if card.apply_poison > 0 || card.apply_weak > 0 {
+    let bg_color = if card.unplayable {
+        Color::srgb(0.35, 0.1, 0.05) // Dark rust theme (Status cards)
+    } else if card.apply_poison > 0 || card.apply_weak > 0 {
         Color::srgb(0.2, 0.0, 0.3) // Purple theme (Status)
     } else if card.apply_stun > 0 {
         Color::srgb(0.4, 0.4, 0.0) // Yellow/Gold theme (Stun)
@@ -40,6 +41,10 @@ pub fn strike() -> Card {
         element: SpellElement::Neutral,
         combo_points_granted: 1,
         finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::LinearSlider,
     }
 }
 
@@ -59,6 +64,10 @@ pub fn defend() -> Card {
         element: SpellElement::Neutral,
         combo_points_granted: 0,
         finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::LinearSlider,
     }
 }
 
@@ -78,6 +87,10 @@ pub fn bash() -> Card {
         element: SpellElement::Neutral,
         combo_points_granted: 1,
         finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::LinearSlider,
     }
 }
 
@@ -97,6 +110,10 @@ pub fn iron_wave() -> Card {
         element: SpellElement::Neutral,
         combo_points_granted: 1,
         finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::LinearSlider,
     }
 }
 
@@ -116,6 +133,10 @@ pub fn deadly_poison() -> Card {
         element: SpellElement::Neutral,
         combo_points_granted: 0,
         finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::LinearSlider,
     }
 }
 
@@ -136,6 +157,10 @@ pub fn heavy_blade() -> Card {
         element: SpellElement::Neutral,
         combo_points_granted: 0,
         finisher_combo_cost: 3,
+        finisher: Finisher::DoubleDamage,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::ChargeMeter,
     }
 }
 
@@ -155,6 +180,10 @@ pub fn shrug_it_off() -> Card {
         element: SpellElement::Neutral,
         combo_points_granted: 0,
         finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::LinearSlider,
     }
 }
 
@@ -174,6 +203,10 @@ pub fn clothesline() -> Card {
         element: SpellElement::Neutral,
         combo_points_granted: 1,
         finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::DirectionalPrompt,
     }
 }
 
@@ -193,6 +226,10 @@ pub fn quick_slash() -> Card {
         element: SpellElement::Neutral,
         combo_points_granted: 2,
         finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::LinearSlider,
     }
 }
 
@@ -212,6 +249,10 @@ pub fn bludgeon() -> Card {
         element: SpellElement::Neutral,
         combo_points_granted: 1,
         finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::DirectionalPrompt,
     }
 }
 
@@ -231,6 +272,10 @@ pub fn poison_cloud() -> Card {
         element: SpellElement::Neutral,
         combo_points_granted: 0,
         finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::LinearSlider,
     }
 }
 
@@ -250,6 +295,10 @@ pub fn fortify() -> Card {
         element: SpellElement::Neutral,
         combo_points_granted: 0,
         finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::LinearSlider,
     }
 }
 
@@ -269,6 +318,10 @@ pub fn uppercut() -> Card {
         element: SpellElement::Neutral,
         combo_points_granted: 1,
         finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::LinearSlider,
     }
 }
 
@@ -288,6 +341,10 @@ pub fn flashbang() -> Card {
         element: SpellElement::Neutral,
         combo_points_granted: 0,
         finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::LinearSlider,
     }
 }
 
@@ -307,6 +364,10 @@ pub fn magic_bolt() -> Card {
         element: SpellElement::Neutral,
         combo_points_granted: 0,
         finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::MovingTarget,
     }
 }
 
@@ -326,6 +387,10 @@ pub fn magic_shield() -> Card {
         element: SpellElement::Neutral,
         combo_points_granted: 0,
         finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::LinearSlider,
     }
 }
 
@@ -345,6 +410,10 @@ pub fn fire_essence() -> Card {
         element: SpellElement::Fire,
         combo_points_granted: 0,
         finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::LinearSlider,
     }
 }
 
@@ -364,6 +433,10 @@ pub fn ice_essence() -> Card {
         element: SpellElement::Ice,
         combo_points_granted: 0,
         finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::LinearSlider,
     }
 }
 
@@ -383,6 +456,10 @@ pub fn wind_essence() -> Card {
         element: SpellElement::Wind,
         combo_points_granted: 0,
         finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::LinearSlider,
     }
 }
 
@@ -402,54 +479,552 @@ pub fn stone_essence() -> Card {
         element: SpellElement::Stone,
         combo_points_granted: 0,
         finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::LinearSlider,
     }
 }
 
-pub fn generate_random_card(class: CharacterClass) -> Card {
-    let mut rng = thread_rng();
-    let roll = rng.gen_range(0..100);
-
-    match class {
-        CharacterClass::Duelist => {
-            if roll < 60 {
-                // Common (60%)
-                let r = rng.gen_range(0..3);
-                match r {
-                    0 => iron_wave(),
-                    1 => shrug_it_off(),
-                    _ => quick_slash(),
-                }
-            } else if roll < 90 {
-                // Rare (30%)
-                let r = rng.gen_range(0..6);
-                match r {
-                    0 => deadly_poison(),
-                    1 => clothesline(),
-                    2 => poison_cloud(),
-                    3 => fortify(),
-                    4 => uppercut(),
-                    _ => flashbang(),
-                }
-            } else {
-                // Legendary (10%)
-                let r = rng.gen_range(0..2);
-                match r {
-                    0 => heavy_blade(),
-                    _ => bludgeon(),
-                }
-            }
-        }
-        CharacterClass::Spellweaver => {
-            // Simple distribution for Spellweaver for now
-            let r = rng.gen_range(0..6);
-            match r {
-                0 => magic_bolt(),
-                1 => magic_shield(),
-                2 => fire_essence(),
-                3 => ice_essence(),
-                4 => stone_essence(),
-                _ => wind_essence(),
-            }
-        }
+pub fn lightning_essence() -> Card {
+    Card {
+        name: "Lightning Essence".to_string(),
+        damage: 3,
+        block: 0,
+        cost: 0,
+        apply_poison: 0,
+        apply_weak: 0,
+        apply_stun: 0,
+        upgraded: false,
+        rarity: Rarity::Common,
+        is_spell_modifier: true,
+        is_spell_source: false,
+        element: SpellElement::Lightning,
+        combo_points_granted: 0,
+        finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::LinearSlider,
     }
 }
+
+pub fn shadow_essence() -> Card {
+    Card {
+        name: "Shadow Essence".to_string(),
+        damage: 2,
+        block: 0,
+        cost: 0,
+        apply_poison: 0,
+        apply_weak: 0,
+        apply_stun: 0,
+        upgraded: false,
+        rarity: Rarity::Common,
+        is_spell_modifier: true,
+        is_spell_source: false,
+        element: SpellElement::Shadow,
+        combo_points_granted: 0,
+        finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::LinearSlider,
+    }
+}
+
+pub fn water_essence() -> Card {
+    Card {
+        name: "Water Essence".to_string(),
+        damage: 0,
+        block: 3,
+        cost: 0,
+        apply_poison: 0,
+        apply_weak: 0,
+        apply_stun: 0,
+        upgraded: false,
+        rarity: Rarity::Common,
+        is_spell_modifier: true,
+        is_spell_source: false,
+        element: SpellElement::Water,
+        combo_points_granted: 0,
+        finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::LinearSlider,
+    }
+}
+
+// Duelist finishers. Each one spends Combo for its bonus.
+pub fn riposte() -> Card {
+    // Spend all Combo: gain 4 Block per point
+    Card {
+        name: "Riposte".to_string(),
+        damage: 0,
+        block: 0,
+        cost: 1,
+        apply_poison: 0,
+        apply_weak: 0,
+        apply_stun: 0,
+        upgraded: false,
+        rarity: Rarity::Common,
+        is_spell_modifier: false,
+        is_spell_source: false,
+        element: SpellElement::Neutral,
+        combo_points_granted: 0,
+        finisher_combo_cost: 1,
+        finisher: Finisher::BlockPerPoint(4),
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::ChargeMeter,
+    }
+}
+
+pub fn flurry() -> Card {
+    // Spend all Combo: hit once per point
+    Card {
+        name: "Flurry".to_string(),
+        damage: 4,
+        block: 0,
+        cost: 1,
+        apply_poison: 0,
+        apply_weak: 0,
+        apply_stun: 0,
+        upgraded: false,
+        rarity: Rarity::Rare,
+        is_spell_modifier: false,
+        is_spell_source: false,
+        element: SpellElement::Neutral,
+        combo_points_granted: 0,
+        finisher_combo_cost: 2,
+        finisher: Finisher::MultiHit,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::MultiTap,
+    }
+}
+
+pub fn read_the_opening() -> Card {
+    // Spend all Combo: draw a card per point
+    Card {
+        name: "Read the Opening".to_string(),
+        damage: 0,
+        block: 0,
+        cost: 0,
+        apply_poison: 0,
+        apply_weak: 0,
+        apply_stun: 0,
+        upgraded: false,
+        rarity: Rarity::Common,
+        is_spell_modifier: false,
+        is_spell_source: false,
+        element: SpellElement::Neutral,
+        combo_points_granted: 0,
+        finisher_combo_cost: 1,
+        finisher: Finisher::DrawPerPoint,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::ChargeMeter,
+    }
+}
+
+pub fn second_wind() -> Card {
+    // Spend all Combo: gain 1 Energy per point
+    Card {
+        name: "Second Wind".to_string(),
+        damage: 0,
+        block: 0,
+        cost: 0,
+        apply_poison: 0,
+        apply_weak: 0,
+        apply_stun: 0,
+        upgraded: false,
+        rarity: Rarity::Rare,
+        is_spell_modifier: false,
+        is_spell_source: false,
+        element: SpellElement::Neutral,
+        combo_points_granted: 0,
+        finisher_combo_cost: 1,
+        finisher: Finisher::EnergyPerPoint,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::ChargeMeter,
+    }
+}
+
+pub fn disarming_blow() -> Card {
+    // Spend 2 Combo: Stun the target
+    Card {
+        name: "Disarming Blow".to_string(),
+        damage: 7,
+        block: 0,
+        cost: 1,
+        apply_poison: 0,
+        apply_weak: 0,
+        apply_stun: 0,
+        upgraded: false,
+        rarity: Rarity::Rare,
+        is_spell_modifier: false,
+        is_spell_source: false,
+        element: SpellElement::Neutral,
+        combo_points_granted: 0,
+        finisher_combo_cost: 2,
+        finisher: Finisher::Stun,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::ChargeMeter,
+    }
+}
+
+// Berserker cards. The scaling ones hit harder the more HP is missing.
+pub fn reckless_swing() -> Card {
+    Card {
+        name: "Reckless Swing".to_string(),
+        damage: 6,
+        block: 0,
+        cost: 1,
+        apply_poison: 0,
+        apply_weak: 0,
+        apply_stun: 0,
+        upgraded: false,
+        rarity: Rarity::Common,
+        is_spell_modifier: false,
+        is_spell_source: false,
+        element: SpellElement::Neutral,
+        combo_points_granted: 0,
+        finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 25,
+        reflex: ReflexVisualType::LinearSlider,
+    }
+}
+
+pub fn iron_hide() -> Card {
+    Card {
+        name: "Iron Hide".to_string(),
+        damage: 0,
+        block: 8,
+        cost: 1,
+        apply_poison: 0,
+        apply_weak: 0,
+        apply_stun: 0,
+        upgraded: false,
+        rarity: Rarity::Common,
+        is_spell_modifier: false,
+        is_spell_source: false,
+        element: SpellElement::Neutral,
+        combo_points_granted: 0,
+        finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::LinearSlider,
+    }
+}
+
+pub fn war_cry() -> Card {
+    Card {
+        name: "War Cry".to_string(),
+        damage: 0,
+        block: 4,
+        cost: 1,
+        apply_poison: 0,
+        apply_weak: 2,
+        apply_stun: 0,
+        upgraded: false,
+        rarity: Rarity::Common,
+        is_spell_modifier: false,
+        is_spell_source: false,
+        element: SpellElement::Neutral,
+        combo_points_granted: 0,
+        finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::LinearSlider,
+    }
+}
+
+pub fn frenzy() -> Card {
+    Card {
+        name: "Frenzy".to_string(),
+        damage: 10,
+        block: 0,
+        cost: 2,
+        apply_poison: 0,
+        apply_weak: 0,
+        apply_stun: 0,
+        upgraded: false,
+        rarity: Rarity::Rare,
+        is_spell_modifier: false,
+        is_spell_source: false,
+        element: SpellElement::Neutral,
+        combo_points_granted: 0,
+        finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 50,
+        reflex: ReflexVisualType::LinearSlider,
+    }
+}
+
+pub fn savage_blow() -> Card {
+    Card {
+        name: "Savage Blow".to_string(),
+        damage: 12,
+        block: 0,
+        cost: 2,
+        apply_poison: 0,
+        apply_weak: 0,
+        apply_stun: 1,
+        upgraded: false,
+        rarity: Rarity::Rare,
+        is_spell_modifier: false,
+        is_spell_source: false,
+        element: SpellElement::Neutral,
+        combo_points_granted: 0,
+        finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::DirectionalPrompt,
+    }
+}
+
+pub fn crimson_guard() -> Card {
+    Card {
+        name: "Crimson Guard".to_string(),
+        damage: 0,
+        block: 14,
+        cost: 2,
+        apply_poison: 0,
+        apply_weak: 0,
+        apply_stun: 0,
+        upgraded: false,
+        rarity: Rarity::Rare,
+        is_spell_modifier: false,
+        is_spell_source: false,
+        element: SpellElement::Neutral,
+        combo_points_granted: 0,
+        finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::LinearSlider,
+    }
+}
+
+pub fn last_stand() -> Card {
+    Card {
+        name: "Last Stand".to_string(),
+        damage: 5,
+        block: 0,
+        cost: 3,
+        apply_poison: 0,
+        apply_weak: 0,
+        apply_stun: 0,
+        upgraded: false,
+        rarity: Rarity::Legendary,
+        is_spell_modifier: false,
+        is_spell_source: false,
+        element: SpellElement::Neutral,
+        combo_points_granted: 0,
+        finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 100,
+        reflex: ReflexVisualType::LinearSlider,
+    }
+}
+
+// Colorless cards belong to no class and are sold in every shop
+pub fn swift_strike() -> Card {
+    Card {
+        name: "Swift Strike".to_string(),
+        damage: 5,
+        block: 0,
+        cost: 0,
+        apply_poison: 0,
+        apply_weak: 0,
+        apply_stun: 0,
+        upgraded: false,
+        rarity: Rarity::Common,
+        is_spell_modifier: false,
+        is_spell_source: false,
+        element: SpellElement::Neutral,
+        combo_points_granted: 0,
+        finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::LinearSlider,
+    }
+}
+
+pub fn good_instincts() -> Card {
+    Card {
+        name: "Good Instincts".to_string(),
+        damage: 0,
+        block: 5,
+        cost: 0,
+        apply_poison: 0,
+        apply_weak: 0,
+        apply_stun: 0,
+        upgraded: false,
+        rarity: Rarity::Common,
+        is_spell_modifier: false,
+        is_spell_source: false,
+        element: SpellElement::Neutral,
+        combo_points_granted: 0,
+        finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::LinearSlider,
+    }
+}
+
+pub fn dark_shackles() -> Card {
+    Card {
+        name: "Dark Shackles".to_string(),
+        damage: 0,
+        block: 0,
+        cost: 0,
+        apply_poison: 0,
+        apply_weak: 2,
+        apply_stun: 0,
+        upgraded: false,
+        rarity: Rarity::Rare,
+        is_spell_modifier: false,
+        is_spell_source: false,
+        element: SpellElement::Neutral,
+        combo_points_granted: 0,
+        finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::LinearSlider,
+    }
+}
+
+pub fn hand_of_greed() -> Card {
+    Card {
+        name: "Hand of Greed".to_string(),
+        damage: 20,
+        block: 0,
+        cost: 2,
+        apply_poison: 0,
+        apply_weak: 0,
+        apply_stun: 0,
+        upgraded: false,
+        rarity: Rarity::Legendary,
+        is_spell_modifier: false,
+        is_spell_source: false,
+        element: SpellElement::Neutral,
+        combo_points_granted: 0,
+        finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::DirectionalPrompt,
+    }
+}
+
+// Status card shuffled into the deck by enemies. Clogs the hand and can't be played.
+pub fn wound() -> Card {
+    Card {
+        name: "Wound".to_string(),
+        damage: 0,
+        block: 0,
+        cost: 0,
+        apply_poison: 0,
+        apply_weak: 0,
+        apply_stun: 0,
+        upgraded: false,
+        rarity: Rarity::Common,
+        is_spell_modifier: false,
+        is_spell_source: false,
+        element: SpellElement::Neutral,
+        combo_points_granted: 0,
+        finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: true,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::LinearSlider,
+    }
+}
+
+pub fn all_cards() -> Vec<Card> {
+    vec![
+        strike(),
+        defend(),
+        bash(),
+        iron_wave(),
+        deadly_poison(),
+        heavy_blade(),
+        shrug_it_off(),
+        clothesline(),
+        quick_slash(),
+        bludgeon(),
+        poison_cloud(),
+        fortify(),
+        uppercut(),
+        flashbang(),
+        riposte(),
+        flurry(),
+        read_the_opening(),
+        second_wind(),
+        disarming_blow(),
+        magic_bolt(),
+        magic_shield(),
+        fire_essence(),
+        ice_essence(),
+        wind_essence(),
+        stone_essence(),
+        lightning_essence(),
+        shadow_essence(),
+        water_essence(),
+        reckless_swing(),
+        iron_hide(),
+        war_cry(),
+        frenzy(),
+        savage_blow(),
+        crimson_guard(),
+        last_stand(),
+        swift_strike(),
+        good_instincts(),
+        dark_shackles(),
+        hand_of_greed(),
+        wound(),
+    ]
+}
+
+pub fn colorless_cards() -> Vec<Card> {
+    vec![
+        swift_strike(),
+        good_instincts(),
+        dark_shackles(),
+        hand_of_greed(),
+    ]
+}
+
+// Looks a card up by its display name, used by data files
+pub fn card_by_name(name: &str) -> Option<Card> {
+    all_cards().into_iter().find(|card| card.name == name)
+}
+
+pub fn upgrade_card(card: &mut Card) {
+    card.upgraded = true;
+    card.name.push('+');
+    if card.damage > 0 {
+        card.damage += 3;
+    }
+    if card.block > 0 {
+        card.block += 3;
+    }
+}
+
+pub fn generate_random_card(class: CharacterClass) -> Card {
+    crate::classes::player_class(class).random_card()
+}
@@ -1,12 +1,17 @@
 use bevy::prelude::*;
+use serde::Deserialize;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
 pub enum Potion {
     Health,
     Strength,
     Energy,
 }
 
+pub fn all_potions() -> Vec<Potion> {
+    vec![Potion::Health, Potion::Strength, Potion::Energy]
+}
+
 pub fn get_potion_name(potion: &Potion) -> String {
     match potion {
         Potion::Health => "Health Potion".to_string(),
@@ -1,12 +1,82 @@
 use bevy::prelude::*;
+use serde::Deserialize;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use crate::components::DeckService;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
 pub enum Relic {
     Vajra,
     BurningBlood,
     Anchor,
     OddlySmoothStone,
     BagOfMarbles,
+    PeacePipe,
+    MembershipCard,
+    SmilingMask,
+    Courier,
+    RedSkull,
+    DuelingGloves,
+    ArcaneFocus,
+    Metronome,
+    Astrolabe,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelicTier {
+    Common,
+    Uncommon,
+    Rare,
+    Shop,    // Only ever sold by the merchant
+    Starter, // Signature relics classes begin the run with
+}
+
+pub fn all_relics() -> Vec<Relic> {
+    vec![
+        Relic::Vajra,
+        Relic::BurningBlood,
+        Relic::Anchor,
+        Relic::OddlySmoothStone,
+        Relic::BagOfMarbles,
+        Relic::PeacePipe,
+        Relic::MembershipCard,
+        Relic::SmilingMask,
+        Relic::Courier,
+        Relic::RedSkull,
+        Relic::DuelingGloves,
+        Relic::ArcaneFocus,
+        Relic::Metronome,
+        Relic::Astrolabe,
+    ]
+}
+
+pub fn relic_tier(relic: &Relic) -> RelicTier {
+    match relic {
+        Relic::Anchor | Relic::OddlySmoothStone | Relic::BagOfMarbles => RelicTier::Common,
+        Relic::Vajra | Relic::Metronome => RelicTier::Uncommon,
+        Relic::BurningBlood | Relic::PeacePipe => RelicTier::Rare,
+        Relic::MembershipCard | Relic::SmilingMask | Relic::Courier | Relic::Astrolabe => {
+            RelicTier::Shop
+        }
+        Relic::RedSkull | Relic::DuelingGloves | Relic::ArcaneFocus => RelicTier::Starter,
+    }
+}
+
+pub fn relic_tier_name(tier: RelicTier) -> &'static str {
+    match tier {
+        RelicTier::Common => "Common",
+        RelicTier::Uncommon => "Uncommon",
+        RelicTier::Rare => "Rare",
+        RelicTier::Shop => "Shop",
+        RelicTier::Starter => "Starter",
+    }
+}
+
+// Relics that chests, elites and events can hand out
+pub fn droppable_relics() -> Vec<Relic> {
+    all_relics()
+        .into_iter()
+        .filter(|relic| !matches!(relic_tier(relic), RelicTier::Shop | RelicTier::Starter))
+        .collect()
 }
 
 pub fn get_relic_visuals(relic: &Relic) -> (String, String, Color) {
@@ -36,6 +106,51 @@ pub fn get_relic_visuals(relic: &Relic) -> (String, String, Color) {
             "Marbles: Apply 1 Weak to enemy at start.".to_string(),
             Color::srgb(0.2, 0.8, 0.2),
         ),
+        Relic::PeacePipe => (
+            "P".to_string(),
+            "Peace Pipe: Rest sites let you remove a card.".to_string(),
+            Color::srgb(0.6, 0.4, 0.2),
+        ),
+        Relic::MembershipCard => (
+            "MC".to_string(),
+            "Membership Card: 50% off everything in shops.".to_string(),
+            Color::srgb(0.9, 0.75, 0.2),
+        ),
+        Relic::SmilingMask => (
+            "SM".to_string(),
+            "Smiling Mask: Card removal always costs 50 gold.".to_string(),
+            Color::srgb(0.8, 0.6, 0.7),
+        ),
+        Relic::Courier => (
+            "C".to_string(),
+            "Courier: The first restock each shop visit is free.".to_string(),
+            Color::srgb(0.3, 0.6, 0.8),
+        ),
+        Relic::RedSkull => (
+            "RS".to_string(),
+            "Red Skull: Attacks deal +3 damage while at or below half HP.".to_string(),
+            Color::srgb(0.7, 0.1, 0.1),
+        ),
+        Relic::DuelingGloves => (
+            "DG".to_string(),
+            "Dueling Gloves: Keep 1 Combo point between turns.".to_string(),
+            Color::srgb(0.9, 0.5, 0.1),
+        ),
+        Relic::ArcaneFocus => (
+            "AF".to_string(),
+            "Arcane Focus: Your first spell each combat gets a free random essence.".to_string(),
+            Color::srgb(0.5, 0.3, 0.9),
+        ),
+        Relic::Metronome => (
+            "M".to_string(),
+            "Metronome: Gain 1 extra Combo whenever you gain Combo, but lose 2 Combo at the end of each turn.".to_string(),
+            Color::srgb(0.8, 0.7, 0.3),
+        ),
+        Relic::Astrolabe => (
+            "As".to_string(),
+            "Astrolabe: When bought, transform a card of your choice.".to_string(),
+            Color::srgb(0.3, 0.4, 0.7),
+        ),
     }
 }
 
@@ -46,5 +161,22 @@ pub fn get_relic_name(relic: &Relic) -> String {
         Relic::Anchor => "Anchor".to_string(),
         Relic::OddlySmoothStone => "Oddly Smooth Stone".to_string(),
         Relic::BagOfMarbles => "Bag of Marbles".to_string(),
+        Relic::PeacePipe => "Peace Pipe".to_string(),
+        Relic::MembershipCard => "Membership Card".to_string(),
+        Relic::SmilingMask => "Smiling Mask".to_string(),
+        Relic::Courier => "Courier".to_string(),
+        Relic::RedSkull => "Red Skull".to_string(),
+        Relic::DuelingGloves => "Dueling Gloves".to_string(),
+        Relic::ArcaneFocus => "Arcane Focus".to_string(),
+        Relic::Metronome => "Metronome".to_string(),
+        Relic::Astrolabe => "Astrolabe".to_string(),
+    }
+}
+
+// Relics that open the card picker the moment they're obtained
+pub fn relic_pickup_service(relic: &Relic) -> Option<DeckService> {
+    match relic {
+        Relic::Astrolabe => Some(DeckService::Transform),
+        _ => None,
     }
 }
@@ -1,15 +1,24 @@
 use bevy::prelude::*;
 
+mod acts;
+mod classes;
 mod cli;
+mod combat_math;
 mod common;
 mod components;
+mod deck_services;
 mod enemies;
+mod event_engine;
 mod item_cards;
 mod item_potions;
 mod item_relics;
+mod map_generator;
+mod map_planner;
+mod reflex;
 mod resources;
 mod scene_battle;
 mod scene_bonus_select;
+mod scene_calibration;
 mod scene_character_select;
 mod scene_deck;
 mod scene_discard;
@@ -19,7 +28,12 @@ mod scene_map;
 mod scene_menu;
 mod scene_rest;
 mod scene_rewards;
+mod scene_settings;
 mod scene_shop;
+mod scene_spellbook;
+mod scene_treasure;
+mod shop_stock;
+mod spell_recipes;
 mod states;
 mod ui;
 
@@ -32,6 +46,7 @@ use enemies::*;
 use resources::*;
 use scene_battle::*;
 use scene_bonus_select::*;
+use scene_calibration::*;
 use scene_character_select::*;
 use scene_deck::*;
 use scene_discard::*;
@@ -41,7 +56,10 @@ use scene_map::*;
 use scene_menu::*;
 use scene_rest::*;
 use scene_rewards::*;
+use scene_settings::*;
 use scene_shop::*;
+use scene_spellbook::*;
+use scene_treasure::*;
 use states::*;
 use ui::*;
 
@@ -49,9 +67,13 @@ fn main() {
     App::new()
         .add_plugins(DefaultPlugins) // Adds windowing, input, etc.
         .add_plugins(cli::CliPlugin)
+        .add_plugins(classes::ClassesPlugin)
         .init_state::<GameState>()
         .init_state::<TurnState>()
         .init_resource::<RunState>()
+        .insert_resource(event_engine::load_event_library())
+        .insert_resource(spell_recipes::load_spellbook())
+        .insert_resource(reflex::load_reflex_settings())
         .add_systems(Startup, setup_camera)
         .add_systems(OnEnter(GameState::MainMenu), setup_main_menu)
         .add_systems(
@@ -60,6 +82,26 @@ fn main() {
                 .run_if(in_state(GameState::MainMenu)),
         )
         .add_systems(OnExit(GameState::MainMenu), despawn_screen::<MainMenuUI>)
+        .add_systems(OnEnter(GameState::Settings), setup_settings_screen)
+        .add_systems(
+            Update,
+            settings_interaction_system.run_if(in_state(GameState::Settings)),
+        )
+        .add_systems(OnExit(GameState::Settings), despawn_screen::<SettingsUI>)
+        .add_systems(OnEnter(GameState::Calibration), setup_calibration_screen)
+        .add_systems(
+            Update,
+            (
+                calibration_ring_system,
+                calibration_input_system,
+                calibration_interaction_system,
+            )
+                .run_if(in_state(GameState::Calibration)),
+        )
+        .add_systems(
+            OnExit(GameState::Calibration),
+            despawn_screen::<CalibrationUI>,
+        )
         .add_systems(OnEnter(GameState::Battle), setup_battle)
         .add_systems(
             Update,
@@ -71,8 +113,6 @@ fn main() {
                     card_hover_system,
                     update_health_ui,
                     update_energy_ui,
-                    update_spell_ui,
-                    update_combo_ui_system,
                     update_status_visuals_system,
                     tooltip_system,
                     update_relic_ui,
@@ -94,6 +134,19 @@ fn main() {
                     resize_background_system,
                     enemy_selection_system,
                 ),
+                (
+                    boss_phase_system,
+                    update_phase_banner_system,
+                    update_enemy_intent_text_system,
+                    pending_draw_system,
+                    (
+                        reflex::apply_reflex_settings_system,
+                        reflex::reflex_challenge_system,
+                        reflex::auto_reflex_system,
+                    )
+                        .chain(),
+                    reflex::reflex_cue_system,
+                ),
             )
                 .run_if(in_state(GameState::Battle)),
         )
@@ -105,7 +158,10 @@ fn main() {
             Update,
             player_attack_animating_system.run_if(in_state(TurnState::PlayerAttackAnimating)),
         )
-        .add_systems(OnEnter(TurnState::PlayerTurnStart), draw_cards_system)
+        .add_systems(
+            OnEnter(TurnState::PlayerTurnStart),
+            (plan_enemy_intents_system, draw_cards_system).chain(),
+        )
         .add_systems(OnEnter(TurnState::PlayerTurnEnd), discard_hand_system)
         .add_systems(
             OnExit(GameState::Battle),
@@ -141,6 +197,9 @@ fn main() {
                 map_interaction_system,
                 process_map_node_select_requests,
                 tooltip_system,
+                (map_view_input_system, map_layout_system).chain(),
+                (map_planner_interaction_system, map_highlight_system).chain(),
+                update_relic_ui,
             )
                 .run_if(in_state(GameState::Map)),
         )
@@ -152,7 +211,12 @@ fn main() {
         .add_systems(
             Update,
             (
-                shop_interaction_system,
+                (
+                    shop_interaction_system,
+                    merchant_interaction_system,
+                    shop_refresh_system,
+                )
+                    .chain(),
                 shop_nav_system,
                 update_shop_gold_ui,
                 resize_background_system,
@@ -160,21 +224,19 @@ fn main() {
                 .run_if(in_state(GameState::Shop)),
         )
         .add_systems(OnExit(GameState::Shop), despawn_screen::<ShopUI>)
-        .add_systems(OnEnter(GameState::ShopRemoveCard), setup_shop_remove_screen)
-        .add_systems(
-            Update,
-            shop_remove_system.run_if(in_state(GameState::ShopRemoveCard)),
-        )
-        .add_systems(
-            OnExit(GameState::ShopRemoveCard),
-            despawn_screen::<ShopRemoveUI>,
-        )
         .add_systems(OnEnter(GameState::Rest), setup_rest_screen)
         .add_systems(
             Update,
             (rest_interaction_system, resize_background_system).run_if(in_state(GameState::Rest)),
         )
         .add_systems(OnExit(GameState::Rest), despawn_screen::<RestUI>)
+        .add_systems(OnEnter(GameState::Treasure), setup_treasure_screen)
+        .add_systems(
+            Update,
+            (treasure_interaction_system, resize_background_system)
+                .run_if(in_state(GameState::Treasure)),
+        )
+        .add_systems(OnExit(GameState::Treasure), despawn_screen::<TreasureUI>)
         .add_systems(OnEnter(GameState::GameOver), setup_game_over_screen)
         .add_systems(
             Update,
@@ -184,12 +246,36 @@ fn main() {
             OnExit(GameState::GameOver),
             (despawn_screen::<GameOverUI>, setup_game),
         )
+        .add_systems(OnEnter(GameState::RunVictory), setup_run_victory_screen)
+        .add_systems(
+            Update,
+            game_over_interaction_system.run_if(in_state(GameState::RunVictory)),
+        )
+        .add_systems(
+            OnExit(GameState::RunVictory),
+            (despawn_screen::<RunVictoryUI>, setup_game),
+        )
         .add_systems(OnEnter(GameState::ViewDeck), setup_view_deck_screen)
         .add_systems(
             Update,
             view_deck_interaction_system.run_if(in_state(GameState::ViewDeck)),
         )
         .add_systems(OnExit(GameState::ViewDeck), despawn_screen::<ViewDeckUI>)
+        .add_systems(OnEnter(GameState::Spellbook), setup_spellbook_screen)
+        .add_systems(
+            Update,
+            spellbook_interaction_system.run_if(in_state(GameState::Spellbook)),
+        )
+        .add_systems(OnExit(GameState::Spellbook), despawn_screen::<SpellbookUI>)
+        .add_systems(OnEnter(GameState::CardPicker), setup_card_picker_screen)
+        .add_systems(
+            Update,
+            card_picker_interaction_system.run_if(in_state(GameState::CardPicker)),
+        )
+        .add_systems(
+            OnExit(GameState::CardPicker),
+            despawn_screen::<CardPickerUI>,
+        )
         .add_systems(
             OnEnter(TurnState::ViewingDiscard),
             setup_view_discard_overlay,
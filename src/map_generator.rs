@@ -0,0 +1,447 @@
+use rand::seq::SliceRandom;
+use rand::{Rng, thread_rng};
+
+use crate::components::{BurningBuff, NodeType};
+use crate::resources::MapNodeData;
+
+// Total levels per act, the last one being the boss
+pub const MAP_LEVELS: usize = 15;
+// Width of the lattice the paths are walked on; levels use as many columns as paths touch
+pub const MAP_COLUMNS: usize = 5;
+pub const MAP_PATHS: usize = 6;
+
+// Elites only start showing up after a couple of fights
+pub const FIRST_ELITE_LEVEL: usize = 3;
+
+// Types that may never follow themselves along a path
+pub const NO_CONSECUTIVE: [NodeType; 2] = [NodeType::Elite, NodeType::Shop];
+
+// Halfway through the act every path passes a treasure chest
+pub fn treasure_level(level_count: usize) -> usize {
+    level_count / 2
+}
+
+pub const MIN_NODE_COUNTS: [(NodeType, usize); 6] = [
+    (NodeType::Elite, 2),
+    (NodeType::Shop, 1),
+    (NodeType::Rest, 3),
+    (NodeType::Event, 1),
+    (NodeType::Unknown, 2),
+    (NodeType::Treasure, 1),
+];
+
+const NODE_WEIGHTS: [(NodeType, u32); 6] = [
+    (NodeType::Battle, 45),
+    (NodeType::Unknown, 14),
+    (NodeType::Event, 8),
+    (NodeType::Elite, 12),
+    (NodeType::Rest, 12),
+    (NodeType::Shop, 9),
+];
+
+const BURNING_BUFFS: [BurningBuff; 3] = [
+    BurningBuff::Vigor,
+    BurningBuff::Strength,
+    BurningBuff::Thorns,
+];
+
+const MAX_ATTEMPTS: usize = 100;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MapError {
+    TooFewLevels,
+    EmptyLevel(usize),
+    TooWide(usize),
+    InvalidEdge {
+        level: usize,
+        index: usize,
+    },
+    DeadEnd {
+        level: usize,
+        index: usize,
+    },
+    Unreachable {
+        level: usize,
+        index: usize,
+    },
+    CrossingPaths {
+        level: usize,
+    },
+    MissingBoss,
+    NoRestBeforeBoss {
+        index: usize,
+    },
+    MissingTreasure {
+        index: usize,
+    },
+    BurningElites {
+        found: usize,
+    },
+    BurningNonElite {
+        level: usize,
+        index: usize,
+    },
+    ConsecutiveNodes {
+        level: usize,
+        index: usize,
+        node_type: NodeType,
+    },
+    TooFew {
+        node_type: NodeType,
+        found: usize,
+        required: usize,
+    },
+}
+
+pub fn generate_map() -> Vec<Vec<MapNodeData>> {
+    let mut rng = thread_rng();
+
+    // Node types are rolled, so a candidate can fall short of the minimum counts
+    for _ in 0..MAX_ATTEMPTS {
+        let levels = generate_candidate(&mut rng);
+        if validate_map(&levels).is_ok() {
+            return levels;
+        }
+    }
+
+    println!("Map generator gave up on a valid map, using the fallback layout");
+    fallback_map()
+}
+
+// Three straight lanes with hand-picked node types, known to pass validate_map
+const FALLBACK_LANES: usize = 3;
+const FALLBACK_LAYOUT: [NodeType; MAP_LEVELS - 1] = [
+    NodeType::Battle,
+    NodeType::Battle,
+    NodeType::Event,
+    NodeType::Elite,
+    NodeType::Battle,
+    NodeType::Unknown,
+    NodeType::Rest,
+    NodeType::Treasure,
+    NodeType::Shop,
+    NodeType::Elite,
+    NodeType::Unknown,
+    NodeType::Rest,
+    NodeType::Battle,
+    NodeType::Rest,
+];
+
+pub fn fallback_map() -> Vec<Vec<MapNodeData>> {
+    let last_lattice_level = FALLBACK_LAYOUT.len() - 1;
+    let mut levels: Vec<Vec<MapNodeData>> = FALLBACK_LAYOUT
+        .iter()
+        .enumerate()
+        .map(|(level, node_type)| {
+            (0..FALLBACK_LANES)
+                .map(|lane| MapNodeData {
+                    node_type: *node_type,
+                    next_indices: vec![if level < last_lattice_level { lane } else { 0 }],
+                    visible: level == 0,
+                    resolved: None,
+                    burning: None,
+                })
+                .collect()
+        })
+        .collect();
+    levels[3][1].burning = Some(BurningBuff::Strength);
+
+    levels.push(vec![MapNodeData {
+        node_type: NodeType::Boss,
+        next_indices: vec![],
+        visible: false,
+        resolved: None,
+        burning: None,
+    }]);
+    levels
+}
+
+fn generate_candidate(rng: &mut impl Rng) -> Vec<Vec<MapNodeData>> {
+    let lattice_levels = MAP_LEVELS - 1;
+
+    // Walk paths up the lattice, recording edges as (from column, to column) per level
+    let mut edges: Vec<Vec<(usize, usize)>> = vec![Vec::new(); lattice_levels - 1];
+    let mut starts: Vec<usize> = Vec::new();
+    for path in 0..MAP_PATHS {
+        let mut column = rng.gen_range(0..MAP_COLUMNS);
+        // The first two paths never share a starting point
+        if path == 1 && column == starts[0] {
+            column = (column + 1) % MAP_COLUMNS;
+        }
+        starts.push(column);
+
+        for level_edges in edges.iter_mut() {
+            let options: Vec<usize> = (column.saturating_sub(1)
+                ..=(column + 1).min(MAP_COLUMNS - 1))
+                .filter(|next| !crosses_any(level_edges, column, *next))
+                .collect();
+            // Going straight up can never cross, so there is always an option
+            let next = *options.choose(rng).unwrap_or(&column);
+            if !level_edges.contains(&(column, next)) {
+                level_edges.push((column, next));
+            }
+            column = next;
+        }
+    }
+
+    // Turn used columns into nodes, keeping them in column order so paths stay uncrossed
+    let mut columns: Vec<Vec<usize>> = Vec::new();
+    for level in 0..lattice_levels {
+        let mut used: Vec<usize> = if level < lattice_levels - 1 {
+            edges[level].iter().map(|(from, _)| *from).collect()
+        } else {
+            edges[level - 1].iter().map(|(_, to)| *to).collect()
+        };
+        used.sort();
+        used.dedup();
+        columns.push(used);
+    }
+
+    let mut levels: Vec<Vec<MapNodeData>> = columns
+        .iter()
+        .enumerate()
+        .map(|(level, used)| {
+            used.iter()
+                .map(|column| {
+                    let next_indices = if level < lattice_levels - 1 {
+                        let mut next: Vec<usize> = edges[level]
+                            .iter()
+                            .filter(|(from, _)| from == column)
+                            .filter_map(|(_, to)| columns[level + 1].iter().position(|c| c == to))
+                            .collect();
+                        next.sort();
+                        next
+                    } else {
+                        vec![0]
+                    };
+                    MapNodeData {
+                        node_type: NodeType::Battle,
+                        next_indices,
+                        visible: level == 0,
+                        resolved: None,
+                        burning: None,
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    levels.push(vec![MapNodeData {
+        node_type: NodeType::Boss,
+        next_indices: vec![],
+        visible: false,
+        resolved: None,
+        burning: None,
+    }]);
+
+    assign_node_types(&mut levels, rng);
+    mark_burning_elite(&mut levels, rng);
+    levels
+}
+
+fn crosses_any(edges: &[(usize, usize)], from: usize, to: usize) -> bool {
+    edges
+        .iter()
+        .any(|&(other_from, other_to)| edges_cross((from, to), (other_from, other_to)))
+}
+
+fn edges_cross(a: (usize, usize), b: (usize, usize)) -> bool {
+    (a.0 < b.0 && a.1 > b.1) || (a.0 > b.0 && a.1 < b.1)
+}
+
+fn parents_of(levels: &[Vec<MapNodeData>], level: usize, index: usize) -> Vec<usize> {
+    if level == 0 {
+        return Vec::new();
+    }
+    levels[level - 1]
+        .iter()
+        .enumerate()
+        .filter(|(_, node)| node.next_indices.contains(&index))
+        .map(|(parent, _)| parent)
+        .collect()
+}
+
+fn assign_node_types(levels: &mut [Vec<MapNodeData>], rng: &mut impl Rng) {
+    let boss_level = levels.len() - 1;
+
+    for level in 0..boss_level {
+        for index in 0..levels[level].len() {
+            let node_type = if level == 0 {
+                NodeType::Battle
+            } else if level == boss_level - 1 {
+                // Always a campfire right before the boss
+                NodeType::Rest
+            } else if level == treasure_level(levels.len()) {
+                NodeType::Treasure
+            } else {
+                let parent_types: Vec<NodeType> = parents_of(levels, level, index)
+                    .iter()
+                    .map(|&parent| levels[level - 1][parent].node_type)
+                    .collect();
+
+                let allowed: Vec<(NodeType, u32)> = NODE_WEIGHTS
+                    .iter()
+                    .copied()
+                    .filter(|(node_type, _)| {
+                        !(NO_CONSECUTIVE.contains(node_type) && parent_types.contains(node_type))
+                    })
+                    .filter(|(node_type, _)| {
+                        *node_type != NodeType::Elite || level >= FIRST_ELITE_LEVEL
+                    })
+                    // Leave the pre-boss campfire as the only rest at the end of the act
+                    .filter(|(node_type, _)| *node_type != NodeType::Rest || level < boss_level - 2)
+                    .collect();
+
+                let total: u32 = allowed.iter().map(|(_, weight)| weight).sum();
+                let mut roll = rng.gen_range(0..total);
+                let mut picked = NodeType::Battle;
+                for (node_type, weight) in allowed {
+                    if roll < weight {
+                        picked = node_type;
+                        break;
+                    }
+                    roll -= weight;
+                }
+                picked
+            };
+            levels[level][index].node_type = node_type;
+        }
+    }
+}
+
+fn mark_burning_elite(levels: &mut [Vec<MapNodeData>], rng: &mut impl Rng) {
+    let elites: Vec<(usize, usize)> = levels
+        .iter()
+        .enumerate()
+        .flat_map(|(level, nodes)| {
+            nodes
+                .iter()
+                .enumerate()
+                .filter(|(_, node)| node.node_type == NodeType::Elite)
+                .map(move |(index, _)| (level, index))
+        })
+        .collect();
+
+    if let Some(&(level, index)) = elites.choose(rng) {
+        levels[level][index].burning = BURNING_BUFFS.choose(rng).copied();
+    }
+}
+
+// Checks a map against the generator's rules. Used by the generator to reject bad rolls.
+pub fn validate_map(levels: &[Vec<MapNodeData>]) -> Result<(), MapError> {
+    if levels.len() < 3 {
+        return Err(MapError::TooFewLevels);
+    }
+    let boss_level = levels.len() - 1;
+
+    // Structure
+    for (level, nodes) in levels.iter().enumerate() {
+        if nodes.is_empty() {
+            return Err(MapError::EmptyLevel(level));
+        }
+        if nodes.len() > MAP_COLUMNS {
+            return Err(MapError::TooWide(level));
+        }
+        for (index, node) in nodes.iter().enumerate() {
+            if level < boss_level && node.next_indices.is_empty() {
+                return Err(MapError::DeadEnd { level, index });
+            }
+            if level < boss_level
+                && node
+                    .next_indices
+                    .iter()
+                    .any(|&next| next >= levels[level + 1].len())
+            {
+                return Err(MapError::InvalidEdge { level, index });
+            }
+            if level > 0 && parents_of(levels, level, index).is_empty() {
+                return Err(MapError::Unreachable { level, index });
+            }
+        }
+    }
+
+    if levels[boss_level].len() != 1 || levels[boss_level][0].node_type != NodeType::Boss {
+        return Err(MapError::MissingBoss);
+    }
+
+    // Non-crossing paths
+    for (level, nodes) in levels.iter().enumerate().take(boss_level) {
+        let edges: Vec<(usize, usize)> = nodes
+            .iter()
+            .enumerate()
+            .flat_map(|(index, node)| node.next_indices.iter().map(move |&next| (index, next)))
+            .collect();
+        for (i, &a) in edges.iter().enumerate() {
+            if edges[i + 1..].iter().any(|&b| edges_cross(a, b)) {
+                return Err(MapError::CrossingPaths { level });
+            }
+        }
+    }
+
+    // Every way into the boss goes through a rest site
+    for index in parents_of(levels, boss_level, 0) {
+        if levels[boss_level - 1][index].node_type != NodeType::Rest {
+            return Err(MapError::NoRestBeforeBoss { index });
+        }
+    }
+
+    for (index, node) in levels[treasure_level(levels.len())].iter().enumerate() {
+        if node.node_type != NodeType::Treasure {
+            return Err(MapError::MissingTreasure { index });
+        }
+    }
+
+    // Exactly one burning elite
+    let mut burning = 0;
+    for (level, nodes) in levels.iter().enumerate() {
+        for (index, node) in nodes.iter().enumerate() {
+            if node.burning.is_none() {
+                continue;
+            }
+            if node.node_type != NodeType::Elite {
+                return Err(MapError::BurningNonElite { level, index });
+            }
+            burning += 1;
+        }
+    }
+    if burning != 1 {
+        return Err(MapError::BurningElites { found: burning });
+    }
+
+    // No back-to-back elites or shops
+    for level in 1..levels.len() {
+        for (index, node) in levels[level].iter().enumerate() {
+            if !NO_CONSECUTIVE.contains(&node.node_type) {
+                continue;
+            }
+            let repeated = parents_of(levels, level, index)
+                .iter()
+                .any(|&parent| levels[level - 1][parent].node_type == node.node_type);
+            if repeated {
+                return Err(MapError::ConsecutiveNodes {
+                    level,
+                    index,
+                    node_type: node.node_type,
+                });
+            }
+        }
+    }
+
+    // Minimum variety per act
+    for (node_type, required) in MIN_NODE_COUNTS {
+        let found = levels
+            .iter()
+            .flatten()
+            .filter(|node| node.node_type == node_type)
+            .count();
+        if found < required {
+            return Err(MapError::TooFew {
+                node_type,
+                found,
+                required,
+            });
+        }
+    }
+
+    Ok(())
+}
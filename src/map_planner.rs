@@ -0,0 +1,121 @@
+use std::cmp::Reverse;
+
+use crate::components::{NodeType, RouteCriterion};
+use crate::resources::MapNodeData;
+
+pub const ROUTE_CRITERIA: [RouteCriterion; 4] = [
+    RouteCriterion::MostElites,
+    RouteCriterion::MostRests,
+    RouteCriterion::FewestBattles,
+    RouteCriterion::MostShops,
+];
+
+pub fn route_criterion_name(criterion: RouteCriterion) -> &'static str {
+    match criterion {
+        RouteCriterion::MostElites => "Most Elites",
+        RouteCriterion::MostRests => "Most Rests",
+        RouteCriterion::FewestBattles => "Fewest Battles",
+        RouteCriterion::MostShops => "Most Shops",
+    }
+}
+
+// Every criterion is maximized; "fewest" ones score negatively
+fn route_score(criterion: RouteCriterion, node_type: NodeType) -> i32 {
+    match (criterion, node_type) {
+        (RouteCriterion::MostElites, NodeType::Elite) => 1,
+        (RouteCriterion::MostRests, NodeType::Rest) => 1,
+        (RouteCriterion::MostShops, NodeType::Shop) => 1,
+        (RouteCriterion::FewestBattles, NodeType::Battle | NodeType::Elite) => -1,
+        _ => 0,
+    }
+}
+
+// Every node that can be reached by walking forward from `start`, including `start`
+pub fn reachable_from(levels: &[Vec<MapNodeData>], start: (usize, usize)) -> Vec<(usize, usize)> {
+    let mut reachable = vec![start];
+    let mut frontier = vec![start];
+    while let Some((level, index)) = frontier.pop() {
+        for &next in &levels[level][index].next_indices {
+            let node = (level + 1, next);
+            if level + 1 < levels.len() && !reachable.contains(&node) {
+                reachable.push(node);
+                frontier.push(node);
+            }
+        }
+    }
+    reachable
+}
+
+// Best route from `from` (or from the start of the map) to the boss for the given criterion.
+// The route passes through every pinned waypoint that can still be visited, in level order;
+// waypoints behind the player or off the way are ignored.
+pub fn plan_route(
+    levels: &[Vec<MapNodeData>],
+    from: Option<(usize, usize)>,
+    criterion: RouteCriterion,
+    waypoints: &[(usize, usize)],
+) -> Vec<(usize, usize)> {
+    let start_level = from.map_or(0, |(level, _)| level + 1);
+    if start_level >= levels.len() {
+        return Vec::new();
+    }
+
+    let mut ahead: Vec<(usize, usize)> = waypoints
+        .iter()
+        .copied()
+        .filter(|(level, _)| *level >= start_level)
+        .collect();
+    ahead.sort();
+    let mut pinned: Vec<(usize, usize)> = Vec::new();
+    for waypoint in ahead {
+        let same_level = pinned.iter().any(|(level, _)| *level == waypoint.0);
+        let reachable = match pinned.last().copied().or(from) {
+            Some(previous) => reachable_from(levels, previous).contains(&waypoint),
+            None => true,
+        };
+        if reachable && !same_level {
+            pinned.push(waypoint);
+        }
+    }
+
+    // best[level][index]: best total score from that node to the boss, None if no allowed way
+    let mut best: Vec<Vec<Option<i32>>> =
+        levels.iter().map(|nodes| vec![None; nodes.len()]).collect();
+    for level in (start_level..levels.len()).rev() {
+        for index in 0..levels[level].len() {
+            if pinned.iter().any(|&(l, i)| l == level && i != index) {
+                continue;
+            }
+            let node = &levels[level][index];
+            let rest = if level + 1 == levels.len() {
+                Some(0)
+            } else {
+                node.next_indices
+                    .iter()
+                    .filter_map(|&next| best[level + 1][next])
+                    .max()
+            };
+            best[level][index] = rest.map(|rest| rest + route_score(criterion, node.node_type));
+        }
+    }
+
+    // Follow the best scores forward, preferring the leftmost node on ties
+    let mut route = Vec::new();
+    let mut options: Vec<usize> = match from {
+        Some((level, index)) => levels[level][index].next_indices.clone(),
+        None => (0..levels[0].len()).collect(),
+    };
+    for (level, scores) in best.iter().enumerate().skip(start_level) {
+        let Some(pick) = options
+            .iter()
+            .copied()
+            .filter(|&index| scores[index].is_some())
+            .max_by_key(|&index| (scores[index], Reverse(index)))
+        else {
+            break;
+        };
+        route.push((level, pick));
+        options = levels[level][pick].next_indices.clone();
+    }
+    route
+}
@@ -0,0 +1,520 @@
+use bevy::audio::{Pitch, PitchBundle};
+use bevy::prelude::*;
+use rand::Rng;
+use std::time::{Duration, Instant};
+
+use crate::cli::{ReflexInput, TriggerReflexRequest};
+use crate::components::*;
+use crate::resources::*;
+
+const SETTINGS_PATH: &str = "settings.json";
+
+// Slow motion stretches every reflex timer by this much
+pub const SLOW_MOTION_FACTOR: f32 = 1.5;
+// The calibration metronome's beat, and how many taps make one measurement
+pub const CALIBRATION_BEAT_SECS: f32 = 0.8;
+pub const CALIBRATION_TAPS: usize = 8;
+
+// Multi-tap: how close to its beat each tap must land, before difficulty scaling
+pub const TAP_PERFECT_WINDOW: f32 = 0.04;
+pub const TAP_GOOD_WINDOW: f32 = 0.09;
+pub const MULTI_TAP_MAX_BEATS: u32 = 5;
+// A charge meter nobody starts holding counts as a miss after this long
+pub const CHARGE_IDLE_SECS: f32 = 3.0;
+pub const MOVING_TARGET_SIZE: f32 = 60.0;
+const BAR_WIDTH: f32 = 400.0;
+
+pub struct ReflexTuning {
+    // Perfect windows are scaled around their centre by this much
+    pub window_scale: f32,
+    pub good_margin: f32,
+}
+
+pub fn difficulty_tuning(difficulty: ReflexDifficulty) -> ReflexTuning {
+    let (window_scale, good_margin) = match difficulty {
+        ReflexDifficulty::Relaxed => (1.5, 0.15),
+        ReflexDifficulty::Normal => (1.0, 0.1),
+        ReflexDifficulty::Hard => (0.6, 0.05),
+    };
+    ReflexTuning {
+        window_scale,
+        good_margin,
+    }
+}
+
+pub fn load_reflex_settings() -> ReflexSettings {
+    let Ok(text) = std::fs::read_to_string(SETTINGS_PATH) else {
+        return ReflexSettings::default();
+    };
+    match serde_json::from_str(&text) {
+        Ok(settings) => settings,
+        Err(error) => {
+            println!("Ignoring unreadable settings file: {}", error);
+            ReflexSettings::default()
+        }
+    }
+}
+
+pub fn save_reflex_settings(settings: &ReflexSettings) {
+    let result = serde_json::to_string_pretty(settings)
+        .map_err(|error| error.to_string())
+        .and_then(|text| std::fs::write(SETTINGS_PATH, text).map_err(|error| error.to_string()));
+    if let Err(error) = result {
+        println!("Failed to save settings: {}", error);
+    }
+}
+
+pub fn apply_reflex_settings(reflex: &mut ReflexState, settings: &ReflexSettings) {
+    let tuning = difficulty_tuning(settings.difficulty);
+    let centre = (reflex.perfect_window_start + reflex.perfect_window_end) / 2.0;
+    let half_width =
+        (reflex.perfect_window_end - reflex.perfect_window_start) / 2.0 * tuning.window_scale;
+    reflex.perfect_window_start = (centre - half_width).max(0.0);
+    reflex.perfect_window_end = (centre + half_width).min(1.0);
+    reflex.good_margin = tuning.good_margin;
+    reflex.tolerance = tuning.window_scale;
+    reflex.auto = settings.auto_reflex;
+    if settings.slow_motion {
+        let duration = reflex.timer.duration().mul_f32(SLOW_MOTION_FACTOR);
+        reflex.timer.set_duration(duration);
+    }
+}
+
+// Reflexes are spawned with Normal timings; settings are applied once, as they appear
+pub fn apply_reflex_settings_system(
+    settings: Res<ReflexSettings>,
+    mut query: Query<(&mut ReflexState, Option<&Parent>), Added<ReflexState>>,
+    mut indicator_query: Query<(&Parent, &mut Style), With<PerfectWindowIndicator>>,
+) {
+    for (mut reflex, parent) in &mut query {
+        apply_reflex_settings(&mut reflex, &settings);
+        // Back-date the start by however long the timer has already run
+        reflex.started_at = Instant::now().checked_sub(reflex.timer.elapsed());
+        let Some(parent) = parent else {
+            continue;
+        };
+        for (indicator_parent, mut style) in &mut indicator_query {
+            if indicator_parent.get() == parent.get() {
+                style.left = Val::Percent(reflex.perfect_window_start * 100.0);
+                style.width =
+                    Val::Percent((reflex.perfect_window_end - reflex.perfect_window_start) * 100.0);
+            }
+        }
+    }
+}
+
+// How far through the reflex an input landed, corrected by the calibrated offset.
+// Falls back to the timer if the reflex hasn't been stamped yet.
+pub fn reflex_ratio(reflex: &ReflexState, pressed_at: Instant, offset_ms: f32) -> f32 {
+    let Some(started_at) = reflex.started_at else {
+        return reflex.timer.fraction();
+    };
+    let elapsed = pressed_at
+        .saturating_duration_since(started_at)
+        .as_secs_f32()
+        - offset_ms / 1000.0;
+    (elapsed / reflex.timer.duration().as_secs_f32()).clamp(0.0, 1.0)
+}
+
+// Seconds between a tap and the nearest metronome beat; negative is early.
+// Taps before the first beat has come close are ignored.
+pub fn beat_offset(since_start: f32, beat: f32) -> Option<f32> {
+    let nearest = (since_start / beat).round();
+    if nearest < 1.0 {
+        return None;
+    }
+    Some(since_start - nearest * beat)
+}
+
+pub fn average_offset_ms(offsets: &[f32]) -> f32 {
+    if offsets.is_empty() {
+        return 0.0;
+    }
+    offsets.iter().sum::<f32>() / offsets.len() as f32 * 1000.0
+}
+
+pub fn judge_reflex(reflex: &ReflexState, ratio: f32) -> ReflexSuccess {
+    if ratio >= reflex.perfect_window_start && ratio <= reflex.perfect_window_end {
+        ReflexSuccess::Perfect
+    } else if ratio >= reflex.perfect_window_start - reflex.good_margin
+        && ratio <= reflex.perfect_window_end + reflex.good_margin
+    {
+        ReflexSuccess::Good
+    } else {
+        ReflexSuccess::Miss
+    }
+}
+
+// Beats spread evenly through the reflex, one per hit
+pub fn multi_tap_beats(hits: u32) -> Vec<f32> {
+    let count = hits.clamp(2, MULTI_TAP_MAX_BEATS);
+    let (first, last) = (0.3, 0.85);
+    (0..count)
+        .map(|i| first + (last - first) * i as f32 / (count - 1) as f32)
+        .collect()
+}
+
+pub fn judge_tap(reflex: &ReflexState, ratio: f32, beat: f32) -> ReflexSuccess {
+    let distance = (ratio - beat).abs();
+    if distance <= TAP_PERFECT_WINDOW * reflex.tolerance {
+        ReflexSuccess::Perfect
+    } else if distance <= TAP_GOOD_WINDOW * reflex.tolerance {
+        ReflexSuccess::Good
+    } else {
+        ReflexSuccess::Miss
+    }
+}
+
+// Beats with no tap count as misses. Perfect needs mostly Perfect taps.
+pub fn combine_taps(results: &[ReflexSuccess], beats: usize) -> ReflexSuccess {
+    if beats == 0 {
+        return ReflexSuccess::Miss;
+    }
+    let score: u32 = results
+        .iter()
+        .map(|result| match result {
+            ReflexSuccess::Perfect => 2,
+            ReflexSuccess::Good => 1,
+            ReflexSuccess::Miss => 0,
+        })
+        .sum();
+    let average = score as f32 / beats as f32;
+    if average >= 1.5 {
+        ReflexSuccess::Perfect
+    } else if average >= 0.75 {
+        ReflexSuccess::Good
+    } else {
+        ReflexSuccess::Miss
+    }
+}
+
+// Window position of the moving target's centre: left to right, bobbing as it goes
+pub fn moving_target_center(ratio: f32, window: Vec2) -> Vec2 {
+    let x = window.x * (0.15 + 0.7 * ratio);
+    let y = window.y * 0.4 + (ratio * std::f32::consts::TAU * 2.0).sin() * 60.0;
+    Vec2::new(x, y)
+}
+
+pub fn judge_click(reflex: &ReflexState, cursor: Option<Vec2>, target: Vec2) -> ReflexSuccess {
+    let Some(cursor) = cursor else {
+        return ReflexSuccess::Miss;
+    };
+    let distance = cursor.distance(target);
+    if distance <= MOVING_TARGET_SIZE / 2.0 * reflex.tolerance {
+        ReflexSuccess::Perfect
+    } else if distance <= MOVING_TARGET_SIZE * reflex.tolerance {
+        ReflexSuccess::Good
+    } else {
+        ReflexSuccess::Miss
+    }
+}
+
+pub fn direction_label(direction: ReflexDirection) -> &'static str {
+    match direction {
+        ReflexDirection::Up => "UP",
+        ReflexDirection::Down => "DOWN",
+        ReflexDirection::Left => "LEFT",
+        ReflexDirection::Right => "RIGHT",
+    }
+}
+
+// Feeds one input to an unresolved reflex, judged the way its minigame is played
+pub fn handle_reflex_input(
+    reflex: &mut ReflexState,
+    request: &TriggerReflexRequest,
+    offset_ms: f32,
+    window: Vec2,
+) {
+    if reflex.result.is_some() {
+        return;
+    }
+    let ratio = reflex_ratio(reflex, request.pressed_at, offset_ms);
+    match (reflex.visual_type, request.input) {
+        (ReflexVisualType::ShrinkingRing | ReflexVisualType::LinearSlider, ReflexInput::Press) => {
+            reflex.result = Some(judge_reflex(reflex, ratio));
+        }
+        (ReflexVisualType::MultiTap, ReflexInput::Press) => {
+            let next = reflex.tap_results.len();
+            if let Some(beat) = reflex.beats.get(next).copied() {
+                let tap = judge_tap(reflex, ratio, beat);
+                reflex.tap_results.push(tap);
+                if reflex.tap_results.len() == reflex.beats.len() {
+                    reflex.result = Some(combine_taps(&reflex.tap_results, reflex.beats.len()));
+                }
+            }
+        }
+        (ReflexVisualType::DirectionalPrompt, ReflexInput::Direction(direction)) => {
+            reflex.result = Some(if reflex.prompt == Some(direction) {
+                judge_reflex(reflex, ratio)
+            } else {
+                ReflexSuccess::Miss
+            });
+        }
+        (ReflexVisualType::DirectionalPrompt, ReflexInput::Press) => {
+            reflex.result = Some(ReflexSuccess::Miss);
+        }
+        (ReflexVisualType::ChargeMeter, ReflexInput::Press)
+            if reflex.charge_started_at.is_none() =>
+        {
+            reflex.charge_started_at = Some(request.pressed_at);
+            reflex.timer.unpause();
+        }
+        (ReflexVisualType::ChargeMeter, ReflexInput::Release) => {
+            if let Some(charge_started_at) = reflex.charge_started_at {
+                let held = request
+                    .pressed_at
+                    .saturating_duration_since(charge_started_at)
+                    .as_secs_f32()
+                    - offset_ms / 1000.0;
+                let fill = (held / reflex.timer.duration().as_secs_f32()).clamp(0.0, 1.0);
+                reflex.result = Some(judge_reflex(reflex, fill));
+            }
+        }
+        (ReflexVisualType::MovingTarget, ReflexInput::Press) => {
+            let target = moving_target_center(ratio, window);
+            reflex.result = Some(judge_click(reflex, request.cursor, target));
+        }
+        _ => {}
+    }
+}
+
+// Resolves minigames that can end without a final input
+pub fn reflex_challenge_system(mut query: Query<&mut ReflexState>) {
+    for mut reflex in &mut query {
+        if reflex.result.is_some() {
+            continue;
+        }
+        match reflex.visual_type {
+            ReflexVisualType::MultiTap => {
+                let last_beat = reflex.beats.last().copied().unwrap_or(0.0);
+                if reflex.timer.fraction() > last_beat + TAP_GOOD_WINDOW * reflex.tolerance {
+                    reflex.result = Some(combine_taps(&reflex.tap_results, reflex.beats.len()));
+                }
+            }
+            ReflexVisualType::ChargeMeter => {
+                let idle = reflex
+                    .started_at
+                    .is_some_and(|started| started.elapsed().as_secs_f32() > CHARGE_IDLE_SECS);
+                if reflex.charge_started_at.is_none() && idle {
+                    reflex.result = Some(ReflexSuccess::Miss);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// Auto-reflex lands a Good as the perfect window opens, so the telegraph still plays.
+// Charge meters never fill on their own, so they resolve straight away.
+pub fn auto_reflex_system(mut query: Query<&mut ReflexState>) {
+    for mut reflex in &mut query {
+        let window_open = reflex.timer.fraction() >= reflex.perfect_window_start
+            || reflex.visual_type == ReflexVisualType::ChargeMeter;
+        if reflex.auto && reflex.result.is_none() && window_open {
+            reflex.result = Some(ReflexSuccess::Good);
+        }
+    }
+}
+
+// Lights the reflex up gold while the perfect window is open and beeps as it opens
+pub fn reflex_cue_system(
+    mut commands: Commands,
+    settings: Res<ReflexSettings>,
+    mut pitches: ResMut<Assets<Pitch>>,
+    mut query: Query<(&mut ReflexState, &mut BorderColor, &mut BackgroundColor), With<ReflexUI>>,
+) {
+    for (mut reflex, mut border, mut background) in &mut query {
+        let ratio = reflex.timer.fraction();
+        let in_window = reflex.result.is_none()
+            && ratio >= reflex.perfect_window_start
+            && ratio <= reflex.perfect_window_end;
+
+        if settings.visual_cue {
+            let color = if in_window {
+                Color::srgb(1.0, 0.85, 0.2)
+            } else {
+                Color::WHITE
+            };
+            match reflex.visual_type {
+                ReflexVisualType::ShrinkingRing => border.0 = color,
+                ReflexVisualType::MovingTarget => {}
+                _ => background.0 = color,
+            }
+        }
+
+        if in_window && !reflex.cue_played {
+            reflex.cue_played = true;
+            if settings.audio_cue {
+                commands.spawn(PitchBundle {
+                    source: pitches.add(Pitch::new(880.0, Duration::from_millis(120))),
+                    settings: PlaybackSettings::DESPAWN,
+                });
+            }
+        }
+    }
+}
+
+pub fn random_direction(rng: &mut impl Rng) -> ReflexDirection {
+    match rng.gen_range(0..4) {
+        0 => ReflexDirection::Up,
+        1 => ReflexDirection::Down,
+        2 => ReflexDirection::Left,
+        _ => ReflexDirection::Right,
+    }
+}
+
+// Spawns a bar-style reflex in the middle of the screen, or the moving target on its own.
+// The defensive ring keeps its own spawner in the enemy turn.
+pub fn spawn_reflex_challenge(commands: &mut Commands, window: Vec2, reflex: ReflexState) {
+    if reflex.visual_type == ReflexVisualType::MovingTarget {
+        let start = moving_target_center(0.0, window);
+        commands.spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(start.x - MOVING_TARGET_SIZE / 2.0),
+                    top: Val::Px(start.y - MOVING_TARGET_SIZE / 2.0),
+                    width: Val::Px(MOVING_TARGET_SIZE),
+                    height: Val::Px(MOVING_TARGET_SIZE),
+                    border: UiRect::all(Val::Px(3.0)),
+                    ..default()
+                },
+                background_color: Color::srgba(0.9, 0.2, 0.2, 0.8).into(),
+                border_color: Color::WHITE.into(),
+                z_index: ZIndex::Global(1000),
+                ..default()
+            },
+            reflex,
+            ReflexUI,
+            BattleEntity,
+        ));
+        return;
+    }
+
+    let visual_type = reflex.visual_type;
+    let beats = reflex.beats.clone();
+    let prompt = reflex.prompt;
+    let (window_start, window_end) = (reflex.perfect_window_start, reflex.perfect_window_end);
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(window.x / 2.0 - BAR_WIDTH / 2.0),
+                    bottom: Val::Px(window.y / 2.0 - 20.0),
+                    width: Val::Px(BAR_WIDTH),
+                    height: Val::Px(40.0),
+                    border: UiRect::all(Val::Px(2.0)),
+                    ..default()
+                },
+                border_color: Color::WHITE.into(),
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.8).into(),
+                z_index: ZIndex::Global(1000),
+                ..default()
+            },
+            BattleEntity,
+        ))
+        .with_children(|parent| {
+            if visual_type == ReflexVisualType::MultiTap {
+                // A marker on every beat instead of a single window
+                for beat in &beats {
+                    parent.spawn(NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            left: Val::Percent((beat - TAP_PERFECT_WINDOW) * 100.0),
+                            width: Val::Percent(TAP_PERFECT_WINDOW * 200.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        background_color: Color::srgba(0.0, 1.0, 0.0, 0.3).into(),
+                        ..default()
+                    });
+                }
+            } else {
+                parent.spawn((
+                    NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            left: Val::Percent(window_start * 100.0),
+                            width: Val::Percent((window_end - window_start) * 100.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        background_color: Color::srgba(0.0, 1.0, 0.0, 0.3).into(),
+                        ..default()
+                    },
+                    PerfectWindowIndicator,
+                ));
+            }
+
+            let label = match (visual_type, prompt) {
+                (ReflexVisualType::DirectionalPrompt, Some(direction)) => {
+                    format!("Press {}!", direction_label(direction))
+                }
+                (ReflexVisualType::MultiTap, _) => format!("Tap x{}", beats.len()),
+                (ReflexVisualType::ChargeMeter, _) => "Hold, then release".to_string(),
+                _ => String::new(),
+            };
+            if !label.is_empty() {
+                parent.spawn(
+                    TextBundle::from_section(
+                        label,
+                        TextStyle {
+                            font: Handle::default(),
+                            font_size: 28.0,
+                            color: Color::srgb(1.0, 0.85, 0.2),
+                        },
+                    )
+                    .with_style(Style {
+                        position_type: PositionType::Absolute,
+                        bottom: Val::Px(46.0),
+                        ..default()
+                    }),
+                );
+            }
+
+            // The charge meter fills from the left; everything else slides across
+            let style = if visual_type == ReflexVisualType::ChargeMeter {
+                Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(0.0),
+                    top: Val::Px(0.0),
+                    width: Val::Percent(0.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                }
+            } else {
+                Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(0.0),
+                    top: Val::Px(0.0),
+                    width: Val::Px(10.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                }
+            };
+            parent.spawn((
+                NodeBundle {
+                    style,
+                    background_color: Color::WHITE.into(),
+                    ..default()
+                },
+                reflex,
+                ReflexUI,
+            ));
+        });
+}
+
+// Where the sliding marker or charge fill sits at this point in the reflex
+pub fn update_reflex_bar(style: &mut Style, reflex: &ReflexState, window: Vec2) {
+    let ratio = reflex.timer.fraction();
+    match reflex.visual_type {
+        ReflexVisualType::ChargeMeter => style.width = Val::Percent(ratio * 100.0),
+        ReflexVisualType::MovingTarget => {
+            let centre = moving_target_center(ratio, window);
+            style.left = Val::Px(centre.x - MOVING_TARGET_SIZE / 2.0);
+            style.top = Val::Px(centre.y - MOVING_TARGET_SIZE / 2.0);
+        }
+        _ => style.left = Val::Px(BAR_WIDTH * ratio),
+    }
+}
@@ -1,9 +1,19 @@
+use crate::components::BurningBuff;
 use crate::components::Card;
 use crate::components::CharacterClass;
+use crate::components::DeckService;
+use crate::components::EnemyKind;
 use crate::components::NodeType;
+use crate::components::RouteCriterion;
+use crate::components::SpellElement;
+use crate::event_engine::EventDefinition;
 use crate::item_potions::Potion;
 use crate::item_relics::Relic;
+use crate::spell_recipes::SpellRecipe;
+use crate::states::GameState;
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
 
 #[derive(Resource, Default)]
 pub struct Deck {
@@ -20,6 +30,8 @@ pub struct MapNodeData {
     pub node_type: NodeType,
     pub next_indices: Vec<usize>,
     pub visible: bool,
+    pub resolved: Option<NodeType>, // What an Unknown node turned out to be once entered
+    pub burning: Option<BurningBuff>, // Set on the single burning elite of each map
 }
 
 #[derive(Resource, Default)]
@@ -29,11 +41,67 @@ pub struct GameMap {
     pub visited_path: Vec<(usize, usize)>,
 }
 
+// Route planner settings and the nodes the player pinned on this act's map
+#[derive(Resource, Default)]
+pub struct MapPlanner {
+    pub criterion: Option<RouteCriterion>,
+    pub waypoints: Vec<(usize, usize)>,
+}
+
+// Pan and zoom of the map screen, kept while the player visits other screens
+#[derive(Resource)]
+pub struct MapView {
+    pub pan: Vec2,
+    pub zoom: f32,
+    pub centered_on: Option<(usize, usize)>, // Node the view last scrolled to on entering the map
+}
+
+impl Default for MapView {
+    fn default() -> Self {
+        Self {
+            pan: Vec2::ZERO,
+            zoom: 1.0,
+            centered_on: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct EncounterRewards {
+    pub gold: (i32, i32), // Inclusive range
+    pub cards: bool,
+    pub relics: usize,
+    pub relic_choice: bool, // Only one of the offered relics can be taken
+}
+
+impl Default for EncounterRewards {
+    fn default() -> Self {
+        Self {
+            gold: (20, 50),
+            cards: true,
+            relics: 0,
+            relic_choice: false,
+        }
+    }
+}
+
+// What the next battle contains; set by the map or by an event before entering Battle
+#[derive(Resource, Debug, Clone)]
+pub struct Encounter {
+    pub enemies: Vec<EnemyKind>,
+    pub background: String,
+    pub burning: Option<BurningBuff>,
+    pub rewards: EncounterRewards,
+}
+
 #[derive(Resource, Default)]
 pub struct RewardStore {
     pub generated: bool,
     pub gold_reward: Option<i32>,
     pub card_choices: Option<Vec<Card>>,
+    pub relic_rewards: Vec<Relic>,
+    pub relic_choice: bool,
 }
 
 #[derive(Resource, Default)]
@@ -42,9 +110,157 @@ pub struct ShopStore {
     pub cards: Vec<Option<(Card, i32)>>,
     pub relics: Vec<Option<(Relic, i32)>>,
     pub potions: Vec<Option<(Potion, i32)>>,
+    pub sale_index: Option<usize>,
+    pub restocks: u32, // Paid restocks this visit
+    pub haggled: bool,
+    pub merchant_line: Option<String>, // Overrides the gold-based greeting
+}
+
+// Chance in percent of an Unknown node not being an event. Each miss makes the
+// other outcomes more likely; rolling one resets it back to its base chance.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct UnknownOdds {
+    pub battle: u32,
+    pub shop: u32,
+    pub treasure: u32,
+}
+
+impl Default for UnknownOdds {
+    fn default() -> Self {
+        Self {
+            battle: 10,
+            shop: 3,
+            treasure: 2,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct EventLibrary {
+    pub events: Vec<EventDefinition>,
+}
+
+// Elemental recipes and cancelling pairs, plus which recipes this run has triggered
+#[derive(Resource)]
+pub struct Spellbook {
+    pub recipes: Vec<SpellRecipe>,
+    pub opposites: Vec<(SpellElement, SpellElement)>,
+    pub discovered: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReflexDifficulty {
+    Relaxed,
+    #[default]
+    Normal,
+    Hard,
+}
+
+// Player options for reflex minigames, saved between sessions
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct ReflexSettings {
+    pub difficulty: ReflexDifficulty,
+    // Every reflex lands as a Good without any input
+    pub auto_reflex: bool,
+    pub slow_motion: bool,
+    pub visual_cue: bool,
+    pub audio_cue: bool,
+    // Average lateness of the player's inputs, measured on the calibration screen
+    pub input_offset_ms: f32,
+}
+
+impl Default for ReflexSettings {
+    fn default() -> Self {
+        Self {
+            difficulty: ReflexDifficulty::Normal,
+            auto_reflex: false,
+            slow_motion: false,
+            visual_cue: true,
+            audio_cue: false,
+            input_offset_ms: 0.0,
+        }
+    }
+}
+
+// Taps collected on the calibration screen, timed against its metronome
+#[derive(Resource)]
+pub struct CalibrationSession {
+    pub started_at: Instant,
+    pub offsets: Vec<f32>,
+}
+
+// The event being played through and the page it is on
+#[derive(Resource)]
+pub struct ActiveEvent {
+    pub id: String,
+    pub page: String,
+    pub after_pick: Option<String>, // Page to move on to once the card picker is confirmed
+}
+
+// Once-per-run events that have already been seen
+#[derive(Resource, Default)]
+pub struct EventHistory {
+    pub seen: Vec<String>,
+}
+
+// The card picker screen: which service to run, what it costs and where to go afterwards
+#[derive(Resource)]
+pub struct CardPicker {
+    pub service: DeckService,
+    pub cost: i32,
+    pub hp_cost: i32,
+    pub return_state: GameState,
+    pub cancel_state: GameState,
+}
+
+// Number of card removals bought from shops this run; each one costs more
+#[derive(Resource, Default)]
+pub struct ShopServices {
+    pub removals: u32,
+}
+
+pub struct PurchaseRecord {
+    pub act: usize,
+    pub item: String,
+    pub gold: i32,
+}
+
+// Shop history for the end-of-run summary
+#[derive(Resource, Default)]
+pub struct RunStats {
+    pub purchases: Vec<PurchaseRecord>,
+    pub sales: Vec<PurchaseRecord>,
+}
+
+impl RunStats {
+    pub fn record_purchase(&mut self, act: usize, item: impl Into<String>, gold: i32) {
+        self.purchases.push(PurchaseRecord {
+            act,
+            item: item.into(),
+            gold,
+        });
+    }
+
+    pub fn record_sale(&mut self, act: usize, item: impl Into<String>, gold: i32) {
+        self.sales.push(PurchaseRecord {
+            act,
+            item: item.into(),
+            gold,
+        });
+    }
+
+    pub fn gold_spent(&self) -> i32 {
+        self.purchases.iter().map(|record| record.gold).sum()
+    }
+
+    pub fn gold_from_sales(&self) -> i32 {
+        self.sales.iter().map(|record| record.gold).sum()
+    }
 }
 
 #[derive(Resource, Default)]
 pub struct RunState {
     pub character_class: CharacterClass,
+    pub act: usize, // 1-based, see acts::ACT_COUNT
 }
@@ -2,14 +2,28 @@ use bevy::color::Alpha;
 use bevy::prelude::*;
 use rand::Rng;
 use rand::thread_rng;
-
-use crate::cli::{EndTurnRequest, PlayCardRequest, TriggerReflexRequest};
+use std::time::Instant;
+
+use crate::classes;
+use crate::classes::duelist::{combo_after_turn, combo_gained, spend_combo};
+use crate::classes::spellweaver::add_free_essence;
+use crate::cli::{EndTurnRequest, PlayCardRequest, ReflexInput, TriggerReflexRequest};
+use crate::combat_math::{
+    OVERLOAD_BACKLASH, apply_spell_elements, card_base_block, card_base_damage, chain_damage,
+    chains_to_next_enemy, cleanse_debuffs, hits_all_enemies, player_attack_damage,
+    player_block_gain, shadow_lifesteal, spell_elements, vulnerable_damage, would_overload,
+};
 use crate::common::spawn_card_visual;
 use crate::components::*;
-use crate::enemies::generate_enemy_move;
+use crate::enemies::{MAX_ENEMY_SLOTS, apply_burning_buff, element_affinity, spawn_enemy};
 use crate::item_potions::{Potion, get_potion_visuals};
 use crate::item_relics::Relic;
+use crate::reflex::{
+    handle_reflex_input, multi_tap_beats, random_direction, spawn_reflex_challenge,
+    update_reflex_bar,
+};
 use crate::resources::*;
+use crate::spell_recipes::{apply_recipe_status, check_recipes, describe_effect, opposite_element};
 use crate::states::*;
 #[derive(Component)]
 pub struct CardAnimating {
@@ -23,69 +37,30 @@ pub struct CardAnimating {
 #[derive(Component)]
 pub struct FirstTurn;
 
+// Arcane Focus hasn't been spent on a spell yet this combat
+#[derive(Component)]
+pub struct ArcaneFocusCharge;
+
 pub fn setup_battle(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut next_turn_state: ResMut<NextState<TurnState>>,
-    game_map: Res<GameMap>,
+    encounter: Res<Encounter>,
     deck: Res<Deck>,
     discard: Res<DiscardPile>,
     mut reward_store: ResMut<RewardStore>,
     player_query: Query<&RelicStore, With<Player>>,
+    run_state: Res<RunState>,
 ) {
     println!("Setting up battle...");
 
-    // Determine Enemy Type
-    let (level, node_type) = if let Some((l, i)) = game_map.current_node {
-        (l, game_map.levels[l][i].node_type)
-    } else {
-        (0, NodeType::Battle)
-    };
-
-    let mut rng = thread_rng();
-    let mut enemies_to_spawn = Vec::new();
-
-    match node_type {
-        NodeType::Boss => enemies_to_spawn.push(EnemyKind::Dragon),
-        NodeType::Elite => enemies_to_spawn.push(EnemyKind::DarkKnight),
-        _ => {
-            if level >= 3 {
-                if rng.gen_bool(0.6) {
-                    enemies_to_spawn.push(EnemyKind::Orc);
-                    enemies_to_spawn.push(EnemyKind::Goblin);
-                } else {
-                    enemies_to_spawn.push(EnemyKind::Orc);
-                }
-            } else {
-                if rng.gen_bool(0.5) {
-                    enemies_to_spawn.push(EnemyKind::Goblin);
-                    enemies_to_spawn.push(EnemyKind::Goblin);
-                    if rng.gen_bool(0.3) {
-                        enemies_to_spawn.push(EnemyKind::Goblin);
-                    }
-                } else {
-                    enemies_to_spawn.push(EnemyKind::Orc);
-                }
-            }
-        }
-    };
+    let enemies_to_spawn = &encounter.enemies;
+    let burning = encounter.burning;
 
     // Spawn Background
-    // Use the first enemy type to determine background
-    let bg_image = if let Some(first) = enemies_to_spawn.first() {
-        match first {
-            EnemyKind::Dragon => "images/backgrounds/DragonLayer.jpg",
-            EnemyKind::Goblin => "images/backgrounds/PoisonCave.jpg",
-            EnemyKind::Orc => "images/backgrounds/RuinedForest.jpg",
-            _ => "images/backgrounds/Battlefield.jpg",
-        }
-    } else {
-        "images/backgrounds/Battlefield.jpg"
-    };
-
     commands.spawn((
         SpriteBundle {
-            texture: asset_server.load(bg_image),
+            texture: asset_server.load(encounter.background.clone()),
             transform: Transform::from_xyz(0.0, 0.0, -100.0),
             ..default()
         },
@@ -101,140 +76,23 @@ pub fn setup_battle(
             initial_weak = 1;
             println!("Bag of Marbles applied 1 Weak!");
         }
+        if relics.relics.contains(&Relic::ArcaneFocus) {
+            commands.spawn((ArcaneFocusCharge, BattleEntity));
+        }
     }
 
     // Spawn Enemies
     for (i, enemy_kind) in enemies_to_spawn.iter().enumerate() {
-        let (hp, name, enemy_sprite) = match enemy_kind {
-            EnemyKind::Goblin => (20, "Goblin", "images/enemies/Goblin.png"),
-            EnemyKind::Orc => (40, "Orc", "images/enemies/Orc.png"),
-            EnemyKind::Dragon => (150, "Dragon", "images/enemies/Dragon.png"),
-            EnemyKind::DarkKnight => (80, "Dark Knight", "images/enemies/DarkKnight.png"),
-        };
-
-        let initial_move = generate_enemy_move(*enemy_kind, false);
-        let x_offset = 50.0 + (i as f32 * 250.0);
-
-        let mut entity_cmds = commands.spawn((
-            Enemy { kind: *enemy_kind },
-            Health {
-                current: hp,
-                max: hp,
-            },
-            Block { value: 0 },
-            StatusStore {
-                weak: initial_weak,
-                ..default()
-            },
-            initial_move,
-            BattleEntity,
-            Interaction::default(), // Allow clicking
-            Tooltip {
-                text: String::new(),
-            },
-            NodeBundle {
-                style: Style {
-                    position_type: PositionType::Absolute,
-                    top: Val::Px(50.0),
-                    right: Val::Px(x_offset),
-                    align_items: AlignItems::Center,
-                    flex_direction: FlexDirection::Column,
-                    border: UiRect::all(Val::Px(2.0)),
-                    ..default()
-                },
-                background_color: Color::srgba(0.0, 0.0, 0.0, 0.0).into(),
-                ..default()
-            },
-        ));
-
+        if i >= MAX_ENEMY_SLOTS {
+            break;
+        }
+        let entity = spawn_enemy(&mut commands, &asset_server, *enemy_kind, i, initial_weak);
+        if let Some(buff) = burning {
+            apply_burning_buff(&mut commands, entity, *enemy_kind, buff, initial_weak);
+        }
         if i == 0 {
-            entity_cmds.insert(Selected);
+            commands.entity(entity).insert(Selected);
         }
-
-        entity_cmds.with_children(|parent| {
-            parent.spawn(ImageBundle {
-                style: Style {
-                    width: Val::Auto,
-                    height: Val::Auto,
-                    margin: UiRect::bottom(Val::Px(10.0)),
-                    ..default()
-                },
-                image: asset_server.load(enemy_sprite).into(),
-                ..default()
-            });
-
-            parent.spawn(TextBundle::from_section(
-                name,
-                TextStyle {
-                    font: Handle::default(),
-                    font_size: 30.0,
-                    color: Color::WHITE,
-                },
-            ));
-
-            // Health & Block Row
-            parent
-                .spawn(NodeBundle {
-                    style: Style {
-                        flex_direction: FlexDirection::Row,
-                        justify_content: JustifyContent::Center,
-                        column_gap: Val::Px(10.0),
-                        ..default()
-                    },
-                    ..default()
-                })
-                .with_children(|row| {
-                    row.spawn((
-                        TextBundle::from_section(
-                            format!("HP: {}/{}", hp, hp),
-                            TextStyle {
-                                font: Handle::default(),
-                                font_size: 20.0,
-                                color: Color::srgb(0.9, 0.3, 0.3),
-                            },
-                        ),
-                        EnemyHealthText,
-                    ));
-                    row.spawn((
-                        TextBundle::from_section(
-                            "Block: 0",
-                            TextStyle {
-                                font: Handle::default(),
-                                font_size: 20.0,
-                                color: Color::srgb(0.5, 0.5, 1.0),
-                            },
-                        ),
-                        EnemyBlockText,
-                    ));
-                });
-
-            // Status
-            parent.spawn((
-                NodeBundle {
-                    style: Style {
-                        flex_direction: FlexDirection::Row,
-                        align_items: AlignItems::Center,
-                        min_height: Val::Px(20.0),
-                        margin: UiRect::top(Val::Px(5.0)),
-                        ..default()
-                    },
-                    ..default()
-                },
-                EnemyStatusText,
-            ));
-
-            parent.spawn((
-                TextBundle::from_section(
-                    "Planning...",
-                    TextStyle {
-                        font: Handle::default(),
-                        font_size: 20.0,
-                        color: Color::srgb(1.0, 1.0, 0.0),
-                    },
-                ),
-                EnemyIntentText,
-            ));
-        });
     }
 
     // Spawn a "Hand" UI container
@@ -339,32 +197,8 @@ pub fn setup_battle(
                 ),
                 PlayerEnergyText,
             ));
-            // Player Combo
-            parent.spawn((
-                TextBundle::from_section(
-                    "Combo: 0",
-                    TextStyle {
-                        font: Handle::default(),
-                        font_size: 24.0,
-                        color: Color::srgb(1.0, 0.5, 0.0),
-                    },
-                ),
-                PlayerComboText,
-            ));
-            // Player Spell Container
-            parent.spawn((
-                NodeBundle {
-                    style: Style {
-                        flex_direction: FlexDirection::Row,
-                        align_items: AlignItems::Center,
-                        min_height: Val::Px(30.0),
-                        margin: UiRect::top(Val::Px(5.0)),
-                        ..default()
-                    },
-                    ..default()
-                },
-                PlayerSpellContainer,
-            ));
+            // Class resources (combo counter, spell essences, ...)
+            classes::player_class(run_state.character_class).spawn_hud(parent);
             // Player Gold
             parent.spawn((
                 TextBundle::from_section(
@@ -545,24 +379,23 @@ pub fn draw_cards_system(
     >,
     mut flash_query: Query<&mut BackgroundColor, With<DamageFlashUi>>,
     first_turn_query: Query<Entity, With<FirstTurn>>,
+    arcane_focus_query: Query<(), With<ArcaneFocusCharge>>,
     run_state: Res<RunState>,
 ) {
     if let Ok((mut energy, mut mana, mut block, mut health, mut status, relics, mut active_spell)) =
         player_query.get_single_mut()
     {
         // Reset Energy / Mana
-        if run_state.character_class == CharacterClass::Spellweaver {
-            if let Some(ref mut m) = mana {
-                m.current += 2;
-            }
-            // No cap
-        } else {
-            energy.current = energy.max;
-        }
+        classes::player_class(run_state.character_class)
+            .refresh_resources(&mut energy, mana.as_deref_mut());
 
         // Reset Active Spell for new turn
         if let Some(ref mut spell) = active_spell {
             **spell = ActiveSpell::default();
+            // The free essence waits on every fresh spell until one is cast
+            if !arcane_focus_query.is_empty() {
+                add_free_essence(spell, &mut thread_rng());
+            }
         }
 
         // Reset Block (unless Anchor on first turn)
@@ -593,10 +426,32 @@ pub fn draw_cards_system(
         }
     }
 
-    let mut hand_cards = Vec::new();
-
     // Draw 5 cards
-    for _ in 0..5 {
+    let hand_cards = draw_from_deck(&mut deck, &mut discard, 5);
+
+    // Spawn cards as children of HandContainer
+    if let Ok(container) = hand_container_query.get_single() {
+        spawn_hand_cards(&mut commands, container, &asset_server, &hand_cards);
+    }
+
+    // Output Hand for CLI Users
+    println!("--- PLAYER TURN START ---");
+    println!("Hand:");
+    for (i, card) in hand_cards.iter().enumerate() {
+        println!(
+            "  [{}] {} (Cost: {}, Dmg: {}, Blk: {})",
+            i, card.name, card.cost, card.damage, card.block
+        );
+    }
+    println!("--- Type 'help' for commands ---");
+
+    next_turn_state.set(TurnState::PlayerTurn);
+}
+
+// Pops cards off the deck, reshuffling the discard pile in when it runs out
+fn draw_from_deck(deck: &mut Deck, discard: &mut DiscardPile, count: u32) -> Vec<Card> {
+    let mut drawn = Vec::new();
+    for _ in 0..count {
         if deck.cards.is_empty() {
             if discard.cards.is_empty() {
                 break;
@@ -610,43 +465,55 @@ pub fn draw_cards_system(
         }
 
         if let Some(card) = deck.cards.pop() {
-            hand_cards.push(card);
+            drawn.push(card);
         }
     }
+    drawn
+}
 
-    // Spawn cards as children of HandContainer
-    if let Ok(container) = hand_container_query.get_single() {
-        let ui_hand = hand_cards.clone();
-        commands.entity(container).with_children(|parent| {
-            for card in ui_hand {
-                spawn_card_visual(
-                    parent,
-                    &asset_server,
-                    &card,
-                    (
-                        card.clone(),
-                        BaseColor(Color::srgb(0.15, 0.15, 0.2)),
-                        Button,
-                        Interaction::default(),
-                    ),
-                    |_| {},
-                );
-            }
-        });
-    }
+fn spawn_hand_cards(
+    commands: &mut Commands,
+    container: Entity,
+    asset_server: &AssetServer,
+    cards: &[Card],
+) {
+    commands.entity(container).with_children(|parent| {
+        for card in cards {
+            spawn_card_visual(
+                parent,
+                asset_server,
+                card,
+                (
+                    card.clone(),
+                    BaseColor(Color::srgb(0.15, 0.15, 0.2)),
+                    Button,
+                    Interaction::default(),
+                ),
+                |_| {},
+            );
+        }
+    });
+}
 
-    // Output Hand for CLI Users
-    println!("--- PLAYER TURN START ---");
-    println!("Hand:");
-    for (i, card) in hand_cards.iter().enumerate() {
-        println!(
-            "  [{}] {} (Cost: {}, Dmg: {}, Blk: {})",
-            i, card.name, card.cost, card.damage, card.block
-        );
+// Draws cards requested mid-turn (e.g. by finishers)
+pub fn pending_draw_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut deck: ResMut<Deck>,
+    mut discard: ResMut<DiscardPile>,
+    pending_query: Query<(Entity, &PendingDraw)>,
+    hand_container_query: Query<Entity, With<HandContainer>>,
+) {
+    for (entity, pending) in &pending_query {
+        commands.entity(entity).despawn();
+        let drawn = draw_from_deck(&mut deck, &mut discard, pending.count);
+        for card in &drawn {
+            println!("Drew {}", card.name);
+        }
+        if let Ok(container) = hand_container_query.get_single() {
+            spawn_hand_cards(&mut commands, container, &asset_server, &drawn);
+        }
     }
-    println!("--- Type 'help' for commands ---");
-
-    next_turn_state.set(TurnState::PlayerTurn);
 }
 
 pub fn discard_hand_system(
@@ -654,18 +521,27 @@ pub fn discard_hand_system(
     mut next_turn_state: ResMut<NextState<TurnState>>,
     mut discard: ResMut<DiscardPile>,
     query: Query<(Entity, &Card)>,
-    mut player_status_query: Query<&mut StatusStore, With<Player>>,
+    mut player_status_query: Query<
+        (&mut StatusStore, &RelicStore, Option<&mut PlayerCombo>),
+        With<Player>,
+    >,
 ) {
     for (entity, card) in query.iter() {
         discard.cards.push(card.clone());
         commands.entity(entity).despawn_recursive();
     }
 
-    // Decrement Player Weak at end of turn
-    if let Ok(mut status) = player_status_query.get_single_mut() {
+    // Decrement Player debuffs at end of turn
+    if let Ok((mut status, relics, combo)) = player_status_query.get_single_mut() {
+        if let Some(mut combo) = combo {
+            combo.current = combo_after_turn(combo.current, &relics.relics);
+        }
         if status.weak > 0 {
             status.weak -= 1;
         }
+        if status.frail > 0 {
+            status.frail -= 1;
+        }
     }
 
     next_turn_state.set(TurnState::EnemyTurn);
@@ -724,13 +600,13 @@ pub fn process_play_card_requests(
     hand_container_query: Query<&Children, With<HandContainer>>,
     card_query: Query<(&Card, &GlobalTransform), Without<CardAnimating>>,
     mut animating_query: Query<(Entity, &mut Style, &mut CardAnimating)>,
-    mut targetable_enemies: Query<Entity, With<Enemy>>,
+    arcane_focus_query: Query<Entity, With<ArcaneFocusCharge>>,
     mut player_query: Query<
         (
             &mut Energy,
             Option<&mut Mana>,
             Option<&mut ActiveSpell>,
-            &StatusStore,
+            &mut StatusStore,
             &RelicStore,
             &mut Health,
             Option<&mut PlayerCombo>,
@@ -740,7 +616,7 @@ pub fn process_play_card_requests(
     mut player_block_query: Query<&mut Block, (With<Player>, Without<Enemy>)>,
     mut block_flash_query: Query<&mut BackgroundColor, With<BlockFlashUi>>,
     window_query: Query<&Window>,
-    mut game_map: ResMut<GameMap>,
+    mut spellbook: ResMut<Spellbook>,
     time: Res<Time>,
     run_state: Res<RunState>,
     mut discard_pile: ResMut<DiscardPile>,
@@ -761,28 +637,29 @@ pub fn process_play_card_requests(
             if ev.hand_index < children.len() {
                 let card_entity = children[ev.hand_index];
                 if let Ok((card_data, transform)) = card_query.get(card_entity) {
+                    if card_data.unplayable {
+                        println!("{} can't be played.", card_data.name);
+                        continue;
+                    }
+
                     // Pre-requisites (mana/energy)
-                    let (mut energy, mut mana, _, _, _, _, _) =
+                    let (mut energy, mut mana, _, _, _, mut health, _) =
                         if let Ok(e) = player_query.get_single_mut() {
                             e
                         } else {
                             continue;
                         };
 
-                    if run_state.character_class == CharacterClass::Spellweaver {
-                        if let Some(ref mut m) = mana {
-                            if m.current < card_data.cost {
-                                println!("Not enough mana!");
-                                continue;
-                            }
-                            m.current -= card_data.cost;
-                        }
-                    } else {
-                        if energy.current < card_data.cost {
-                            println!("Not enough energy!");
-                            continue;
-                        }
-                        energy.current -= card_data.cost;
+                    if let Err(reason) = classes::player_class(run_state.character_class)
+                        .pay_card_cost(
+                            card_data.cost,
+                            &mut energy,
+                            mana.as_deref_mut(),
+                            &mut health,
+                        )
+                    {
+                        println!("{}", reason);
+                        continue;
                     }
 
                     // For targeted spells, CLI overrides visual selected
@@ -838,10 +715,10 @@ pub fn process_play_card_requests(
             println!("Player plays: {}", card_data.name);
 
             let (
-                _,
-                _,
+                mut player_energy,
+                mut player_mana,
                 mut active_spell,
-                player_status,
+                mut player_status,
                 player_relics,
                 mut player_health,
                 mut player_combo,
@@ -855,16 +732,8 @@ pub fn process_play_card_requests(
             if card_data.is_spell_modifier {
                 if let Some(ref mut spell) = active_spell {
                     // Check for cancellation
-                    let opposite = match card_data.element {
-                        SpellElement::Fire => SpellElement::Ice,
-                        SpellElement::Ice => SpellElement::Fire,
-                        SpellElement::Wind => SpellElement::Stone,
-                        SpellElement::Stone => SpellElement::Wind,
-                        _ => SpellElement::Neutral,
-                    };
-
                     let mut cancelled = false;
-                    if opposite != SpellElement::Neutral {
+                    if let Some(opposite) = opposite_element(&spellbook, card_data.element) {
                         if let Some(idx) = spell.essences.iter().position(|e| e.element == opposite)
                         {
                             // Remove the opposite essence
@@ -886,7 +755,15 @@ pub fn process_play_card_requests(
                         }
                     }
 
-                    if !cancelled {
+                    if !cancelled && would_overload(spell) {
+                        let backlash = OVERLOAD_BACKLASH.min(player_health.current - 1).max(0);
+                        player_health.current -= backlash;
+                        **spell = ActiveSpell::default();
+                        println!(
+                            "Overload! The spell collapses, dealing {} damage to you",
+                            backlash
+                        );
+                    } else if !cancelled {
                         spell.bonus_damage += card_data.damage;
                         spell.bonus_block += card_data.block;
                         spell.element = card_data.element;
@@ -897,40 +774,27 @@ pub fn process_play_card_requests(
                         });
                         spell.essence_history.push(card_data.element);
 
-                        let history_len = spell.essence_history.len();
-                        if history_len >= 3 {
-                            let third = spell.essence_history[history_len - 1].clone();
-                            let second = spell.essence_history[history_len - 2].clone();
-                            let first = spell.essence_history[history_len - 3].clone();
-
-                            if first == SpellElement::Fire
-                                && second == SpellElement::Ice
-                                && third == SpellElement::Wind
-                            {
-                                spell.bonus_damage += 20;
-                                println!("✨ Elemental Combo (Thermal Updraft)! +20 Damage!");
-                                spell.essence_history.clear();
-                            } else if first == SpellElement::Wind
-                                && second == SpellElement::Fire
-                                && third == SpellElement::Stone
-                            {
-                                spell.bonus_block += 20;
-                                println!("✨ Elemental Combo (Volcanic Ash)! +20 Block!");
-                                spell.essence_history.clear();
-                            } else if first != second
-                                && second != third
-                                && first != third
-                                && first != SpellElement::Neutral
-                                && second != SpellElement::Neutral
-                                && third != SpellElement::Neutral
-                            {
-                                spell.bonus_damage += 10;
-                                spell.bonus_block += 10;
-                                println!("✨ Elemental Convergence! +10 Damage, +10 Block!");
-                                spell.essence_history.clear();
-                            } else {
-                                spell.essence_history.remove(0);
+                        if let Some(recipe) = check_recipes(&spellbook, &mut spell.essence_history)
+                        {
+                            let effect = &recipe.effect;
+                            spell.bonus_damage += effect.bonus_damage;
+                            spell.bonus_block += effect.bonus_block;
+                            if let Some(status) = effect.status {
+                                for (_, _, _, mut enemy_status, _, _) in &mut enemy_query {
+                                    apply_recipe_status(status, &mut enemy_status);
+                                }
+                            }
+                            if effect.draw > 0 {
+                                commands.spawn((PendingDraw { count: effect.draw }, BattleEntity));
+                            }
+                            if let Some(ref mut mana) = player_mana {
+                                mana.current += effect.mana_refund;
                             }
+                            if !spellbook.discovered.contains(&recipe.id) {
+                                spellbook.discovered.push(recipe.id.clone());
+                                println!("New recipe discovered: {}!", recipe.name);
+                            }
+                            println!("✨ {}! {}", recipe.name, describe_effect(effect));
                         }
                         println!(
                             "Essence Added! Current Bonus: +{} Dmg / +{} Blk ({:?})",
@@ -944,17 +808,31 @@ pub fn process_play_card_requests(
                 continue;
             }
 
-            let mut final_damage = card_data.damage;
-            let mut final_block = card_data.block;
+            let mut final_damage =
+                card_base_damage(card_data, &player_health, active_spell.as_deref());
+            let mut final_block = card_base_block(card_data, active_spell.as_deref());
 
+            let mut hits = 1;
+            let mut finisher_stun = 0;
             if let Some(mut combo) = player_combo {
-                if card_data.finisher_combo_cost > 0 {
-                    if combo.current >= card_data.finisher_combo_cost {
-                        combo.current -= card_data.finisher_combo_cost;
-                        final_damage *= 2;
+                if card_data.finisher != Finisher::None {
+                    if let Some(spent) = spend_combo(card_data, &mut combo.current) {
+                        match card_data.finisher {
+                            Finisher::DoubleDamage => final_damage *= 2,
+                            Finisher::BlockPerPoint(per_point) => {
+                                final_block += per_point * spent as i32
+                            }
+                            Finisher::DrawPerPoint => {
+                                commands.spawn((PendingDraw { count: spent }, BattleEntity));
+                            }
+                            Finisher::EnergyPerPoint => player_energy.current += spent as i32,
+                            Finisher::MultiHit => hits = spent,
+                            Finisher::Stun => finisher_stun = 1,
+                            Finisher::None => {}
+                        }
                         println!(
-                            "Finisher triggered! Consumed {} Combo, Final Damage: {}",
-                            card_data.finisher_combo_cost, final_damage
+                            "Finisher triggered! Consumed {} Combo ({:?})",
+                            spent, card_data.finisher
                         );
                     } else {
                         println!("Not enough Combo points for Finisher.");
@@ -962,33 +840,21 @@ pub fn process_play_card_requests(
                 }
 
                 if card_data.combo_points_granted > 0 {
-                    combo.current += card_data.combo_points_granted;
-                    println!(
-                        "Gained {} Combo points, total: {}",
-                        card_data.combo_points_granted, combo.current
-                    );
+                    let gained =
+                        combo_gained(card_data.combo_points_granted, &player_relics.relics);
+                    combo.current += gained;
+                    println!("Gained {} Combo points, total: {}", gained, combo.current);
                 }
             }
 
-            // Spell Flags
-            let mut spell_has_fire = false;
-            let mut spell_has_ice = false;
-            let mut spell_has_wind = false;
-            let mut spell_has_stone = false;
-
+            let mut elements = Vec::new();
             if card_data.is_spell_source {
                 if let Some(ref mut spell) = active_spell {
-                    final_damage += spell.bonus_damage;
-                    final_block += spell.bonus_block;
-
-                    for essence in &spell.essences {
-                        match essence.element {
-                            SpellElement::Fire => spell_has_fire = true,
-                            SpellElement::Ice => spell_has_ice = true,
-                            SpellElement::Wind => spell_has_wind = true,
-                            SpellElement::Stone => spell_has_stone = true,
-                            _ => {}
-                        }
+                    elements = spell_elements(spell);
+                    if elements.contains(&SpellElement::Water)
+                        && cleanse_debuffs(&mut player_status)
+                    {
+                        println!("Water cleanses your debuffs");
                     }
                     println!(
                         "Spell Cast! Total: {} Dmg / {} Blk",
@@ -997,15 +863,16 @@ pub fn process_play_card_requests(
 
                     // Consume Essence
                     **spell = ActiveSpell::default();
+                    for charge in &arcane_focus_query {
+                        commands.entity(charge).despawn();
+                    }
                 }
             }
 
             if final_block > 0 {
                 if let Ok(mut block) = player_block_query.get_single_mut() {
-                    let mut block_gain = final_block;
-                    if player_relics.relics.contains(&Relic::OddlySmoothStone) {
-                        block_gain += 1;
-                    }
+                    let block_gain =
+                        player_block_gain(final_block, &player_relics.relics, &player_status);
                     block.value += block_gain;
                     println!("Player gains {} block", block_gain);
                     for mut bg in &mut block_flash_query {
@@ -1047,7 +914,7 @@ pub fn process_play_card_requests(
 
             // Identify Targets
             let mut target_entities = Vec::new();
-            if spell_has_wind {
+            if hits_all_enemies(&elements) {
                 for (e, _, _, _, _, _) in enemy_query.iter() {
                     target_entities.push(e);
                 }
@@ -1070,114 +937,90 @@ pub fn process_play_card_requests(
                 }
             }
 
-            let mut damage = final_damage;
-            let (_, _, _, player_status, player_relics, _, _) =
+            let (_, _, _, player_status, player_relics, player_health, _) =
                 if let Ok(p) = player_query.get_single_mut() {
                     p
                 } else {
                     continue;
                 };
-
-            if player_relics.relics.contains(&Relic::Vajra) && damage > 0 {
-                damage += 1;
-            }
-            if player_status.strength > 0 && damage > 0 {
-                damage += player_status.strength;
-            }
-            if player_status.weak > 0 {
-                damage = (damage as f32 * 0.75) as i32;
+            let damage = player_attack_damage(
+                final_damage,
+                run_state.character_class,
+                &player_relics.relics,
+                &player_status,
+                &player_health,
+            );
+
+            let mut chain_target = None;
+            if chains_to_next_enemy(&elements) && target_entities.len() == 1 {
+                chain_target = enemy_query
+                    .iter()
+                    .map(|(e, _, _, _, _, _)| e)
+                    .find(|e| *e != target_entities[0]);
             }
 
             if damage > 0 && !target_entities.is_empty() {
-                // Spawn Offensive Reflex
+                // Spawn Offensive Reflex, played the way the card asks for
                 if let Ok(window) = window_query.get_single() {
-                    let cx = window.width() / 2.0;
-                    let cy = window.height() / 2.0;
-
-                    // Container
-                    commands
-                        .spawn((
-                            NodeBundle {
-                                style: Style {
-                                    position_type: PositionType::Absolute,
-                                    left: Val::Px(cx - 200.0),
-                                    bottom: Val::Px(cy - 20.0),
-                                    width: Val::Px(400.0),
-                                    height: Val::Px(40.0),
-                                    border: UiRect::all(Val::Px(2.0)),
-                                    ..default()
-                                },
-                                border_color: Color::WHITE.into(),
-                                background_color: Color::srgba(0.0, 0.0, 0.0, 0.8).into(),
-                                ..default()
+                    let visual_type = match card_data.reflex {
+                        ReflexVisualType::MultiTap if hits < 2 => ReflexVisualType::LinearSlider,
+                        reflex => reflex,
+                    };
+                    let duration = if visual_type == ReflexVisualType::MultiTap {
+                        1.6
+                    } else {
+                        1.0
+                    };
+                    let mut timer = Timer::from_seconds(duration, TimerMode::Once);
+                    // Charge meters only run while held
+                    if visual_type == ReflexVisualType::ChargeMeter {
+                        timer.pause();
+                    }
+                    spawn_reflex_challenge(
+                        &mut commands,
+                        Vec2::new(window.width(), window.height()),
+                        ReflexState {
+                            started_at: None,
+                            timer,
+                            perfect_window_start: 0.7,
+                            perfect_window_end: 0.8,
+                            result: None,
+                            is_defensive: false,
+                            source_entity: None,
+                            target_entity: Some(target_entities[0]),
+                            base_damage: damage,
+                            visual_type,
+                            good_margin: 0.1,
+                            auto: false,
+                            cue_played: false,
+                            tolerance: 1.0,
+                            beats: if visual_type == ReflexVisualType::MultiTap {
+                                multi_tap_beats(hits)
+                            } else {
+                                Vec::new()
                             },
-                            BattleEntity,
-                        ))
-                        .with_children(|parent| {
-                            // Perfect Window Indicator (static)
-                            parent.spawn(NodeBundle {
-                                style: Style {
-                                    position_type: PositionType::Absolute,
-                                    left: Val::Percent(70.0),  // 0.7
-                                    width: Val::Percent(10.0), // 0.8 - 0.7 = 0.1
-                                    height: Val::Percent(100.0),
-                                    ..default()
-                                },
-                                background_color: Color::srgba(0.0, 1.0, 0.0, 0.3).into(),
-                                ..default()
-                            });
-
-                            // Moving Slider
-                            parent.spawn((
-                                NodeBundle {
-                                    style: Style {
-                                        position_type: PositionType::Absolute,
-                                        left: Val::Px(0.0),
-                                        top: Val::Px(0.0),
-                                        width: Val::Px(10.0),
-                                        height: Val::Percent(100.0),
-                                        ..default()
-                                    },
-                                    background_color: Color::WHITE.into(),
-                                    ..default()
-                                },
-                                ReflexState {
-                                    start_time: 0.0,
-                                    timer: Timer::from_seconds(1.0, TimerMode::Once),
-                                    perfect_window_start: 0.7,
-                                    perfect_window_end: 0.8,
-                                    result: None,
-                                    is_defensive: false,
-                                    source_entity: None,
-                                    target_entity: Some(target_entities[0]),
-                                    base_damage: damage,
-                                    visual_type: ReflexVisualType::LinearSlider,
-                                },
-                                ReflexUI,
-                            ));
-                        });
-                }
-
-                let mut elements = Vec::new();
-                if spell_has_fire {
-                    elements.push(SpellElement::Fire);
-                }
-                if spell_has_ice {
-                    elements.push(SpellElement::Ice);
-                }
-                if spell_has_wind {
-                    elements.push(SpellElement::Wind);
-                }
-                if spell_has_stone {
-                    elements.push(SpellElement::Stone);
+                            tap_results: Vec::new(),
+                            prompt: if visual_type == ReflexVisualType::DirectionalPrompt {
+                                Some(random_direction(&mut thread_rng()))
+                            } else {
+                                None
+                            },
+                            charge_started_at: None,
+                        },
+                    );
                 }
 
                 commands.spawn((
                     PendingPlayerAttack {
                         targets: target_entities.clone(),
+                        chain_target,
                         damage,
-                        card: card_data.clone(),
+                        card: Card {
+                            apply_stun: card_data.apply_stun + finisher_stun,
+                            ..card_data.clone()
+                        },
                         spell_elements: elements,
+                        hits,
                     },
                     BattleEntity,
                 ));
@@ -1195,8 +1038,8 @@ pub fn process_play_card_requests(
                         if card_data.apply_weak > 0 {
                             enemy_status.weak += card_data.apply_weak;
                         }
-                        if card_data.apply_stun > 0 {
-                            enemy_status.stun += card_data.apply_stun;
+                        if card_data.apply_stun + finisher_stun > 0 {
+                            enemy_status.stun += card_data.apply_stun + finisher_stun;
                         }
                     }
                 }
@@ -1572,6 +1415,96 @@ pub fn update_status_visuals_system(
                     ));
                 });
         }
+        if status.strength > 0 {
+            parent
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            margin: UiRect::right(Val::Px(5.0)),
+                            padding: UiRect::all(Val::Px(3.0)),
+                            border: UiRect::all(Val::Px(1.0)),
+                            ..default()
+                        },
+                        background_color: Color::srgba(0.8, 0.2, 0.2, 0.5).into(),
+                        border_color: Color::srgb(1.0, 0.3, 0.3).into(),
+                        ..default()
+                    },
+                    Interaction::None,
+                    Tooltip {
+                        text: "Strength: Attacks deal more damage.".to_string(),
+                    },
+                ))
+                .with_children(|p| {
+                    p.spawn(TextBundle::from_section(
+                        format!("Str {}", status.strength),
+                        TextStyle {
+                            font_size: 16.0,
+                            color: Color::WHITE,
+                            font: Handle::default(),
+                        },
+                    ));
+                });
+        }
+        if status.vulnerable > 0 {
+            parent
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            margin: UiRect::right(Val::Px(5.0)),
+                            padding: UiRect::all(Val::Px(3.0)),
+                            border: UiRect::all(Val::Px(1.0)),
+                            ..default()
+                        },
+                        background_color: Color::srgba(0.9, 0.5, 0.1, 0.5).into(),
+                        border_color: Color::srgb(1.0, 0.6, 0.2).into(),
+                        ..default()
+                    },
+                    Interaction::None,
+                    Tooltip {
+                        text: "Vulnerable: Take 50% more damage from attacks.".to_string(),
+                    },
+                ))
+                .with_children(|p| {
+                    p.spawn(TextBundle::from_section(
+                        format!("Vuln {}", status.vulnerable),
+                        TextStyle {
+                            font_size: 16.0,
+                            color: Color::WHITE,
+                            font: Handle::default(),
+                        },
+                    ));
+                });
+        }
+        if status.frail > 0 {
+            parent
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            margin: UiRect::right(Val::Px(5.0)),
+                            padding: UiRect::all(Val::Px(3.0)),
+                            border: UiRect::all(Val::Px(1.0)),
+                            ..default()
+                        },
+                        background_color: Color::srgba(0.4, 0.4, 0.7, 0.5).into(),
+                        border_color: Color::srgb(0.6, 0.6, 0.9).into(),
+                        ..default()
+                    },
+                    Interaction::None,
+                    Tooltip {
+                        text: "Frail: Gain 25% less block from cards.".to_string(),
+                    },
+                ))
+                .with_children(|p| {
+                    p.spawn(TextBundle::from_section(
+                        format!("Frail {}", status.frail),
+                        TextStyle {
+                            font_size: 16.0,
+                            color: Color::WHITE,
+                            font: Handle::default(),
+                        },
+                    ));
+                });
+        }
     };
 
     if !player_query.is_empty() || !player_ui_added.is_empty() {
@@ -1683,6 +1616,9 @@ pub fn cleanup_battle_deck(
 
     deck.cards.append(&mut discard.cards);
 
+    // Status cards only last for the battle they were added in
+    deck.cards.retain(|card| !card.unplayable);
+
     let mut rng = thread_rng();
     use rand::seq::SliceRandom;
     deck.cards.shuffle(&mut rng);
@@ -1702,31 +1638,53 @@ pub fn discard_pile_click_system(
 pub fn reflex_input_system(
     input: Res<ButtonInput<KeyCode>>,
     mouse_input: Res<ButtonInput<MouseButton>>,
+    window_query: Query<&Window>,
     mut ev_reflex: EventWriter<TriggerReflexRequest>,
 ) {
+    let pressed_at = Instant::now();
+    let cursor = window_query
+        .get_single()
+        .ok()
+        .and_then(|window| window.cursor_position());
+    let mut send = |input| {
+        ev_reflex.send(TriggerReflexRequest {
+            input,
+            pressed_at,
+            cursor,
+        });
+    };
+
     if input.just_pressed(KeyCode::Space) || mouse_input.just_pressed(MouseButton::Left) {
-        ev_reflex.send(TriggerReflexRequest);
+        send(ReflexInput::Press);
+    }
+    if input.just_released(KeyCode::Space) || mouse_input.just_released(MouseButton::Left) {
+        send(ReflexInput::Release);
+    }
+    for (key, direction) in [
+        (KeyCode::ArrowUp, ReflexDirection::Up),
+        (KeyCode::ArrowDown, ReflexDirection::Down),
+        (KeyCode::ArrowLeft, ReflexDirection::Left),
+        (KeyCode::ArrowRight, ReflexDirection::Right),
+    ] {
+        if input.just_pressed(key) {
+            send(ReflexInput::Direction(direction));
+        }
     }
 }
 
 pub fn process_reflex_requests(
     mut ev_reflex: EventReader<TriggerReflexRequest>,
     mut query: Query<&mut ReflexState>,
+    settings: Res<ReflexSettings>,
+    window_query: Query<&Window>,
 ) {
-    for _ in ev_reflex.read() {
+    let window = window_query
+        .get_single()
+        .map(|window| Vec2::new(window.width(), window.height()))
+        .unwrap_or_default();
+    for ev in ev_reflex.read() {
         for mut reflex in query.iter_mut() {
-            if reflex.result.is_none() {
-                let ratio = reflex.timer.fraction();
-                if ratio >= reflex.perfect_window_start && ratio <= reflex.perfect_window_end {
-                    reflex.result = Some(ReflexSuccess::Perfect);
-                } else if ratio >= reflex.perfect_window_start - 0.1
-                    && ratio <= reflex.perfect_window_end + 0.1
-                {
-                    reflex.result = Some(ReflexSuccess::Good);
-                } else {
-                    reflex.result = Some(ReflexSuccess::Miss);
-                }
-            }
+            handle_reflex_input(&mut reflex, ev, settings.input_offset_ms, window);
         }
     }
 }
@@ -1753,10 +1711,12 @@ pub fn update_reflex_ui_system(
 
             style.left = Val::Px(cx - current_size / 2.0);
             style.bottom = Val::Px(cy - 150.0 + (max_size - current_size) / 2.0);
-        } else if reflex.visual_type == ReflexVisualType::LinearSlider {
-            // Slider moves from left to right within its parent
-            let max_width = 400.0; // Same as parent width
-            style.left = Val::Px(max_width * reflex.timer.fraction());
+        } else {
+            update_reflex_bar(
+                &mut style,
+                reflex,
+                Vec2::new(window.width(), window.height()),
+            );
         }
     }
 }
@@ -1767,8 +1727,19 @@ pub fn player_attack_animating_system(
     mut next_turn_state: ResMut<NextState<TurnState>>,
     mut reflex_query: Query<(Entity, Option<&Parent>, &mut ReflexState)>,
     pending_query: Query<(Entity, &PendingPlayerAttack)>,
-    mut enemy_query: Query<(Entity, &mut Health, &mut Block, &mut StatusStore), With<Enemy>>,
-    mut player_query: Query<(&RelicStore, &mut Health), (With<Player>, Without<Enemy>)>,
+    mut enemy_query: Query<
+        (
+            Entity,
+            &mut Health,
+            &mut Block,
+            &mut StatusStore,
+            Has<Minion>,
+            Option<&Thorns>,
+        ),
+        With<Enemy>,
+    >,
+    kind_query: Query<&Enemy>,
+    mut player_query: Query<(&RelicStore, &mut Health, &mut Block), (With<Player>, Without<Enemy>)>,
     mut game_map: ResMut<GameMap>,
     time: Res<Time>,
     window_query: Query<&Window>,
@@ -1791,51 +1762,64 @@ pub fn player_attack_animating_system(
                     reflex.result, multiplier, final_damage
                 );
 
-                let spell_has_fire = pending_attack.spell_elements.contains(&SpellElement::Fire);
-                let spell_has_ice = pending_attack.spell_elements.contains(&SpellElement::Ice);
-                let spell_has_stone = pending_attack.spell_elements.contains(&SpellElement::Stone);
                 let spell_has_wind = pending_attack.spell_elements.contains(&SpellElement::Wind);
 
                 let mut burning_snapshot = Vec::new();
                 if spell_has_wind {
-                    for (e, _, _, status) in enemy_query.iter() {
+                    for (e, _, _, status, _, _) in enemy_query.iter() {
                         if status.burning > 0 && pending_attack.targets.contains(&e) {
                             burning_snapshot.push((e, status.burning));
                         }
                     }
                 }
 
-                let mut enemies_remaining = enemy_query.iter().count();
-                for target_entity in &pending_attack.targets {
-                    if let Ok((e, mut hp, mut block, mut status)) =
-                        enemy_query.get_mut(*target_entity)
+                // Only leaders count towards victory; their minions flee with them
+                let mut thorns_damage = 0;
+                let mut enemies_remaining = enemy_query
+                    .iter()
+                    .filter(|(_, hp, _, _, is_minion, _)| hp.current > 0 && !is_minion)
+                    .count();
+                let mut hit_list: Vec<(Entity, i32)> = pending_attack
+                    .targets
+                    .iter()
+                    .map(|target| (*target, final_damage))
+                    .collect();
+                if let Some(chain_target) = pending_attack.chain_target {
+                    hit_list.push((chain_target, chain_damage(final_damage)));
+                    println!("Lightning chains to {:?}", chain_target);
+                }
+                let mut hp_damage_dealt = 0;
+                for (target_entity, hit_damage) in hit_list {
+                    let affinity = match kind_query.get(target_entity) {
+                        Ok(enemy) => element_affinity(enemy.kind),
+                        Err(_) => continue,
+                    };
+                    if let Ok((e, mut hp, mut block, mut status, is_minion, thorns)) =
+                        enemy_query.get_mut(target_entity)
                     {
-                        // Statuses apply
-                        if pending_attack.card.apply_poison > 0 {
-                            status.poison += pending_attack.card.apply_poison;
-                        }
-                        if pending_attack.card.apply_weak > 0 {
-                            status.weak += pending_attack.card.apply_weak;
-                        }
-                        if pending_attack.card.apply_stun > 0 {
-                            status.stun += pending_attack.card.apply_stun;
+                        // Statuses apply to the card's own targets, not the chain
+                        if pending_attack.targets.contains(&target_entity) {
+                            if pending_attack.card.apply_poison > 0 {
+                                status.poison += pending_attack.card.apply_poison;
+                            }
+                            if pending_attack.card.apply_weak > 0 {
+                                status.weak += pending_attack.card.apply_weak;
+                            }
+                            if pending_attack.card.apply_stun > 0 {
+                                status.stun += pending_attack.card.apply_stun;
+                            }
                         }
 
-                        let mut target_dmg = final_damage;
-
-                        if spell_has_ice && status.burning > 0 {
-                            status.burning = 0;
-                            println!("Extinguished!");
-                        }
-                        if spell_has_fire && status.frozen > 0 {
-                            status.frozen = 0;
-                            println!("Melted!");
-                        }
-                        if spell_has_stone && status.frozen > 0 {
-                            status.frozen = 0;
-                            target_dmg *= 2;
-                            println!("Shattered! Double Damage!");
+                        let outcome = apply_spell_elements(
+                            hit_damage,
+                            &pending_attack.spell_elements,
+                            &affinity,
+                            &mut status,
+                        );
+                        for reaction in &outcome.reactions {
+                            println!("{}!", reaction);
                         }
+                        let target_dmg = vulnerable_damage(outcome.damage, &status);
                         if spell_has_wind {
                             for (source_entity, amount) in &burning_snapshot {
                                 if *source_entity != e {
@@ -1845,33 +1829,53 @@ pub fn player_attack_animating_system(
                             }
                         }
 
-                        let block_dmg = std::cmp::min(target_dmg, block.value);
-                        block.value -= block_dmg;
-                        hp.current -= target_dmg - block_dmg;
-                        println!("Dealt {} damage to {:?}", target_dmg, target_entity);
-
-                        // Threshold Status checks
-                        if target_dmg > 10 {
-                            if spell_has_fire {
-                                status.burning += 3;
-                            }
-                            if spell_has_ice {
-                                status.frozen += 2;
-                            }
-                            if spell_has_stone {
-                                status.stun += 1;
+                        // Thorns answer every hit that lands on them
+                        for _ in 0..pending_attack.hits {
+                            if let Some(thorns) = thorns {
+                                thorns_damage += thorns.amount;
                             }
+                            let block_dmg = std::cmp::min(target_dmg, block.value);
+                            block.value -= block_dmg;
+                            hp.current -= target_dmg - block_dmg;
+                            hp_damage_dealt += target_dmg - block_dmg;
+                            println!("Dealt {} damage to {:?}", target_dmg, target_entity);
                         }
 
                         if hp.current <= 0 {
                             commands.entity(e).despawn_recursive();
-                            enemies_remaining -= 1;
+                            if !is_minion {
+                                enemies_remaining -= 1;
+                            }
                         }
                     }
                 }
 
-                if enemies_remaining == 0 {
-                    if let Ok((player_relics, mut player_health)) = player_query.get_single_mut() {
+                let heal = shadow_lifesteal(&pending_attack.spell_elements, hp_damage_dealt);
+                if heal > 0
+                    && let Ok((_, mut player_health, _)) = player_query.get_single_mut()
+                {
+                    player_health.current = (player_health.current + heal).min(player_health.max);
+                    println!("Shadow drains {} HP", heal);
+                }
+
+                let mut player_died = false;
+                if thorns_damage > 0 {
+                    if let Ok((_, mut player_health, mut player_block)) =
+                        player_query.get_single_mut()
+                    {
+                        let blocked = thorns_damage.min(player_block.value);
+                        player_block.value -= blocked;
+                        player_health.current -= thorns_damage - blocked;
+                        println!("Thorns deal {} damage back!", thorns_damage);
+                        player_died = player_health.current <= 0;
+                    }
+                }
+
+                if player_died {
+                    next_game_state.set(GameState::GameOver);
+                } else if enemies_remaining == 0 {
+                    if let Ok((player_relics, mut player_health, _)) = player_query.get_single_mut()
+                    {
                         if player_relics.relics.contains(&Relic::BurningBlood) {
                             player_health.current =
                                 (player_health.current + 6).min(player_health.max);
@@ -1960,18 +1964,3 @@ pub fn player_attack_animating_system(
         }
     }
 }
-
-pub fn update_combo_ui_system(
-    player_query: Query<&PlayerCombo, With<Player>>,
-    mut text_query: Query<&mut Text, With<PlayerComboText>>,
-) {
-    if let Ok(combo) = player_query.get_single() {
-        for mut text in &mut text_query {
-            if combo.current > 0 {
-                text.sections[0].value = format!("Combo: {}", combo.current);
-            } else {
-                text.sections[0].value = "Combo: 0".to_string();
-            }
-        }
-    }
-}
@@ -1,5 +1,7 @@
 use crate::components::*;
-use crate::item_relics::Relic;
+use crate::deck_services::random_service_target;
+use crate::item_cards::upgrade_card;
+use crate::item_relics::{Relic, droppable_relics};
 use crate::resources::*;
 use crate::states::*;
 use bevy::prelude::*;
@@ -122,14 +124,7 @@ pub fn bonus_select_interaction_system(
                     println!("Bonus: Gained 100 Gold");
                 }
                 BonusType::Relic => {
-                    let all_relics = vec![
-                        Relic::Vajra,
-                        Relic::BurningBlood,
-                        Relic::Anchor,
-                        Relic::OddlySmoothStone,
-                        Relic::BagOfMarbles,
-                    ];
-                    let available: Vec<Relic> = all_relics
+                    let available: Vec<Relic> = droppable_relics()
                         .into_iter()
                         .filter(|r| !relic_store.relics.contains(r))
                         .collect();
@@ -140,26 +135,9 @@ pub fn bonus_select_interaction_system(
                     }
                 }
                 BonusType::Upgrade => {
-                    let mut rng = thread_rng();
-                    let upgradable_indices: Vec<usize> = deck
-                        .cards
-                        .iter()
-                        .enumerate()
-                        .filter(|(_, c)| !c.upgraded)
-                        .map(|(i, _)| i)
-                        .collect();
-
-                    if let Some(&index) = upgradable_indices.choose(&mut rng) {
-                        let card = &mut deck.cards[index];
-                        card.upgraded = true;
-                        card.name.push_str("+");
-                        if card.damage > 0 {
-                            card.damage += 3;
-                        }
-                        if card.block > 0 {
-                            card.block += 3;
-                        }
-                        println!("Bonus: Upgraded {}", card.name);
+                    if let Some(index) = random_service_target(&deck, DeckService::Upgrade) {
+                        upgrade_card(&mut deck.cards[index]);
+                        println!("Bonus: Upgraded {}", deck.cards[index].name);
                     }
                 }
             }
@@ -0,0 +1,202 @@
+use crate::components::*;
+use crate::reflex::{
+    CALIBRATION_BEAT_SECS, CALIBRATION_TAPS, average_offset_ms, beat_offset, save_reflex_settings,
+};
+use crate::resources::*;
+use crate::states::*;
+use bevy::prelude::*;
+use std::time::Instant;
+
+const RING_MAX_SIZE: f32 = 300.0;
+const RING_TARGET_SIZE: f32 = 90.0;
+
+fn calibration_text(taps: usize, settings: &ReflexSettings) -> String {
+    format!(
+        "Taps: {}/{}\nCurrent offset: {:+.0} ms",
+        taps, CALIBRATION_TAPS, settings.input_offset_ms
+    )
+}
+
+pub fn setup_calibration_screen(mut commands: Commands, settings: Res<ReflexSettings>) {
+    commands.insert_resource(CalibrationSession {
+        started_at: Instant::now(),
+        offsets: Vec::new(),
+    });
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(16.0),
+                    ..default()
+                },
+                background_color: Color::srgba(0.1, 0.1, 0.1, 0.95).into(),
+                ..default()
+            },
+            CalibrationUI,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Input Calibration",
+                TextStyle {
+                    font: Handle::default(),
+                    font_size: 50.0,
+                    color: Color::WHITE,
+                },
+            ));
+            parent.spawn(TextBundle::from_section(
+                "Press Space or click each time the ring meets the circle",
+                TextStyle {
+                    font: Handle::default(),
+                    font_size: 20.0,
+                    color: Color::srgb(0.8, 0.8, 0.8),
+                },
+            ));
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(RING_MAX_SIZE),
+                        height: Val::Px(RING_MAX_SIZE),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|area| {
+                    // Target circle
+                    area.spawn(NodeBundle {
+                        style: Style {
+                            width: Val::Px(RING_TARGET_SIZE),
+                            height: Val::Px(RING_TARGET_SIZE),
+                            border: UiRect::all(Val::Px(4.0)),
+                            ..default()
+                        },
+                        border_color: Color::srgba(0.0, 1.0, 0.0, 0.8).into(),
+                        ..default()
+                    });
+                    area.spawn((
+                        NodeBundle {
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                width: Val::Px(RING_MAX_SIZE),
+                                height: Val::Px(RING_MAX_SIZE),
+                                border: UiRect::all(Val::Px(4.0)),
+                                ..default()
+                            },
+                            border_color: Color::WHITE.into(),
+                            ..default()
+                        },
+                        CalibrationRing,
+                    ));
+                });
+
+            parent.spawn((
+                TextBundle::from_section(
+                    calibration_text(0, &settings),
+                    TextStyle {
+                        font: Handle::default(),
+                        font_size: 24.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                CalibrationText,
+            ));
+
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            margin: UiRect::top(Val::Px(20.0)),
+                            padding: UiRect::all(Val::Px(15.0)),
+                            ..default()
+                        },
+                        background_color: Color::srgb(0.3, 0.3, 0.3).into(),
+                        ..default()
+                    },
+                    ReturnFromCalibrationButton,
+                ))
+                .with_children(|p| {
+                    p.spawn(TextBundle::from_section(
+                        "Back",
+                        TextStyle {
+                            font: Handle::default(),
+                            font_size: 25.0,
+                            color: Color::WHITE,
+                        },
+                    ));
+                });
+        });
+}
+
+// The ring closes on the target exactly on each beat, then starts over
+pub fn calibration_ring_system(
+    session: Res<CalibrationSession>,
+    mut ring_query: Query<&mut Style, With<CalibrationRing>>,
+) {
+    let since_start = session.started_at.elapsed().as_secs_f32();
+    let progress = (since_start % CALIBRATION_BEAT_SECS) / CALIBRATION_BEAT_SECS;
+    let size = RING_MAX_SIZE - (RING_MAX_SIZE - RING_TARGET_SIZE) * progress;
+    for mut style in &mut ring_query {
+        style.width = Val::Px(size);
+        style.height = Val::Px(size);
+        style.left = Val::Px((RING_MAX_SIZE - size) / 2.0);
+        style.top = Val::Px((RING_MAX_SIZE - size) / 2.0);
+    }
+}
+
+// Every full set of taps replaces the saved offset with their average
+pub fn calibration_input_system(
+    input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    button_query: Query<&Interaction, With<Button>>,
+    mut session: ResMut<CalibrationSession>,
+    mut settings: ResMut<ReflexSettings>,
+    mut text_query: Query<&mut Text, With<CalibrationText>>,
+) {
+    let over_button = button_query.iter().any(|i| *i != Interaction::None);
+    let tapped = input.just_pressed(KeyCode::Space)
+        || (mouse_input.just_pressed(MouseButton::Left) && !over_button);
+    if !tapped {
+        return;
+    }
+
+    let since_start = session.started_at.elapsed().as_secs_f32();
+    let Some(offset) = beat_offset(since_start, CALIBRATION_BEAT_SECS) else {
+        return;
+    };
+    session.offsets.push(offset);
+    if session.offsets.len() >= CALIBRATION_TAPS {
+        settings.input_offset_ms = average_offset_ms(&session.offsets);
+        save_reflex_settings(&settings);
+        println!(
+            "Input offset calibrated: {:+.0} ms",
+            settings.input_offset_ms
+        );
+        session.offsets.clear();
+    }
+
+    for mut text in &mut text_query {
+        text.sections[0].value = calibration_text(session.offsets.len(), &settings);
+    }
+}
+
+pub fn calibration_interaction_system(
+    mut next_game_state: ResMut<NextState<GameState>>,
+    interaction_query: Query<
+        &Interaction,
+        (Changed<Interaction>, With<ReturnFromCalibrationButton>),
+    >,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            next_game_state.set(GameState::Settings);
+        }
+    }
+}
@@ -1,4 +1,6 @@
+use crate::classes::{PLAYABLE_CLASSES, player_class};
 use crate::components::*;
+use crate::item_relics::get_relic_visuals;
 use crate::resources::*;
 use crate::states::*;
 use bevy::prelude::*;
@@ -51,95 +53,81 @@ pub fn setup_character_select_screen(mut commands: Commands, asset_server: Res<A
                     ..default()
                 })
                 .with_children(|container| {
-                    // Duelist
-                    container
-                        .spawn((
-                            ButtonBundle {
-                                style: Style {
-                                    width: Val::Px(220.0),
-                                    height: Val::Px(320.0),
-                                    flex_direction: FlexDirection::Column,
-                                    justify_content: JustifyContent::Center,
-                                    align_items: AlignItems::Center,
-                                    border: UiRect::all(Val::Px(2.0)),
-                                    padding: UiRect::all(Val::Px(10.0)),
-                                    ..default()
-                                },
-                                background_color: Color::srgb(0.3, 0.1, 0.1).into(),
-                                border_color: Color::WHITE.into(),
-                                ..default()
-                            },
-                            SelectDuelistButton,
-                        ))
-                        .with_children(|btn| {
-                            btn.spawn(TextBundle::from_section(
-                                "The Duelist",
-                                TextStyle {
-                                    font: Handle::default(),
-                                    font_size: 30.0,
-                                    color: Color::WHITE,
-                                },
-                            ));
-                            btn.spawn(
-                                TextBundle::from_section(
-                                    "A balanced warrior.",
-                                    TextStyle {
-                                        font: Handle::default(),
-                                        font_size: 16.0,
-                                        color: Color::srgb(0.8, 0.8, 0.8),
+                    for class in PLAYABLE_CLASSES {
+                        let definition = player_class(class).definition();
+                        container
+                            .spawn((
+                                ButtonBundle {
+                                    style: Style {
+                                        width: Val::Px(220.0),
+                                        height: Val::Px(320.0),
+                                        flex_direction: FlexDirection::Column,
+                                        justify_content: JustifyContent::Center,
+                                        align_items: AlignItems::Center,
+                                        border: UiRect::all(Val::Px(2.0)),
+                                        padding: UiRect::all(Val::Px(10.0)),
+                                        ..default()
                                     },
-                                )
-                                .with_style(Style {
-                                    margin: UiRect::top(Val::Px(20.0)),
-                                    ..default()
-                                }),
-                            );
-                        });
-
-                    // Spellweaver
-                    container
-                        .spawn((
-                            ButtonBundle {
-                                style: Style {
-                                    width: Val::Px(220.0),
-                                    height: Val::Px(320.0),
-                                    flex_direction: FlexDirection::Column,
-                                    justify_content: JustifyContent::Center,
-                                    align_items: AlignItems::Center,
-                                    border: UiRect::all(Val::Px(2.0)),
-                                    padding: UiRect::all(Val::Px(10.0)),
+                                    background_color: definition.color.into(),
+                                    border_color: Color::WHITE.into(),
+                                    // Class art, darkened so the text stays readable
+                                    image: UiImage::new(asset_server.load(definition.art))
+                                        .with_color(Color::srgb(0.45, 0.45, 0.45)),
                                     ..default()
                                 },
-                                background_color: Color::srgb(0.1, 0.1, 0.4).into(),
-                                border_color: Color::WHITE.into(),
-                                ..default()
-                            },
-                            SelectSpellweaverButton,
-                        ))
-                        .with_children(|btn| {
-                            btn.spawn(TextBundle::from_section(
-                                "The Spellweaver",
-                                TextStyle {
-                                    font: Handle::default(),
-                                    font_size: 30.0,
-                                    color: Color::WHITE,
-                                },
-                            ));
-                            btn.spawn(
-                                TextBundle::from_section(
-                                    "Master of the arcane.",
+                                SelectClassButton { class },
+                            ))
+                            .with_children(|btn| {
+                                btn.spawn(TextBundle::from_section(
+                                    definition.name,
                                     TextStyle {
                                         font: Handle::default(),
-                                        font_size: 16.0,
-                                        color: Color::srgb(0.8, 0.8, 0.8),
+                                        font_size: 30.0,
+                                        color: Color::WHITE,
                                     },
-                                )
-                                .with_style(Style {
-                                    margin: UiRect::top(Val::Px(20.0)),
-                                    ..default()
-                                }),
-                            );
-                        });
+                                ));
+                                btn.spawn(
+                                    TextBundle::from_section(
+                                        format!(
+                                            "{}\n\nHP: {}  Gold: {}",
+                                            definition.description,
+                                            definition.max_hp,
+                                            definition.gold
+                                        ),
+                                        TextStyle {
+                                            font: Handle::default(),
+                                            font_size: 16.0,
+                                            color: Color::srgb(0.8, 0.8, 0.8),
+                                        },
+                                    )
+                                    .with_style(Style {
+                                        margin: UiRect::top(Val::Px(20.0)),
+                                        ..default()
+                                    }),
+                                );
+                                for relic in &definition.starting_relics {
+                                    btn.spawn(
+                                        TextBundle::from_section(
+                                            format!(
+                                                "Starting relic:\n{}",
+                                                get_relic_visuals(relic).1
+                                            ),
+                                            TextStyle {
+                                                font: Handle::default(),
+                                                font_size: 16.0,
+                                                color: Color::srgb(1.0, 0.85, 0.3),
+                                            },
+                                        )
+                                        .with_style(
+                                            Style {
+                                                margin: UiRect::top(Val::Px(10.0)),
+                                                ..default()
+                                            },
+                                        ),
+                                    );
+                                }
+                            });
+                    }
                 });
         });
 }
@@ -147,19 +135,11 @@ pub fn setup_character_select_screen(mut commands: Commands, asset_server: Res<A
 pub fn character_select_interaction_system(
     mut next_state: ResMut<NextState<GameState>>,
     mut run_state: ResMut<RunState>,
-    duelist_query: Query<&Interaction, (Changed<Interaction>, With<SelectDuelistButton>)>,
-    spellweaver_query: Query<&Interaction, (Changed<Interaction>, With<SelectSpellweaverButton>)>,
+    class_query: Query<(&Interaction, &SelectClassButton), Changed<Interaction>>,
 ) {
-    for interaction in &duelist_query {
-        if *interaction == Interaction::Pressed {
-            run_state.character_class = CharacterClass::Duelist;
-            next_state.set(GameState::BonusSelect);
-        }
-    }
-
-    for interaction in &spellweaver_query {
+    for (interaction, button) in &class_query {
         if *interaction == Interaction::Pressed {
-            run_state.character_class = CharacterClass::Spellweaver;
+            run_state.character_class = button.class;
             next_state.set(GameState::BonusSelect);
         }
     }
@@ -1,5 +1,6 @@
 use crate::common::spawn_card_visual;
 use crate::components::*;
+use crate::deck_services::*;
 use crate::resources::*;
 use crate::states::*;
 use bevy::prelude::*;
@@ -97,3 +98,190 @@ pub fn view_deck_interaction_system(
         }
     }
 }
+
+pub fn setup_card_picker_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    deck: Res<Deck>,
+    picker: Res<CardPicker>,
+) {
+    let mut costs = Vec::new();
+    if picker.cost > 0 {
+        costs.push(format!("{}g", picker.cost));
+    }
+    if picker.hp_cost > 0 {
+        costs.push(format!("{} HP", picker.hp_cost));
+    }
+    let title = if costs.is_empty() {
+        format!("Select Card to {}", deck_service_name(picker.service))
+    } else {
+        format!(
+            "Select Card to {} (Cost: {})",
+            deck_service_name(picker.service),
+            costs.join(", ")
+        )
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    padding: UiRect::all(Val::Px(20.0)),
+                    ..default()
+                },
+                background_color: Color::srgba(0.05, 0.05, 0.05, 0.98).into(),
+                z_index: ZIndex::Global(100),
+                ..default()
+            },
+            CardPickerUI,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                title,
+                TextStyle {
+                    font: Handle::default(),
+                    font_size: 40.0,
+                    color: Color::WHITE,
+                },
+            ));
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        flex_wrap: FlexWrap::Wrap,
+                        justify_content: JustifyContent::Center,
+                        margin: UiRect::top(Val::Px(20.0)),
+                        row_gap: Val::Px(10.0),
+                        column_gap: Val::Px(10.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|grid| {
+                    for (index, card) in deck.cards.iter().enumerate() {
+                        if can_apply_service(picker.service, card) {
+                            spawn_card_visual(
+                                grid,
+                                &asset_server,
+                                card,
+                                (Button, Interaction::default(), CardPickerButton { index }),
+                                |_| {},
+                            );
+                        } else {
+                            // Shown dimmed so the deck reads complete, but not clickable
+                            spawn_card_visual(grid, &asset_server, card, (), |card_ui| {
+                                card_ui.spawn(NodeBundle {
+                                    style: Style {
+                                        position_type: PositionType::Absolute,
+                                        width: Val::Percent(100.0),
+                                        height: Val::Percent(100.0),
+                                        ..default()
+                                    },
+                                    background_color: Color::srgba(0.0, 0.0, 0.0, 0.6).into(),
+                                    z_index: ZIndex::Local(20),
+                                    ..default()
+                                });
+                            });
+                        }
+                    }
+                });
+
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            margin: UiRect::top(Val::Px(30.0)),
+                            padding: UiRect::all(Val::Px(15.0)),
+                            ..default()
+                        },
+                        background_color: Color::srgb(0.3, 0.3, 0.3).into(),
+                        ..default()
+                    },
+                    CancelCardPickerButton,
+                ))
+                .with_children(|p| {
+                    p.spawn(TextBundle::from_section(
+                        "Cancel",
+                        TextStyle {
+                            font: Handle::default(),
+                            font_size: 25.0,
+                            color: Color::WHITE,
+                        },
+                    ));
+                });
+        });
+}
+
+pub fn card_picker_interaction_system(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut deck: ResMut<Deck>,
+    picker: Res<CardPicker>,
+    mut shop_services: ResMut<ShopServices>,
+    mut run_stats: ResMut<RunStats>,
+    run_state: Res<RunState>,
+    mut active_event: Option<ResMut<ActiveEvent>>,
+    mut player_query: Query<(&mut Gold, &mut Health), With<Player>>,
+    card_query: Query<(&Interaction, &CardPickerButton), Changed<Interaction>>,
+    cancel_query: Query<&Interaction, (Changed<Interaction>, With<CancelCardPickerButton>)>,
+) {
+    for (interaction, button) in &card_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Ok((mut gold, mut health)) = player_query.get_single_mut() else {
+            continue;
+        };
+        if gold.amount < picker.cost {
+            println!("Not enough gold");
+            continue;
+        }
+        if picker.hp_cost > 0 && health.current <= picker.hp_cost {
+            println!("Not enough HP");
+            continue;
+        }
+
+        if let Some(message) = apply_deck_service(
+            &mut deck,
+            button.index,
+            picker.service,
+            run_state.character_class,
+        ) {
+            println!("{}", message);
+            gold.amount -= picker.cost;
+            health.current -= picker.hp_cost;
+            // Only removals bought from the merchant raise the merchant's price
+            if picker.return_state == GameState::Shop && picker.service == DeckService::Remove {
+                shop_services.removals += 1;
+                run_stats.record_purchase(run_state.act, "Card Removal", picker.cost);
+            }
+            // Events move on past the choice only once its card is picked
+            if picker.return_state == GameState::Event
+                && let Some(active) = active_event.as_mut()
+                && let Some(page) = active.after_pick.take()
+            {
+                active.page = page;
+            }
+        }
+        // Heading anywhere but back into the event ends it
+        if picker.return_state != GameState::Event && active_event.is_some() {
+            commands.remove_resource::<ActiveEvent>();
+        }
+        next_state.set(picker.return_state);
+        return;
+    }
+
+    for interaction in &cancel_query {
+        if *interaction == Interaction::Pressed {
+            if let Some(active) = active_event.as_mut() {
+                active.after_pick = None;
+            }
+            next_state.set(picker.cancel_state);
+        }
+    }
+}
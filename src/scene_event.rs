@@ -1,12 +1,94 @@
+use crate::acts::battles_fought_this_act;
 use crate::components::*;
+use crate::event_engine::*;
 use crate::resources::*;
 use crate::states::*;
 use bevy::prelude::*;
-use rand::{Rng, thread_rng};
 
-pub fn setup_event_screen(mut commands: Commands) {
-    let mut rng = thread_rng();
-    let event_type = rng.gen_range(0..2); // 0: Shrine, 1: Beggar
+pub fn setup_event_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut next_state: ResMut<NextState<GameState>>,
+    library: Res<EventLibrary>,
+    active: Option<Res<ActiveEvent>>,
+    mut history: ResMut<EventHistory>,
+    run_state: Res<RunState>,
+    deck: Res<Deck>,
+    player_query: Query<(&Health, &Gold, &RelicStore), With<Player>>,
+) {
+    // Coming back from the card picker picks the event up where it left off
+    let resumed = active.and_then(|active| {
+        library
+            .events
+            .iter()
+            .find(|event| event.id == active.id)
+            .map(|event| (event, active.page.clone()))
+    });
+    let (event, page) = match resumed {
+        Some(resumed) => resumed,
+        None => {
+            let Some(event) = pick_event(&library, run_state.act, &history) else {
+                println!("No events available, returning to map");
+                next_state.set(GameState::Map);
+                return;
+            };
+            if event.once_per_run {
+                history.seen.push(event.id.clone());
+            }
+            println!("Event: {}", event.title);
+            (event, event.start.clone())
+        }
+    };
+
+    let Ok((health, gold, relics)) = player_query.get_single() else {
+        return;
+    };
+    let unlocked = choices_unlocked(
+        event,
+        &page,
+        run_state.character_class,
+        health,
+        gold,
+        &deck,
+        relics,
+    );
+    spawn_event_page(&mut commands, &asset_server, event, &page, &unlocked);
+
+    commands.insert_resource(ActiveEvent {
+        id: event.id.clone(),
+        page,
+        after_pick: None,
+    });
+}
+
+fn choices_unlocked(
+    event: &EventDefinition,
+    page: &str,
+    class: CharacterClass,
+    health: &Health,
+    gold: &Gold,
+    deck: &Deck,
+    relics: &RelicStore,
+) -> Vec<bool> {
+    event.pages[page]
+        .choices
+        .iter()
+        .map(|choice| {
+            choice.requires.iter().all(|requirement| {
+                meets_requirement(requirement, class, health, gold, deck, relics)
+            })
+        })
+        .collect()
+}
+
+fn spawn_event_page(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    event: &EventDefinition,
+    page_id: &str,
+    unlocked: &[bool],
+) {
+    let page = &event.pages[page_id];
 
     commands
         .spawn((
@@ -27,29 +109,8 @@ pub fn setup_event_screen(mut commands: Commands) {
             EventUI,
         ))
         .with_children(|parent| {
-            let (title, desc, options) = match event_type {
-                0 => (
-                    "Mysterious Shrine",
-                    "You stumble upon an ancient shrine glowing with faint light.",
-                    vec![
-                        ("Pray (Heal 15 HP)", 0),
-                        ("Desecrate (Gain 50 Gold, Lose 5 HP)", 1),
-                        ("Leave", 2),
-                    ],
-                ),
-                _ => (
-                    "Old Beggar",
-                    "A beggar approaches you, asking for spare change.",
-                    vec![
-                        ("Give 10 Gold (Remove a random Strike)", 3),
-                        ("Attack (Gain 10 Gold)", 4),
-                        ("Ignore", 2),
-                    ],
-                ),
-            };
-
             parent.spawn(TextBundle::from_section(
-                title,
+                event.title.clone(),
                 TextStyle {
                     font: Handle::default(),
                     font_size: 40.0,
@@ -57,41 +118,85 @@ pub fn setup_event_screen(mut commands: Commands) {
                 },
             ));
 
-            parent.spawn(TextBundle::from_section(
-                desc,
-                TextStyle {
-                    font: Handle::default(),
-                    font_size: 24.0,
-                    color: Color::WHITE,
-                },
-            ));
+            if let Some(image) = &page.image {
+                parent.spawn(ImageBundle {
+                    style: Style {
+                        width: Val::Px(480.0),
+                        height: Val::Px(220.0),
+                        margin: UiRect::vertical(Val::Px(15.0)),
+                        ..default()
+                    },
+                    image: asset_server.load(image.clone()).into(),
+                    ..default()
+                });
+            }
+
+            parent.spawn(
+                TextBundle::from_section(
+                    page.text.clone(),
+                    TextStyle {
+                        font: Handle::default(),
+                        font_size: 24.0,
+                        color: Color::WHITE,
+                    },
+                )
+                .with_style(Style {
+                    max_width: Val::Px(700.0),
+                    ..default()
+                }),
+            );
+
+            for (index, choice) in page.choices.iter().enumerate() {
+                let available = unlocked.get(index).copied().unwrap_or(true);
+                let label = if available {
+                    choice.text.clone()
+                } else {
+                    let needs: Vec<String> = choice.requires.iter().map(requirement_text).collect();
+                    format!("{} (Requires {})", choice.text, needs.join(", "))
+                };
 
-            for (text, id) in options {
                 parent
                     .spawn((
                         ButtonBundle {
                             style: Style {
-                                width: Val::Px(400.0),
-                                height: Val::Px(60.0),
+                                width: Val::Px(500.0),
+                                min_height: Val::Px(60.0),
                                 margin: UiRect::top(Val::Px(20.0)),
+                                padding: UiRect::horizontal(Val::Px(10.0)),
                                 justify_content: JustifyContent::Center,
                                 align_items: AlignItems::Center,
                                 border: UiRect::all(Val::Px(2.0)),
                                 ..default()
                             },
-                            background_color: Color::srgb(0.2, 0.2, 0.3).into(),
-                            border_color: Color::WHITE.into(),
+                            background_color: if available {
+                                Color::srgb(0.2, 0.2, 0.3)
+                            } else {
+                                Color::srgb(0.12, 0.12, 0.12)
+                            }
+                            .into(),
+                            border_color: if available {
+                                Color::WHITE
+                            } else {
+                                Color::srgb(0.3, 0.3, 0.3)
+                            }
+                            .into(),
                             ..default()
                         },
-                        EventOptionButton { effect_id: id },
+                        EventOptionButton {
+                            choice_index: index,
+                        },
                     ))
                     .with_children(|b| {
                         b.spawn(TextBundle::from_section(
-                            text,
+                            label,
                             TextStyle {
                                 font: Handle::default(),
                                 font_size: 20.0,
-                                color: Color::WHITE,
+                                color: if available {
+                                    Color::WHITE
+                                } else {
+                                    Color::srgb(0.5, 0.5, 0.5)
+                                },
                             },
                         ));
                     });
@@ -100,58 +205,131 @@ pub fn setup_event_screen(mut commands: Commands) {
 }
 
 pub fn event_interaction_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
     mut next_state: ResMut<NextState<GameState>>,
-    mut player_query: Query<(&mut Health, &mut Gold), With<Player>>,
+    library: Res<EventLibrary>,
+    active: Option<ResMut<ActiveEvent>>,
+    run_state: Res<RunState>,
+    game_map: Res<GameMap>,
     mut deck: ResMut<Deck>,
-    interaction_query: Query<
-        (&Interaction, &EventOptionButton),
-        (Changed<Interaction>, With<EventOptionButton>),
+    mut player_query: Query<
+        (&mut Health, &mut Gold, &mut RelicStore, &mut PotionStore),
+        With<Player>,
     >,
+    ui_query: Query<Entity, With<EventUI>>,
+    interaction_query: Query<(&Interaction, &EventOptionButton), Changed<Interaction>>,
 ) {
+    let Some(mut active) = active else {
+        return;
+    };
+    let Some(event) = library.events.iter().find(|event| event.id == active.id) else {
+        return;
+    };
+
     for (interaction, button) in &interaction_query {
-        if *interaction == Interaction::Pressed {
-            if let Ok((mut health, mut gold)) = player_query.get_single_mut() {
-                match button.effect_id {
-                    0 => {
-                        // Pray
-                        health.current = (health.current + 15).min(health.max);
-                        println!("Healed 15 HP");
-                    }
-                    1 => {
-                        // Desecrate
-                        gold.amount += 50;
-                        health.current -= 5;
-                        println!("Gained 50 Gold, Lost 5 HP");
-                    }
-                    2 => {
-                        // Leave/Ignore
-                        println!("Left event");
-                    }
-                    3 => {
-                        // Give Gold (Remove Card)
-                        if gold.amount >= 10 {
-                            gold.amount -= 10;
-                            if let Some(idx) = deck.cards.iter().position(|c| c.name == "Strike") {
-                                deck.cards.remove(idx);
-                                println!("Removed a Strike");
-                            } else if !deck.cards.is_empty() {
-                                deck.cards.remove(0);
-                                println!("Removed a card");
-                            }
-                        } else {
-                            println!("Not enough gold!");
-                            continue;
-                        }
-                    }
-                    4 => {
-                        // Attack
-                        gold.amount += 10;
-                        println!("Gained 10 Gold");
-                    }
-                    _ => {}
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Some(choice) = event.pages[&active.page].choices.get(button.choice_index) else {
+            continue;
+        };
+        let Ok((mut health, mut gold, mut relics, mut potions)) = player_query.get_single_mut()
+        else {
+            continue;
+        };
+        let mut context = EventContext {
+            class: run_state.character_class,
+            health: &mut health,
+            gold: &mut gold,
+            deck: &mut deck,
+            relics: &mut relics,
+            potions: &mut potions,
+        };
+        if !context.meets_all(&choice.requires) {
+            println!("Requirements not met");
+            continue;
+        }
+
+        let mut outcomes = choice.outcomes.clone();
+        let mut next = choice.next.clone();
+        if let Some(random) = roll_random_outcome(&choice.random) {
+            outcomes.extend(random.outcomes.iter().cloned());
+            next = random.next.clone();
+        }
+        let picked_service = outcomes.iter().find_map(|outcome| match outcome {
+            Outcome::ChooseCard { service } => Some(*service),
+            _ => None,
+        });
+        let (mut picker_cost, mut picker_hp_cost) = (0, 0);
+        if picked_service.is_some() {
+            (outcomes, picker_cost, picker_hp_cost) = split_picker_costs(&outcomes);
+        }
+        for outcome in &outcomes {
+            context.apply(outcome);
+        }
+
+        if context.health.current <= 0 {
+            next_state.set(GameState::GameOver);
+            return;
+        }
+        let fight = outcomes.iter().find_map(|outcome| match outcome {
+            Outcome::Fight(fight) => Some(fight),
+            _ => None,
+        });
+        if let Some(fight) = fight {
+            commands.insert_resource(fight_encounter(
+                fight,
+                run_state.act,
+                battles_fought_this_act(&game_map),
+            ));
+            commands.remove_resource::<ActiveEvent>();
+            next_state.set(GameState::Battle);
+            return;
+        }
+
+        if let Some(service) = picked_service {
+            // The event continues on its next page (or ends) once the card is picked, and
+            // cancelling comes back to this page with the costs unpaid
+            active.after_pick = next;
+            let return_state = if active.after_pick.is_some() {
+                GameState::Event
+            } else {
+                GameState::Map
+            };
+            commands.insert_resource(CardPicker {
+                service,
+                cost: picker_cost,
+                hp_cost: picker_hp_cost,
+                return_state,
+                cancel_state: GameState::Event,
+            });
+            next_state.set(GameState::CardPicker);
+            return;
+        }
+
+        match next {
+            Some(page) => {
+                let unlocked = choices_unlocked(
+                    event,
+                    &page,
+                    run_state.character_class,
+                    context.health,
+                    context.gold,
+                    context.deck,
+                    context.relics,
+                );
+                for entity in &ui_query {
+                    commands.entity(entity).despawn_recursive();
                 }
+                spawn_event_page(&mut commands, &asset_server, event, &page, &unlocked);
+                active.page = page;
+            }
+            None => {
+                commands.remove_resource::<ActiveEvent>();
+                next_state.set(GameState::Map);
             }
-            next_state.set(GameState::Map);
         }
+        return;
     }
 }
@@ -1,8 +1,9 @@
 use crate::components::*;
+use crate::resources::{RunState, RunStats};
 use crate::states::*;
 use bevy::prelude::*;
 
-pub fn setup_game_over_screen(mut commands: Commands) {
+pub fn setup_game_over_screen(mut commands: Commands, run_stats: Res<RunStats>) {
     commands
         .spawn((
             NodeBundle {
@@ -30,6 +31,8 @@ pub fn setup_game_over_screen(mut commands: Commands) {
                 },
             ));
 
+            spawn_run_stats_text(parent, &run_stats);
+
             parent
                 .spawn((
                     ButtonBundle {
@@ -56,6 +59,107 @@ pub fn setup_game_over_screen(mut commands: Commands) {
         });
 }
 
+pub fn setup_run_victory_screen(
+    mut commands: Commands,
+    run_state: Res<RunState>,
+    run_stats: Res<RunStats>,
+) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.9).into(),
+                z_index: ZIndex::Global(200),
+                ..default()
+            },
+            RunVictoryUI,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "VICTORY",
+                TextStyle {
+                    font: Handle::default(),
+                    font_size: 60.0,
+                    color: Color::srgb(1.0, 0.84, 0.0),
+                },
+            ));
+
+            parent.spawn(TextBundle::from_section(
+                format!("All {} acts conquered.", run_state.act),
+                TextStyle {
+                    font: Handle::default(),
+                    font_size: 30.0,
+                    color: Color::WHITE,
+                },
+            ));
+
+            spawn_run_stats_text(parent, &run_stats);
+
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            margin: UiRect::top(Val::Px(30.0)),
+                            padding: UiRect::all(Val::Px(15.0)),
+                            ..default()
+                        },
+                        background_color: Color::srgb(0.3, 0.3, 0.3).into(),
+                        ..default()
+                    },
+                    RestartButton,
+                ))
+                .with_children(|p| {
+                    p.spawn(TextBundle::from_section(
+                        "New Run",
+                        TextStyle {
+                            font: Handle::default(),
+                            font_size: 30.0,
+                            color: Color::WHITE,
+                        },
+                    ));
+                });
+        });
+}
+
+fn spawn_run_stats_text(parent: &mut ChildBuilder, run_stats: &RunStats) {
+    let mut summary = format!(
+        "Shop purchases: {} for {}g\nSold to merchants: {} for {}g",
+        run_stats.purchases.len(),
+        run_stats.gold_spent(),
+        run_stats.sales.len(),
+        run_stats.gold_from_sales()
+    );
+    // The last few purchases, most recent first
+    for record in run_stats.purchases.iter().rev().take(5) {
+        summary.push_str(&format!(
+            "\n  Act {}: {} ({}g)",
+            record.act, record.item, record.gold
+        ));
+    }
+
+    parent.spawn(
+        TextBundle::from_section(
+            summary,
+            TextStyle {
+                font: Handle::default(),
+                font_size: 20.0,
+                color: Color::srgb(0.8, 0.8, 0.8),
+            },
+        )
+        .with_style(Style {
+            margin: UiRect::top(Val::Px(20.0)),
+            ..default()
+        }),
+    );
+}
+
 pub fn game_over_interaction_system(
     mut next_game_state: ResMut<NextState<GameState>>,
     interaction_query: Query<&Interaction, (Changed<Interaction>, With<RestartButton>)>,
@@ -1,10 +1,41 @@
+use crate::acts::{act_definition, battles_fought_this_act, encounter_for_node};
 use crate::cli::MapNodeSelectRequest;
 use crate::components::*;
+use crate::enemies::burning_buff_description;
+use crate::map_generator::MAP_COLUMNS;
+use crate::map_planner::{ROUTE_CRITERIA, plan_route, reachable_from, route_criterion_name};
 use crate::resources::*;
 use crate::states::*;
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
 use bevy::prelude::*;
+use rand::{Rng, thread_rng};
+
+pub fn setup_map_screen(
+    mut commands: Commands,
+    game_map: Res<GameMap>,
+    run_state: Res<RunState>,
+    mut map_view: ResMut<MapView>,
+    planner: Res<MapPlanner>,
+    window_query: Query<&Window>,
+) {
+    // Scroll to where the player is whenever they have moved since the last visit
+    if map_view.centered_on != game_map.current_node || game_map.current_node.is_none() {
+        if let Ok(window) = window_query.get_single() {
+            let window_size = Vec2::new(window.width(), window.height());
+            let level = game_map.current_node.map_or(0, |(l, _)| l);
+            map_view.pan.x = level_spacing(game_map.levels.len(), window_size.x, map_view.zoom)
+                * level as f32
+                - window_size.x / 3.0;
+            map_view.pan = clamp_map_pan(
+                map_view.pan,
+                game_map.levels.len(),
+                window_size,
+                map_view.zoom,
+            );
+        }
+        map_view.centered_on = game_map.current_node;
+    }
 
-pub fn setup_map_screen(mut commands: Commands, game_map: Res<GameMap>) {
     let map_root = commands
         .spawn((
             NodeBundle {
@@ -21,82 +52,129 @@ pub fn setup_map_screen(mut commands: Commands, game_map: Res<GameMap>) {
         ))
         .id();
 
-    let window_width = 1280.0;
-    let window_height = 720.0;
-    let level_count = game_map.levels.len();
-    let x_step = (window_width - 200.0) / (level_count as f32 - 1.0);
+    // Act Title
+    commands
+        .spawn((
+            TextBundle::from_section(
+                format!(
+                    "Act {}: {}",
+                    run_state.act,
+                    act_definition(run_state.act).name
+                ),
+                TextStyle {
+                    font: Handle::default(),
+                    font_size: 28.0,
+                    color: Color::srgb(0.9, 0.85, 0.7),
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(20.0),
+                left: Val::Px(20.0),
+                ..default()
+            }),
+            MapUI,
+        ))
+        .set_parent(map_root);
 
-    let get_px_pos = |level: usize, index: usize, total: usize| -> Vec2 {
-        let x = 100.0 + x_step * level as f32;
-        let base_y = window_height / 2.0 + (index as f32 - (total as f32 - 1.0) / 2.0) * 120.0;
-        let y = base_y; // + game_map.levels[level][index].y_jitter;
-        Vec2::new(x, y)
-    };
+    // Relics, hover for details
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(60.0),
+                    left: Val::Px(20.0),
+                    flex_direction: FlexDirection::Row,
+                    align_items: AlignItems::Center,
+                    min_height: Val::Px(20.0),
+                    ..default()
+                },
+                z_index: ZIndex::Global(20),
+                ..default()
+            },
+            PlayerRelicText,
+            MapUI,
+        ))
+        .set_parent(map_root);
 
-    // Spawn Paths
+    // Controls Hint
+    commands
+        .spawn((
+            TextBundle::from_section(
+                "Scroll / Arrows: Pan   Ctrl+Scroll / +-: Zoom",
+                TextStyle {
+                    font: Handle::default(),
+                    font_size: 14.0,
+                    color: Color::srgb(0.6, 0.6, 0.6),
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(20.0),
+                right: Val::Px(20.0),
+                ..default()
+            }),
+            MapUI,
+        ))
+        .set_parent(map_root);
+
+    // Spawn Paths: every edge faintly, the way here in green and the next steps brighter.
+    // Positions are filled in by map_layout_system.
     for (l_idx, level) in game_map.levels.iter().enumerate() {
         if l_idx == game_map.levels.len() - 1 {
             break;
         }
         for (n_idx, node) in level.iter().enumerate() {
-            let start_pos = get_px_pos(l_idx, n_idx, level.len());
-
             for &next_idx in &node.next_indices {
-                let end_pos = get_px_pos(l_idx + 1, next_idx, game_map.levels[l_idx + 1].len());
-
-                let diff = end_pos - start_pos;
-                let length = diff.length();
-                // Negate the Y difference for Transform rotation to fix inverted diagonals
-                let angle = (-diff.y).atan2(diff.x);
-                let center = (start_pos + end_pos) / 2.0;
-
                 let is_path_taken = game_map.visited_path.contains(&(l_idx, n_idx))
                     && game_map.visited_path.contains(&(l_idx + 1, next_idx));
+                let is_available = game_map.current_node == Some((l_idx, n_idx))
+                    || (game_map.current_node.is_none() && l_idx == 0);
 
-                // Let's also draw available next paths faintly!
-                let is_available = game_map.current_node == Some((l_idx, n_idx));
-
-                if is_path_taken || is_available {
-                    let height = if is_path_taken { 6.0 } else { 4.0 };
-                    let color = if is_path_taken {
-                        Color::srgb(0.2, 0.8, 0.2)
-                    } else {
-                        Color::srgb(0.4, 0.4, 0.4)
-                    };
+                let (thickness, color) = if is_path_taken {
+                    (6.0, Color::srgb(0.2, 0.8, 0.2))
+                } else if is_available {
+                    (4.0, Color::srgb(0.5, 0.5, 0.5))
+                } else {
+                    (2.0, Color::srgba(0.5, 0.5, 0.6, 0.2))
+                };
 
-                    commands
-                        .spawn((
-                            NodeBundle {
-                                style: Style {
-                                    position_type: PositionType::Absolute,
-                                    left: Val::Px(center.x),
-                                    bottom: Val::Px(center.y),
-                                    width: Val::Px(0.0),
-                                    height: Val::Px(0.0),
-                                    ..default()
-                                },
-                                transform: Transform::from_rotation(Quat::from_rotation_z(angle)),
-                                z_index: ZIndex::Global(1),
+                commands
+                    .spawn((
+                        NodeBundle {
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                width: Val::Px(0.0),
+                                height: Val::Px(0.0),
                                 ..default()
                             },
-                            MapUI,
-                        ))
-                        .set_parent(map_root)
-                        .with_children(|p| {
-                            p.spawn(NodeBundle {
+                            z_index: ZIndex::Global(1),
+                            ..default()
+                        },
+                        MapEdge {
+                            from: (l_idx, n_idx),
+                            to: (l_idx + 1, next_idx),
+                            color,
+                        },
+                        MapUI,
+                    ))
+                    .set_parent(map_root)
+                    .with_children(|p| {
+                        p.spawn((
+                            NodeBundle {
                                 style: Style {
                                     position_type: PositionType::Absolute,
-                                    left: Val::Px(-length / 2.0),
-                                    bottom: Val::Px(-height / 2.0),
-                                    width: Val::Px(length),
-                                    height: Val::Px(height),
+                                    height: Val::Px(thickness),
+                                    bottom: Val::Px(-thickness / 2.0),
                                     ..default()
                                 },
                                 background_color: color.into(),
                                 ..default()
-                            });
-                        });
-                }
+                            },
+                            MapEdgeLine,
+                        ));
+                    });
             }
         }
     }
@@ -104,8 +182,6 @@ pub fn setup_map_screen(mut commands: Commands, game_map: Res<GameMap>) {
     // Spawn Nodes
     for (l_idx, level) in game_map.levels.iter().enumerate() {
         for (n_idx, node) in level.iter().enumerate() {
-            let pos = get_px_pos(l_idx, n_idx, level.len());
-
             let is_current = game_map.current_node == Some((l_idx, n_idx));
             let is_visited = game_map.visited_path.contains(&(l_idx, n_idx));
 
@@ -119,8 +195,8 @@ pub fn setup_map_screen(mut commands: Commands, game_map: Res<GameMap>) {
                 l_idx == 0
             };
 
-            // Fog of War Logic: Only show Boss, Visited, Current, and Immediate Next nodes
-            let is_visible = if node.node_type == NodeType::Boss {
+            // Fog of War Logic: Only show Boss, Burning Elite, Visited, Current, and Immediate Next nodes
+            let is_visible = if node.node_type == NodeType::Boss || node.burning.is_some() {
                 true
             } else if is_visited || is_current || is_available {
                 true
@@ -132,50 +208,39 @@ pub fn setup_map_screen(mut commands: Commands, game_map: Res<GameMap>) {
                 Color::srgb(0.0, 1.0, 0.0) // Bright Green
             } else if is_visited {
                 Color::srgb(0.4, 0.4, 0.4) // Dim Grey
+            } else if is_available && node.burning.is_some() {
+                burning_elite_visuals().0
             } else if is_available {
-                match node.node_type {
-                    NodeType::Boss => Color::srgb(0.8, 0.0, 0.0),
-                    NodeType::Elite => Color::srgb(0.8, 0.0, 0.8), // Purple
-                    NodeType::Event => Color::srgb(0.5, 0.5, 0.8), // Blue-ish
-                    NodeType::Shop => Color::srgb(0.8, 0.6, 0.0),  // Gold
-                    NodeType::Rest => Color::srgb(0.2, 0.6, 0.8),  // Cyan
-                    NodeType::Battle => Color::WHITE,
-                }
+                node_visuals(node.node_type).0
+            } else if node.burning.is_some() {
+                Color::srgb(0.0, 0.4, 0.2) // Visible but not reachable (Burning Elite)
             } else if is_visible {
                 Color::srgb(0.6, 0.0, 0.0) // Visible but not reachable (Boss)
             } else {
                 Color::srgb(0.1, 0.1, 0.15) // Unknown / Dark Blue-Grey
             };
 
-            let size = if node.node_type == NodeType::Boss {
-                60.0
-            } else if node.node_type == NodeType::Elite {
-                50.0
-            } else {
-                40.0
-            };
-
-            let (label, tooltip_text) = if is_visible {
-                match node.node_type {
-                    NodeType::Battle => ("B", "Battle: Standard enemy encounter"),
-                    NodeType::Shop => ("S", "Shop: Trade gold for goods"),
-                    NodeType::Rest => ("R", "Rest: Heal or upgrade"),
-                    NodeType::Boss => ("BOSS", "Boss: The final challenge"),
-                    NodeType::Elite => ("E", "Elite: Powerful enemy, rare rewards"),
-                    NodeType::Event => ("?", "Event: Unknown occurrence"),
-                }
+            let (label, tooltip_text) = if let (true, Some(buff)) = (is_visible, node.burning) {
+                let (_, label, tooltip) = burning_elite_visuals();
+                (
+                    label,
+                    format!("{}\nBuff: {}", tooltip, burning_buff_description(buff)),
+                )
+            } else if is_visible {
+                let (_, label, tooltip) = node_visuals(node.node_type);
+                let tooltip = match node.resolved {
+                    Some(resolved) => format!("{} (It was: {:?})", tooltip, resolved),
+                    None => tooltip.to_string(),
+                };
+                (label, tooltip)
             } else {
-                ("?", "Unknown Location")
+                ("?", "Unknown Location".to_string())
             };
 
             let mut node_entity = commands.spawn((
                 ButtonBundle {
                     style: Style {
                         position_type: PositionType::Absolute,
-                        left: Val::Px(pos.x - size / 2.0),
-                        bottom: Val::Px(pos.y - size / 2.0),
-                        width: Val::Px(size),
-                        height: Val::Px(size),
                         justify_content: JustifyContent::Center,
                         align_items: AlignItems::Center,
                         border: UiRect::all(Val::Px(2.0)),
@@ -197,9 +262,7 @@ pub fn setup_map_screen(mut commands: Commands, game_map: Res<GameMap>) {
                     node_type: node.node_type,
                 },
                 MapUI,
-                Tooltip {
-                    text: tooltip_text.to_string(),
-                },
+                Tooltip { text: tooltip_text },
             ));
 
             node_entity.set_parent(map_root);
@@ -217,9 +280,106 @@ pub fn setup_map_screen(mut commands: Commands, game_map: Res<GameMap>) {
                         },
                     },
                 ));
+
+                // Waypoint Pin
+                p.spawn((
+                    NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            top: Val::Px(-6.0),
+                            right: Val::Px(-6.0),
+                            width: Val::Px(14.0),
+                            height: Val::Px(14.0),
+                            ..default()
+                        },
+                        background_color: Color::srgb(1.0, 0.5, 0.1).into(),
+                        border_radius: BorderRadius::all(Val::Percent(50.0)),
+                        visibility: if planner.waypoints.contains(&(l_idx, n_idx)) {
+                            Visibility::Inherited
+                        } else {
+                            Visibility::Hidden
+                        },
+                        ..default()
+                    },
+                    MapWaypointMarker {
+                        level: l_idx,
+                        index: n_idx,
+                    },
+                ));
             });
         }
 
+        // Legend
+        commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        bottom: Val::Px(20.0),
+                        left: Val::Px(20.0),
+                        flex_direction: FlexDirection::Column,
+                        padding: UiRect::all(Val::Px(10.0)),
+                        row_gap: Val::Px(4.0),
+                        ..default()
+                    },
+                    background_color: Color::srgba(0.0, 0.0, 0.0, 0.6).into(),
+                    z_index: ZIndex::Global(20),
+                    ..default()
+                },
+                MapUI,
+            ))
+            .set_parent(map_root)
+            .with_children(|legend| {
+                let entries = LEGEND_NODE_TYPES
+                    .into_iter()
+                    .map(node_visuals)
+                    .chain(std::iter::once(burning_elite_visuals()));
+                for (color, label, tooltip) in entries {
+                    legend
+                        .spawn(NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Row,
+                                align_items: AlignItems::Center,
+                                column_gap: Val::Px(8.0),
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .with_children(|row| {
+                            row.spawn(NodeBundle {
+                                style: Style {
+                                    width: Val::Px(22.0),
+                                    height: Val::Px(22.0),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    ..default()
+                                },
+                                background_color: color.into(),
+                                border_radius: BorderRadius::all(Val::Percent(50.0)),
+                                ..default()
+                            })
+                            .with_children(|icon| {
+                                icon.spawn(TextBundle::from_section(
+                                    label,
+                                    TextStyle {
+                                        font: Handle::default(),
+                                        font_size: 12.0,
+                                        color: Color::BLACK,
+                                    },
+                                ));
+                            });
+                            row.spawn(TextBundle::from_section(
+                                tooltip,
+                                TextStyle {
+                                    font: Handle::default(),
+                                    font_size: 14.0,
+                                    color: Color::WHITE,
+                                },
+                            ));
+                        });
+                }
+            });
+
         // View Deck Button
         commands
             .spawn((
@@ -248,6 +408,109 @@ pub fn setup_map_screen(mut commands: Commands, game_map: Res<GameMap>) {
                     },
                 ));
             });
+
+        // Spellbook Button
+        if run_state.character_class == CharacterClass::Spellweaver {
+            commands
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            top: Val::Px(20.0),
+                            right: Val::Px(140.0),
+                            padding: UiRect::all(Val::Px(10.0)),
+                            ..default()
+                        },
+                        background_color: Color::srgb(0.4, 0.2, 0.6).into(),
+                        ..default()
+                    },
+                    ViewSpellbookButton,
+                    MapUI,
+                ))
+                .set_parent(map_root)
+                .with_children(|p| {
+                    p.spawn(TextBundle::from_section(
+                        "Spellbook",
+                        TextStyle {
+                            font: Handle::default(),
+                            font_size: 20.0,
+                            color: Color::WHITE,
+                        },
+                    ));
+                });
+        }
+
+        // Route Planner
+        commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        top: Val::Px(75.0),
+                        right: Val::Px(20.0),
+                        flex_direction: FlexDirection::Column,
+                        padding: UiRect::all(Val::Px(10.0)),
+                        row_gap: Val::Px(4.0),
+                        ..default()
+                    },
+                    background_color: Color::srgba(0.0, 0.0, 0.0, 0.6).into(),
+                    z_index: ZIndex::Global(20),
+                    ..default()
+                },
+                MapUI,
+            ))
+            .set_parent(map_root)
+            .with_children(|panel| {
+                panel.spawn(TextBundle::from_section(
+                    "Route Planner",
+                    TextStyle {
+                        font: Handle::default(),
+                        font_size: 18.0,
+                        color: Color::WHITE,
+                    },
+                ));
+
+                let options = ROUTE_CRITERIA
+                    .into_iter()
+                    .map(|criterion| (Some(criterion), route_criterion_name(criterion)))
+                    .chain(std::iter::once((None, "Off")));
+                for (criterion, name) in options {
+                    panel
+                        .spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)),
+                                    ..default()
+                                },
+                                background_color: planner_button_color(
+                                    planner.criterion == criterion,
+                                )
+                                .into(),
+                                ..default()
+                            },
+                            PlannerCriterionButton { criterion },
+                        ))
+                        .with_children(|p| {
+                            p.spawn(TextBundle::from_section(
+                                name,
+                                TextStyle {
+                                    font: Handle::default(),
+                                    font_size: 16.0,
+                                    color: Color::WHITE,
+                                },
+                            ));
+                        });
+                }
+
+                panel.spawn(TextBundle::from_section(
+                    "Right-click: Pin Waypoint",
+                    TextStyle {
+                        font: Handle::default(),
+                        font_size: 13.0,
+                        color: Color::srgb(0.6, 0.6, 0.6),
+                    },
+                ));
+            });
     }
 
     println!("--- MAP ---");
@@ -273,6 +536,7 @@ pub fn map_interaction_system(
         (Changed<Interaction>, With<Button>),
     >,
     view_deck_query: Query<&Interaction, (Changed<Interaction>, With<ViewDeckButton>)>,
+    spellbook_query: Query<&Interaction, (Changed<Interaction>, With<ViewSpellbookButton>)>,
     mut ev_map: EventWriter<MapNodeSelectRequest>,
 ) {
     for (interaction, node_btn) in &node_interaction_query {
@@ -287,11 +551,416 @@ pub fn map_interaction_system(
             next_game_state.set(GameState::ViewDeck);
         }
     }
+    for interaction in &spellbook_query {
+        if *interaction == Interaction::Pressed {
+            next_game_state.set(GameState::Spellbook);
+        }
+    }
+}
+
+pub const MIN_LEVEL_SPACING: f32 = 110.0;
+const MAX_NODE_SPACING: f32 = 120.0;
+const MAP_MARGIN: f32 = 100.0;
+pub const MIN_MAP_ZOOM: f32 = 0.5;
+pub const MAX_MAP_ZOOM: f32 = 2.0;
+const MAP_PAN_SPEED: f32 = 600.0;
+
+// Horizontal distance between levels. Levels spread across the window, but never closer
+// than MIN_LEVEL_SPACING, in which case the map becomes wider than the window and scrolls.
+pub fn level_spacing(level_count: usize, window_width: f32, zoom: f32) -> f32 {
+    let fit = (window_width - 2.0 * MAP_MARGIN) / (level_count.max(2) - 1) as f32;
+    fit.max(MIN_LEVEL_SPACING) * zoom
+}
+
+fn node_spacing(window_height: f32, zoom: f32) -> f32 {
+    let fit = (window_height - 2.0 * MAP_MARGIN) / (MAP_COLUMNS - 1) as f32;
+    fit.min(MAX_NODE_SPACING) * zoom
+}
+
+// Screen position (from the bottom left) of a node's center
+pub fn map_node_position(
+    level: usize,
+    index: usize,
+    count: usize,
+    level_count: usize,
+    window_size: Vec2,
+    view: &MapView,
+) -> Vec2 {
+    let x = MAP_MARGIN + level_spacing(level_count, window_size.x, view.zoom) * level as f32;
+    let y = window_size.y / 2.0
+        + (index as f32 - (count as f32 - 1.0) / 2.0) * node_spacing(window_size.y, view.zoom);
+    Vec2::new(x - view.pan.x, y + view.pan.y)
+}
+
+// Keeps the map from being scrolled entirely off screen
+pub fn clamp_map_pan(pan: Vec2, level_count: usize, window_size: Vec2, zoom: f32) -> Vec2 {
+    let width = level_spacing(level_count, window_size.x, zoom) * (level_count.max(1) - 1) as f32
+        + 2.0 * MAP_MARGIN;
+    let height = node_spacing(window_size.y, zoom) * (MAP_COLUMNS - 1) as f32 + 2.0 * MAP_MARGIN;
+    let max_x = (width - window_size.x).max(0.0);
+    let max_y = ((height - window_size.y) / 2.0).max(0.0);
+    Vec2::new(pan.x.clamp(0.0, max_x), pan.y.clamp(-max_y, max_y))
+}
+
+fn map_node_size(node_type: NodeType) -> f32 {
+    match node_type {
+        NodeType::Boss => 60.0,
+        NodeType::Elite => 50.0,
+        _ => 40.0,
+    }
+}
+
+pub fn map_view_input_system(
+    mut map_view: ResMut<MapView>,
+    mut wheel_events: EventReader<MouseWheel>,
+    keys: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    game_map: Res<GameMap>,
+    window_query: Query<&Window>,
+) {
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+    let window_size = Vec2::new(window.width(), window.height());
+    let zooming = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+
+    let mut pan = map_view.pan;
+    let mut zoom = map_view.zoom;
+
+    for event in wheel_events.read() {
+        let amount = match event.unit {
+            MouseScrollUnit::Line => event.y * 40.0,
+            MouseScrollUnit::Pixel => event.y,
+        };
+        if zooming {
+            zoom *= 1.0 + amount / 400.0;
+        } else {
+            pan.x -= amount + event.x;
+        }
+    }
+
+    let step = MAP_PAN_SPEED * time.delta_seconds();
+    if keys.any_pressed([KeyCode::ArrowLeft, KeyCode::KeyA]) {
+        pan.x -= step;
+    }
+    if keys.any_pressed([KeyCode::ArrowRight, KeyCode::KeyD]) {
+        pan.x += step;
+    }
+    if keys.any_pressed([KeyCode::ArrowUp, KeyCode::KeyW]) {
+        pan.y -= step;
+    }
+    if keys.any_pressed([KeyCode::ArrowDown, KeyCode::KeyS]) {
+        pan.y += step;
+    }
+    if keys.any_pressed([KeyCode::Equal, KeyCode::NumpadAdd]) {
+        zoom *= 1.0 + time.delta_seconds();
+    }
+    if keys.any_pressed([KeyCode::Minus, KeyCode::NumpadSubtract]) {
+        zoom /= 1.0 + time.delta_seconds();
+    }
+
+    // Zoom around the middle of the screen so the view doesn't jump sideways
+    zoom = zoom.clamp(MIN_MAP_ZOOM, MAX_MAP_ZOOM);
+    if zoom != map_view.zoom {
+        let center = pan.x + window_size.x / 2.0 - MAP_MARGIN;
+        pan.x = center * zoom / map_view.zoom - window_size.x / 2.0 + MAP_MARGIN;
+    }
+    let pan = clamp_map_pan(pan, game_map.levels.len(), window_size, zoom);
+
+    if pan != map_view.pan || zoom != map_view.zoom {
+        map_view.pan = pan;
+        map_view.zoom = zoom;
+    }
+}
+
+// Places nodes and edges for the current window size, scroll and zoom
+pub fn map_layout_system(
+    map_view: Res<MapView>,
+    game_map: Res<GameMap>,
+    window_query: Query<Ref<Window>>,
+    mut node_query: Query<(Ref<MapNodeButton>, &mut Style), Without<MapEdgeLine>>,
+    mut edge_query: Query<
+        (Ref<MapEdge>, &mut Style, &mut Transform, &Children),
+        Without<MapNodeButton>,
+    >,
+    mut line_query: Query<
+        &mut Style,
+        (With<MapEdgeLine>, Without<MapEdge>, Without<MapNodeButton>),
+    >,
+) {
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+    let spawned = node_query.iter().any(|(node, _)| node.is_added());
+    if !(map_view.is_changed() || window.is_changed() || spawned) {
+        return;
+    }
+
+    let window_size = Vec2::new(window.width(), window.height());
+    let level_count = game_map.levels.len();
+    let position = |(level, index): (usize, usize)| {
+        map_node_position(
+            level,
+            index,
+            game_map.levels[level].len(),
+            level_count,
+            window_size,
+            &map_view,
+        )
+    };
+
+    for (node, mut style) in &mut node_query {
+        let pos = position((node.level, node.index));
+        let size = map_node_size(node.node_type) * map_view.zoom.sqrt();
+        style.left = Val::Px(pos.x - size / 2.0);
+        style.bottom = Val::Px(pos.y - size / 2.0);
+        style.width = Val::Px(size);
+        style.height = Val::Px(size);
+    }
+
+    for (edge, mut style, mut transform, children) in &mut edge_query {
+        let start = position(edge.from);
+        let end = position(edge.to);
+        let diff = end - start;
+        let length = diff.length();
+        let center = (start + end) / 2.0;
+
+        style.left = Val::Px(center.x);
+        style.bottom = Val::Px(center.y);
+        // Negate the Y difference for Transform rotation to fix inverted diagonals
+        transform.rotation = Quat::from_rotation_z((-diff.y).atan2(diff.x));
+
+        for &child in children {
+            if let Ok(mut line_style) = line_query.get_mut(child) {
+                line_style.left = Val::Px(-length / 2.0);
+                line_style.width = Val::Px(length);
+            }
+        }
+    }
+}
+
+fn planner_button_color(selected: bool) -> Color {
+    if selected {
+        Color::srgb(0.2, 0.5, 0.6)
+    } else {
+        Color::srgb(0.2, 0.2, 0.25)
+    }
+}
+
+pub fn map_planner_interaction_system(
+    mut planner: ResMut<MapPlanner>,
+    game_map: Res<GameMap>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    criterion_query: Query<(&Interaction, &PlannerCriterionButton), Changed<Interaction>>,
+    node_query: Query<(&Interaction, &MapNodeButton)>,
+) {
+    for (interaction, button) in &criterion_query {
+        if *interaction == Interaction::Pressed {
+            planner.criterion = button.criterion;
+        }
+    }
+
+    if !mouse.just_pressed(MouseButton::Right) {
+        return;
+    }
+    for (interaction, node) in &node_query {
+        if *interaction != Interaction::Hovered {
+            continue;
+        }
+        let key = (node.level, node.index);
+        if game_map.visited_path.contains(&key) {
+            continue;
+        }
+        if let Some(pos) = planner
+            .waypoints
+            .iter()
+            .position(|waypoint| *waypoint == key)
+        {
+            planner.waypoints.remove(pos);
+        } else {
+            planner.waypoints.push(key);
+        }
+    }
+}
+
+// Colors nodes and edges for the planned route, and on hover every path that continues
+// from the hovered node
+pub fn map_highlight_system(
+    game_map: Res<GameMap>,
+    planner: Res<MapPlanner>,
+    mut node_query: Query<(&Interaction, &MapNodeButton, &mut BorderColor)>,
+    edge_query: Query<(&MapEdge, &Children)>,
+    spawned_query: Query<(), Added<MapEdge>>,
+    mut line_query: Query<&mut BackgroundColor, With<MapEdgeLine>>,
+    mut button_query: Query<(&PlannerCriterionButton, &mut BackgroundColor), Without<MapEdgeLine>>,
+    mut marker_query: Query<(&MapWaypointMarker, &mut Visibility)>,
+    mut last_hovered: Local<Option<(usize, usize)>>,
+) {
+    let hovered = node_query
+        .iter()
+        .find(|(interaction, _, _)| **interaction != Interaction::None)
+        .map(|(_, node, _)| (node.level, node.index));
+    if hovered == *last_hovered && !planner.is_changed() && spawned_query.is_empty() {
+        return;
+    }
+    *last_hovered = hovered;
+
+    let reachable = hovered.map_or(Vec::new(), |start| reachable_from(&game_map.levels, start));
+    let mut route = planner.criterion.map_or(Vec::new(), |criterion| {
+        plan_route(
+            &game_map.levels,
+            game_map.current_node,
+            criterion,
+            &planner.waypoints,
+        )
+    });
+    if let Some(current) = game_map.current_node {
+        route.insert(0, current);
+    }
+    let hover_color = Color::srgb(1.0, 0.85, 0.3);
+    let route_color = Color::srgb(0.3, 0.9, 1.0);
+
+    for (_, node, mut border) in &mut node_query {
+        let key = (node.level, node.index);
+        *border = if reachable.contains(&key) {
+            hover_color
+        } else if route.contains(&key) {
+            route_color
+        } else {
+            Color::WHITE
+        }
+        .into();
+    }
+
+    for (edge, children) in &edge_query {
+        let color = if reachable.contains(&edge.from) && reachable.contains(&edge.to) {
+            hover_color
+        } else if route.contains(&edge.from) && route.contains(&edge.to) {
+            route_color
+        } else {
+            edge.color
+        };
+        for &child in children {
+            if let Ok(mut line_color) = line_query.get_mut(child) {
+                *line_color = color.into();
+            }
+        }
+    }
+
+    for (button, mut color) in &mut button_query {
+        *color = planner_button_color(planner.criterion == button.criterion).into();
+    }
+
+    for (marker, mut visibility) in &mut marker_query {
+        *visibility = if planner.waypoints.contains(&(marker.level, marker.index)) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+const LEGEND_NODE_TYPES: [NodeType; 8] = [
+    NodeType::Battle,
+    NodeType::Elite,
+    NodeType::Event,
+    NodeType::Unknown,
+    NodeType::Shop,
+    NodeType::Treasure,
+    NodeType::Rest,
+    NodeType::Boss,
+];
+
+// Color, label and description of each node type, shared by the map nodes and the legend
+pub fn node_visuals(node_type: NodeType) -> (Color, &'static str, &'static str) {
+    match node_type {
+        NodeType::Battle => (Color::WHITE, "B", "Battle: Standard enemy encounter"),
+        NodeType::Shop => (
+            Color::srgb(0.8, 0.6, 0.0), // Gold
+            "S",
+            "Shop: Trade gold for goods",
+        ),
+        NodeType::Rest => (
+            Color::srgb(0.2, 0.6, 0.8), // Cyan
+            "R",
+            "Rest: Heal or upgrade",
+        ),
+        NodeType::Boss => (
+            Color::srgb(0.8, 0.0, 0.0),
+            "BOSS",
+            "Boss: Guardian of this act",
+        ),
+        NodeType::Elite => (
+            Color::srgb(0.8, 0.0, 0.8), // Purple
+            "E",
+            "Elite: Powerful enemy, rare rewards",
+        ),
+        NodeType::Event => (
+            Color::srgb(0.5, 0.5, 0.8), // Blue-ish
+            "!",
+            "Event: A strange encounter",
+        ),
+        NodeType::Treasure => (
+            Color::srgb(0.7, 0.45, 0.15), // Chest brown
+            "T",
+            "Treasure: A chest with a relic and gold",
+        ),
+        NodeType::Unknown => (
+            Color::srgb(0.65, 0.65, 0.65),
+            "?",
+            "Unknown: Event, fight, shop or treasure",
+        ),
+    }
+}
+
+pub fn burning_elite_visuals() -> (Color, &'static str, &'static str) {
+    (
+        Color::srgb(0.1, 0.85, 0.45), // Emerald
+        "E!",
+        "Burning Elite: Empowered elite, extra relic",
+    )
+}
+
+// Decides what an Unknown node holds. `roll` is 0..100. Outcomes that don't come up
+// get more likely for the next Unknown node, the rolled one goes back to its base odds.
+pub fn resolve_unknown_node(odds: &mut UnknownOdds, roll: u32) -> NodeType {
+    let base = UnknownOdds::default();
+
+    let result = if roll < odds.battle {
+        NodeType::Battle
+    } else if roll < odds.battle + odds.shop {
+        NodeType::Shop
+    } else if roll < odds.battle + odds.shop + odds.treasure {
+        NodeType::Treasure
+    } else {
+        NodeType::Event
+    };
+
+    if result == NodeType::Battle {
+        odds.battle = base.battle;
+    } else {
+        odds.battle += base.battle;
+    }
+    if result == NodeType::Shop {
+        odds.shop = base.shop;
+    } else {
+        odds.shop += base.shop;
+    }
+    if result == NodeType::Treasure {
+        odds.treasure = base.treasure;
+    } else {
+        odds.treasure += base.treasure;
+    }
+
+    result
 }
 
 pub fn process_map_node_select_requests(
+    mut commands: Commands,
     mut next_game_state: ResMut<NextState<GameState>>,
+    run_state: Res<RunState>,
     mut game_map: ResMut<GameMap>,
+    mut unknown_odds: ResMut<UnknownOdds>,
     mut ev_reader: EventReader<MapNodeSelectRequest>,
 ) {
     // Process all events
@@ -318,14 +987,26 @@ pub fn process_map_node_select_requests(
             game_map.visited_path.push((target_level, ev.index));
 
             // Transition
-            let node_type = game_map.levels[target_level][ev.index].node_type;
+            let mut node_type = game_map.levels[target_level][ev.index].node_type;
+            if node_type == NodeType::Unknown {
+                node_type = resolve_unknown_node(&mut unknown_odds, thread_rng().gen_range(0..100));
+                game_map.levels[target_level][ev.index].resolved = Some(node_type);
+                println!("The unknown path leads to: {:?}", node_type);
+            }
             match node_type {
                 NodeType::Battle | NodeType::Boss | NodeType::Elite => {
+                    commands.insert_resource(encounter_for_node(
+                        run_state.act,
+                        node_type,
+                        game_map.levels[target_level][ev.index].burning,
+                        battles_fought_this_act(&game_map),
+                    ));
                     next_game_state.set(GameState::Battle)
                 }
                 NodeType::Shop => next_game_state.set(GameState::Shop),
                 NodeType::Rest => next_game_state.set(GameState::Rest),
-                NodeType::Event => next_game_state.set(GameState::Event),
+                NodeType::Event | NodeType::Unknown => next_game_state.set(GameState::Event),
+                NodeType::Treasure => next_game_state.set(GameState::Treasure),
             }
         } else {
             println!("Node {} is not a valid path!", ev.index);
@@ -65,16 +65,49 @@ pub fn setup_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
                         },
                     ));
                 });
+
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(200.0),
+                            height: Val::Px(60.0),
+                            margin: UiRect::top(Val::Px(20.0)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        background_color: Color::srgb(0.3, 0.3, 0.4).into(),
+                        ..default()
+                    },
+                    OpenSettingsButton,
+                ))
+                .with_children(|p| {
+                    p.spawn(TextBundle::from_section(
+                        "Settings",
+                        TextStyle {
+                            font: Handle::default(),
+                            font_size: 30.0,
+                            color: Color::WHITE,
+                        },
+                    ));
+                });
         });
 }
 
 pub fn menu_interaction_system(
     mut next_game_state: ResMut<NextState<GameState>>,
     interaction_query: Query<&Interaction, (Changed<Interaction>, With<StartGameButton>)>,
+    settings_query: Query<&Interaction, (Changed<Interaction>, With<OpenSettingsButton>)>,
 ) {
     for interaction in &interaction_query {
         if *interaction == Interaction::Pressed {
             next_game_state.set(GameState::CharacterSelect);
         }
     }
+    for interaction in &settings_query {
+        if *interaction == Interaction::Pressed {
+            next_game_state.set(GameState::Settings);
+        }
+    }
 }
@@ -1,17 +1,16 @@
 use bevy::prelude::*;
-use rand::seq::SliceRandom;
-use rand::thread_rng;
 
 use crate::components::*;
+use crate::item_relics::Relic;
 use crate::resources::*;
 use crate::states::*;
 
 pub fn setup_rest_screen(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    player_query: Query<&Health, With<Player>>,
+    player_query: Query<(&Health, &RelicStore), With<Player>>,
 ) {
-    let health = player_query.single();
+    let (health, relics) = player_query.single();
 
     commands.spawn((
         SpriteBundle {
@@ -97,7 +96,7 @@ pub fn setup_rest_screen(
                 ))
                 .with_children(|p| {
                     p.spawn(TextBundle::from_section(
-                        "Upgrade Random Card",
+                        "Upgrade a Card",
                         TextStyle {
                             font: Handle::default(),
                             font_size: 30.0,
@@ -106,6 +105,36 @@ pub fn setup_rest_screen(
                     ));
                 });
 
+            // Remove Button (Peace Pipe)
+            if relics.relics.contains(&Relic::PeacePipe) {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(250.0),
+                                height: Val::Px(60.0),
+                                margin: UiRect::all(Val::Px(20.0)),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: Color::srgb(0.4, 0.2, 0.4).into(),
+                            ..default()
+                        },
+                        RestRemoveButton,
+                    ))
+                    .with_children(|p| {
+                        p.spawn(TextBundle::from_section(
+                            "Remove a Card",
+                            TextStyle {
+                                font: Handle::default(),
+                                font_size: 30.0,
+                                color: Color::WHITE,
+                            },
+                        ));
+                    });
+            }
+
             // Leave Button
             parent
                 .spawn((
@@ -137,11 +166,12 @@ pub fn setup_rest_screen(
 }
 
 pub fn rest_interaction_system(
+    mut commands: Commands,
     mut next_game_state: ResMut<NextState<GameState>>,
     mut player_query: Query<&mut Health, With<Player>>,
-    mut deck: ResMut<Deck>,
     heal_query: Query<&Interaction, (Changed<Interaction>, With<HealButton>)>,
     upgrade_query: Query<&Interaction, (Changed<Interaction>, With<UpgradeButton>)>,
+    remove_query: Query<&Interaction, (Changed<Interaction>, With<RestRemoveButton>)>,
     leave_query: Query<&Interaction, (Changed<Interaction>, With<LeaveRestButton>)>,
 ) {
     for interaction in &heal_query {
@@ -155,34 +185,30 @@ pub fn rest_interaction_system(
         }
     }
 
+    // Picking a card uses up the rest; cancelling comes back to the campfire
     for interaction in &upgrade_query {
         if *interaction == Interaction::Pressed {
-            // Upgrade random card
-            let mut rng = thread_rng();
-            let upgradable_indices: Vec<usize> = deck
-                .cards
-                .iter()
-                .enumerate()
-                .filter(|(_, c)| !c.upgraded)
-                .map(|(i, _)| i)
-                .collect();
+            commands.insert_resource(CardPicker {
+                service: DeckService::Upgrade,
+                cost: 0,
+                hp_cost: 0,
+                return_state: GameState::Map,
+                cancel_state: GameState::Rest,
+            });
+            next_game_state.set(GameState::CardPicker);
+        }
+    }
 
-            if let Some(&index) = upgradable_indices.choose(&mut rng) {
-                let card = &mut deck.cards[index];
-                card.upgraded = true;
-                card.name.push_str("+");
-                if card.damage > 0 {
-                    card.damage += 3;
-                }
-                if card.block > 0 {
-                    card.block += 3;
-                }
-                println!("Upgraded {}!", card.name);
-                next_game_state.set(GameState::Map);
-            } else {
-                println!("No cards to upgrade!");
-                next_game_state.set(GameState::Map);
-            }
+    for interaction in &remove_query {
+        if *interaction == Interaction::Pressed {
+            commands.insert_resource(CardPicker {
+                service: DeckService::Remove,
+                cost: 0,
+                hp_cost: 0,
+                return_state: GameState::Map,
+                cancel_state: GameState::Rest,
+            });
+            next_game_state.set(GameState::CardPicker);
         }
     }
 
@@ -1,9 +1,12 @@
+use crate::acts::{is_final_act, start_next_act};
 use crate::common::spawn_card_visual;
 use crate::components::*;
 use crate::item_cards::generate_random_card;
+use crate::item_relics::{droppable_relics, get_relic_name, get_relic_visuals};
 use crate::resources::*;
 use crate::states::*;
 use bevy::prelude::*;
+use rand::seq::SliceRandom;
 use rand::{Rng, thread_rng};
 
 pub fn setup_victory_screen(
@@ -11,31 +14,48 @@ pub fn setup_victory_screen(
     mut reward_store: ResMut<RewardStore>,
     game_map: Res<GameMap>,
     run_state: Res<RunState>,
+    player_query: Query<&RelicStore, With<Player>>,
+    encounter: Res<Encounter>,
 ) {
+    let node_type = if let Some((l, i)) = game_map.current_node {
+        game_map.levels[l][i].node_type
+    } else {
+        NodeType::Battle
+    };
+    let rewards = &encounter.rewards;
+
     // Generate rewards if not already generated
     if !reward_store.generated {
         let mut rng = thread_rng();
 
-        let node_type = if let Some((l, i)) = game_map.current_node {
-            game_map.levels[l][i].node_type
-        } else {
-            NodeType::Battle
-        };
-
         // Gold Reward
-        let (min, max) = if node_type == NodeType::Elite {
-            (50, 100)
-        } else {
-            (20, 50)
-        };
-        reward_store.gold_reward = Some(rng.gen_range(min..=max));
+        let (min, max) = rewards.gold;
+        if max > 0 {
+            reward_store.gold_reward = Some(rng.gen_range(min..=max.max(min)));
+        }
 
         // Card Reward (3 random choices)
-        let mut choices = Vec::new();
-        for _ in 0..3 {
-            choices.push(generate_random_card(run_state.character_class));
+        if rewards.cards {
+            let mut choices = Vec::new();
+            for _ in 0..3 {
+                choices.push(generate_random_card(run_state.character_class));
+            }
+            reward_store.card_choices = Some(choices);
         }
-        reward_store.card_choices = Some(choices);
+
+        // Relic Reward
+        let owned = player_query
+            .get_single()
+            .map(|store| store.relics.clone())
+            .unwrap_or_default();
+        let mut pool: Vec<_> = droppable_relics()
+            .into_iter()
+            .filter(|relic| !owned.contains(relic))
+            .collect();
+        pool.shuffle(&mut rng);
+        pool.truncate(rewards.relics);
+        reward_store.relic_rewards = pool;
+        reward_store.relic_choice = rewards.relic_choice;
 
         reward_store.generated = true;
     }
@@ -58,7 +78,11 @@ pub fn setup_victory_screen(
         ))
         .with_children(|parent| {
             parent.spawn(TextBundle::from_section(
-                "Enemy Defeated!",
+                if node_type == NodeType::Boss {
+                    format!("Act {} Complete!", run_state.act)
+                } else {
+                    "Enemy Defeated!".to_string()
+                },
                 TextStyle {
                     font: Handle::default(),
                     font_size: 50.0,
@@ -107,6 +131,50 @@ pub fn setup_victory_screen(
                     });
             }
 
+            // Relic Reward Buttons
+            if reward_store.relic_choice && !reward_store.relic_rewards.is_empty() {
+                parent.spawn(TextBundle::from_section(
+                    "Choose a relic",
+                    TextStyle {
+                        font: Handle::default(),
+                        font_size: 22.0,
+                        color: Color::srgb(0.8, 0.8, 0.8),
+                    },
+                ));
+            }
+            for relic in &reward_store.relic_rewards {
+                let (_, description, color) = get_relic_visuals(relic);
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(300.0),
+                                height: Val::Px(60.0),
+                                margin: UiRect::top(Val::Px(10.0)),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                border: UiRect::all(Val::Px(2.0)),
+                                ..default()
+                            },
+                            background_color: Color::srgb(0.2, 0.2, 0.2).into(),
+                            border_color: color.into(),
+                            ..default()
+                        },
+                        RewardRelicButton { relic: *relic },
+                        Tooltip { text: description },
+                    ))
+                    .with_children(|p| {
+                        p.spawn(TextBundle::from_section(
+                            format!("Relic: {}", get_relic_name(relic)),
+                            TextStyle {
+                                font: Handle::default(),
+                                font_size: 25.0,
+                                color: Color::WHITE,
+                            },
+                        ));
+                    });
+            }
+
             // Card Reward Button
             if reward_store.card_choices.is_some() {
                 parent
@@ -155,7 +223,13 @@ pub fn setup_victory_screen(
                 ))
                 .with_children(|p| {
                     p.spawn(TextBundle::from_section(
-                        "Proceed to Map",
+                        if node_type != NodeType::Boss {
+                            "Proceed to Map"
+                        } else if is_final_act(&run_state) {
+                            "Claim Victory"
+                        } else {
+                            "Onward to the Next Act"
+                        },
                         TextStyle {
                             font: Handle::default(),
                             font_size: 30.0,
@@ -170,16 +244,22 @@ pub fn reward_interaction_system(
     mut commands: Commands,
     mut next_game_state: ResMut<NextState<GameState>>,
     mut reward_store: ResMut<RewardStore>,
-    mut player_gold: Query<&mut Gold, With<Player>>,
+    mut player_gold: Query<(&mut Gold, &mut RelicStore), With<Player>>,
     gold_btn_query: Query<(Entity, &Interaction), (Changed<Interaction>, With<RewardGoldButton>)>,
+    relic_btn_query: Query<(Entity, &Interaction, &RewardRelicButton), Changed<Interaction>>,
+    all_relic_buttons: Query<Entity, With<RewardRelicButton>>,
     card_btn_query: Query<&Interaction, (Changed<Interaction>, With<RewardCardButton>)>,
     proceed_btn_query: Query<&Interaction, (Changed<Interaction>, With<ProceedButton>)>,
+    mut game_map: ResMut<GameMap>,
+    mut run_state: ResMut<RunState>,
+    mut player_health: Query<&mut Health, With<Player>>,
+    mut planner: ResMut<MapPlanner>,
 ) {
     // Handle Gold Reward
     for (entity, interaction) in &gold_btn_query {
         if *interaction == Interaction::Pressed {
             if let Some(amount) = reward_store.gold_reward {
-                if let Ok(mut gold) = player_gold.get_single_mut() {
+                if let Ok((mut gold, _)) = player_gold.get_single_mut() {
                     gold.amount += amount;
                     reward_store.gold_reward = None;
                     commands.entity(entity).despawn_recursive();
@@ -188,6 +268,29 @@ pub fn reward_interaction_system(
         }
     }
 
+    // Handle Relic Rewards
+    for (entity, interaction, button) in &relic_btn_query {
+        if *interaction == Interaction::Pressed {
+            if let Ok((_, mut relics)) = player_gold.get_single_mut() {
+                relics.relics.push(button.relic);
+                reward_store
+                    .relic_rewards
+                    .retain(|relic| *relic != button.relic);
+                println!("Obtained {}!", get_relic_name(&button.relic));
+                commands.entity(entity).despawn_recursive();
+                // Taking one relic of a choice gives up the rest
+                if reward_store.relic_choice {
+                    reward_store.relic_rewards.clear();
+                    for other in &all_relic_buttons {
+                        if other != entity {
+                            commands.entity(other).despawn_recursive();
+                        }
+                    }
+                }
+            }
+        }
+    }
+
     // Handle Card Reward
     for interaction in &card_btn_query {
         if *interaction == Interaction::Pressed {
@@ -198,6 +301,21 @@ pub fn reward_interaction_system(
     // Handle Proceed
     for interaction in &proceed_btn_query {
         if *interaction == Interaction::Pressed {
+            let beat_boss = game_map
+                .current_node
+                .is_some_and(|(l, i)| game_map.levels[l][i].node_type == NodeType::Boss);
+
+            if beat_boss && is_final_act(&run_state) {
+                next_game_state.set(GameState::RunVictory);
+                continue;
+            }
+            if beat_boss {
+                if let Ok(mut health) = player_health.get_single_mut() {
+                    start_next_act(&mut run_state, &mut game_map, &mut health);
+                    // Waypoints belong to the old act's map
+                    planner.waypoints.clear();
+                }
+            }
             next_game_state.set(GameState::Map);
         }
     }
@@ -0,0 +1,178 @@
+use crate::components::*;
+use crate::reflex::save_reflex_settings;
+use crate::resources::*;
+use crate::states::*;
+use bevy::prelude::*;
+
+const REFLEX_OPTIONS: [ReflexOption; 5] = [
+    ReflexOption::Difficulty,
+    ReflexOption::AutoReflex,
+    ReflexOption::SlowMotion,
+    ReflexOption::VisualCue,
+    ReflexOption::AudioCue,
+];
+
+pub fn reflex_option_text(option: ReflexOption, settings: &ReflexSettings) -> String {
+    let on_off = |enabled: bool| if enabled { "On" } else { "Off" };
+    match option {
+        ReflexOption::Difficulty => format!("Reflex Difficulty: {:?}", settings.difficulty),
+        ReflexOption::AutoReflex => format!("Auto-Reflex: {}", on_off(settings.auto_reflex)),
+        ReflexOption::SlowMotion => format!("Slow Motion: {}", on_off(settings.slow_motion)),
+        ReflexOption::VisualCue => format!("Visual Cue: {}", on_off(settings.visual_cue)),
+        ReflexOption::AudioCue => format!("Audio Cue: {}", on_off(settings.audio_cue)),
+    }
+}
+
+pub fn toggle_reflex_option(option: ReflexOption, settings: &mut ReflexSettings) {
+    match option {
+        ReflexOption::Difficulty => {
+            settings.difficulty = match settings.difficulty {
+                ReflexDifficulty::Relaxed => ReflexDifficulty::Normal,
+                ReflexDifficulty::Normal => ReflexDifficulty::Hard,
+                ReflexDifficulty::Hard => ReflexDifficulty::Relaxed,
+            }
+        }
+        ReflexOption::AutoReflex => settings.auto_reflex = !settings.auto_reflex,
+        ReflexOption::SlowMotion => settings.slow_motion = !settings.slow_motion,
+        ReflexOption::VisualCue => settings.visual_cue = !settings.visual_cue,
+        ReflexOption::AudioCue => settings.audio_cue = !settings.audio_cue,
+    }
+}
+
+pub fn setup_settings_screen(mut commands: Commands, settings: Res<ReflexSettings>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(12.0),
+                    ..default()
+                },
+                background_color: Color::srgba(0.1, 0.1, 0.1, 0.95).into(),
+                ..default()
+            },
+            SettingsUI,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Settings",
+                TextStyle {
+                    font: Handle::default(),
+                    font_size: 50.0,
+                    color: Color::WHITE,
+                },
+            ));
+
+            for option in REFLEX_OPTIONS {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(360.0),
+                                padding: UiRect::all(Val::Px(12.0)),
+                                justify_content: JustifyContent::Center,
+                                ..default()
+                            },
+                            background_color: Color::srgb(0.25, 0.25, 0.35).into(),
+                            ..default()
+                        },
+                        ReflexOptionButton { option },
+                    ))
+                    .with_children(|p| {
+                        p.spawn((
+                            TextBundle::from_section(
+                                reflex_option_text(option, &settings),
+                                TextStyle {
+                                    font: Handle::default(),
+                                    font_size: 22.0,
+                                    color: Color::WHITE,
+                                },
+                            ),
+                            ReflexOptionLabel { option },
+                        ));
+                    });
+            }
+
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(360.0),
+                            padding: UiRect::all(Val::Px(12.0)),
+                            justify_content: JustifyContent::Center,
+                            ..default()
+                        },
+                        background_color: Color::srgb(0.25, 0.35, 0.25).into(),
+                        ..default()
+                    },
+                    OpenCalibrationButton,
+                ))
+                .with_children(|p| {
+                    p.spawn(TextBundle::from_section(
+                        format!("Calibrate Input ({:+.0} ms)", settings.input_offset_ms),
+                        TextStyle {
+                            font: Handle::default(),
+                            font_size: 22.0,
+                            color: Color::WHITE,
+                        },
+                    ));
+                });
+
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            margin: UiRect::top(Val::Px(20.0)),
+                            padding: UiRect::all(Val::Px(15.0)),
+                            ..default()
+                        },
+                        background_color: Color::srgb(0.3, 0.3, 0.3).into(),
+                        ..default()
+                    },
+                    ReturnFromSettingsButton,
+                ))
+                .with_children(|p| {
+                    p.spawn(TextBundle::from_section(
+                        "Back",
+                        TextStyle {
+                            font: Handle::default(),
+                            font_size: 25.0,
+                            color: Color::WHITE,
+                        },
+                    ));
+                });
+        });
+}
+
+pub fn settings_interaction_system(
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut settings: ResMut<ReflexSettings>,
+    option_query: Query<(&Interaction, &ReflexOptionButton), Changed<Interaction>>,
+    return_query: Query<&Interaction, (Changed<Interaction>, With<ReturnFromSettingsButton>)>,
+    calibrate_query: Query<&Interaction, (Changed<Interaction>, With<OpenCalibrationButton>)>,
+    mut label_query: Query<(&ReflexOptionLabel, &mut Text)>,
+) {
+    for (interaction, button) in &option_query {
+        if *interaction == Interaction::Pressed {
+            toggle_reflex_option(button.option, &mut settings);
+            save_reflex_settings(&settings);
+            for (label, mut text) in &mut label_query {
+                text.sections[0].value = reflex_option_text(label.option, &settings);
+            }
+        }
+    }
+    for interaction in &return_query {
+        if *interaction == Interaction::Pressed {
+            next_game_state.set(GameState::MainMenu);
+        }
+    }
+    for interaction in &calibrate_query {
+        if *interaction == Interaction::Pressed {
+            next_game_state.set(GameState::Calibration);
+        }
+    }
+}
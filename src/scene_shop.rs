@@ -1,60 +1,59 @@
 use crate::common::spawn_card_visual;
 use crate::components::*;
-use crate::item_cards::generate_random_card;
-use crate::item_potions::{Potion, get_potion_name};
-use crate::item_relics::{Relic, get_relic_name};
+use crate::item_potions::get_potion_name;
+use crate::item_relics::{
+    RelicTier, get_relic_name, relic_pickup_service, relic_tier, relic_tier_name,
+};
 use crate::resources::*;
+use crate::shop_stock::*;
 use crate::states::*;
 use bevy::prelude::*;
-use rand::{Rng, thread_rng};
+use rand::thread_rng;
 
 pub fn setup_shop_screen(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    player_query: Query<&Gold, With<Player>>,
+    player_query: Query<&RelicStore, With<Player>>,
     run_state: Res<RunState>,
     mut shop_store: ResMut<ShopStore>,
 ) {
-    let gold = player_query.single().amount;
-
     if !shop_store.generated {
-        let mut rng = thread_rng();
-
-        // Generate Cards
-        shop_store.cards.clear();
-        for _ in 0..3 {
-            let card = generate_random_card(run_state.character_class);
-            let cost = rng.gen_range(40..80);
-            shop_store.cards.push(Some((card, cost)));
-        }
-
-        // Generate Relic
-        shop_store.relics.clear();
-        use rand::seq::SliceRandom;
-        let all_relics = vec![
-            Relic::Vajra,
-            Relic::BurningBlood,
-            Relic::Anchor,
-            Relic::OddlySmoothStone,
-            Relic::BagOfMarbles,
-        ];
-        let relic = *all_relics.choose(&mut rng).unwrap();
-        let cost = rng.gen_range(100..150);
-        shop_store.relics.push(Some((relic, cost)));
+        let relics = player_query.single();
+        stock_shop(
+            &mut shop_store,
+            run_state.character_class,
+            &relics.relics,
+            &mut thread_rng(),
+        );
+        shop_store.restocks = 0;
+        shop_store.haggled = false;
+        shop_store.merchant_line = None;
+        shop_store.generated = true;
+    }
 
-        // Generate Potion
-        shop_store.potions.clear();
-        let potion_type = match rng.gen_range(0..3) {
-            0 => Potion::Health,
-            1 => Potion::Strength,
-            _ => Potion::Energy,
-        };
-        let cost = rng.gen_range(20..40);
-        shop_store.potions.push(Some((potion_type, cost)));
+    // Let the refresh system build the screen, also when coming back from the card picker
+    shop_store.set_changed();
+}
 
-        shop_store.generated = true;
+// Rebuilds the whole screen whenever the stock changes
+pub fn shop_refresh_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    player_query: Query<(&Gold, &RelicStore, &PotionStore), With<Player>>,
+    shop_store: Res<ShopStore>,
+    shop_services: Res<ShopServices>,
+    ui_query: Query<Entity, (With<ShopUI>, Without<Parent>)>,
+) {
+    if !shop_store.is_changed() {
+        return;
+    }
+    for entity in &ui_query {
+        commands.entity(entity).despawn_recursive();
     }
 
+    let (gold, relics, potions) = player_query.single();
+    let gold = gold.amount;
+    let relics = &relics.relics;
+    let potions = &potions.potions;
+
     commands.spawn((
         SpriteBundle {
             texture: asset_server.load("images/backgrounds/RoadsideShop.jpg"),
@@ -94,20 +93,34 @@ pub fn setup_shop_screen(
                 ShopGoldText,
             ));
 
-            // Shop Items Container
+            let merchant_line = shop_store
+                .merchant_line
+                .clone()
+                .unwrap_or_else(|| merchant_greeting(gold).to_string());
+            parent.spawn(TextBundle::from_section(
+                merchant_line,
+                TextStyle {
+                    font: Handle::default(),
+                    font_size: 22.0,
+                    color: Color::srgb(0.9, 0.85, 0.7),
+                },
+            ));
+
+            // Cards: class slots first, then colorless
             parent
                 .spawn(NodeBundle {
                     style: Style {
                         flex_direction: FlexDirection::Row,
-                        margin: UiRect::all(Val::Px(20.0)),
+                        margin: UiRect::all(Val::Px(10.0)),
                         ..default()
                     },
                     ..default()
                 })
                 .with_children(|items| {
-                    // Sell a Card
                     for (index, item) in shop_store.cards.iter().enumerate() {
                         if let Some((card, cost)) = item {
+                            let cost = shop_price(*cost, relics);
+                            let on_sale = shop_store.sale_index == Some(index);
                             spawn_card_visual(
                                 items,
                                 &asset_server,
@@ -117,27 +130,56 @@ pub fn setup_shop_screen(
                                     Interaction::default(),
                                     BuyCardButton {
                                         card: card.clone(),
-                                        cost: *cost,
+                                        cost,
                                         index,
                                     },
                                 ),
                                 |card_ui| {
+                                    let (label, color) = if on_sale {
+                                        (format!("SALE {}g", cost), Color::srgb(0.3, 1.0, 0.3))
+                                    } else if index >= CLASS_CARD_SLOTS {
+                                        (
+                                            format!("Colorless {}g", cost),
+                                            Color::srgb(1.0, 0.84, 0.0),
+                                        )
+                                    } else {
+                                        (format!("{}g", cost), Color::srgb(1.0, 0.84, 0.0))
+                                    };
                                     card_ui.spawn(TextBundle::from_section(
-                                        format!("{}g", cost),
+                                        label,
                                         TextStyle {
                                             font: Handle::default(),
                                             font_size: 20.0,
-                                            color: Color::srgb(1.0, 0.84, 0.0),
+                                            color,
                                         },
                                     ));
                                 },
                             );
                         }
                     }
+                });
 
+            // Relics, potions and services
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        margin: UiRect::all(Val::Px(10.0)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|items| {
                     // Sell a Relic
                     for (index, item) in shop_store.relics.iter().enumerate() {
                         if let Some((relic, cost)) = item {
+                            let cost = shop_price(*cost, relics);
+                            let tier = relic_tier(relic);
+                            let background = if tier == RelicTier::Shop {
+                                Color::srgb(0.5, 0.35, 0.1)
+                            } else {
+                                Color::srgb(0.4, 0.2, 0.2)
+                            };
                             items
                                 .spawn((
                                     ButtonBundle {
@@ -151,19 +193,24 @@ pub fn setup_shop_screen(
                                             border: UiRect::all(Val::Px(2.0)),
                                             ..default()
                                         },
-                                        background_color: Color::srgb(0.4, 0.2, 0.2).into(),
+                                        background_color: background.into(),
                                         border_color: Color::WHITE.into(),
                                         ..default()
                                     },
                                     BuyRelicButton {
                                         relic: *relic,
-                                        cost: *cost,
+                                        cost,
                                         index,
                                     },
                                 ))
                                 .with_children(|b| {
                                     b.spawn(TextBundle::from_section(
-                                        format!("{}\n{}g", get_relic_name(relic), cost),
+                                        format!(
+                                            "{}\n{}\n{}g",
+                                            get_relic_name(relic),
+                                            relic_tier_name(tier),
+                                            cost
+                                        ),
                                         TextStyle {
                                             font: Handle::default(),
                                             font_size: 20.0,
@@ -177,6 +224,7 @@ pub fn setup_shop_screen(
                     // Sell a Potion
                     for (index, item) in shop_store.potions.iter().enumerate() {
                         if let Some((potion, cost)) = item {
+                            let cost = shop_price(*cost, relics);
                             let name = get_potion_name(potion);
                             items
                                 .spawn((
@@ -197,7 +245,7 @@ pub fn setup_shop_screen(
                                     },
                                     BuyPotionButton {
                                         potion: *potion,
-                                        cost: *cost,
+                                        cost,
                                         index,
                                     },
                                 ))
@@ -215,7 +263,7 @@ pub fn setup_shop_screen(
                     }
 
                     // Remove Card Service
-                    let remove_cost = 75;
+                    let remove_cost = card_removal_price(shop_services.removals, relics);
                     items
                         .spawn((
                             ButtonBundle {
@@ -245,6 +293,103 @@ pub fn setup_shop_screen(
                                 },
                             ));
                         });
+
+                    // Restock Service
+                    let restock_cost = restock_price(shop_store.restocks, relics);
+                    items
+                        .spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    width: Val::Px(120.0),
+                                    height: Val::Px(160.0),
+                                    margin: UiRect::all(Val::Px(10.0)),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    flex_direction: FlexDirection::Column,
+                                    border: UiRect::all(Val::Px(2.0)),
+                                    ..default()
+                                },
+                                background_color: Color::srgb(0.2, 0.3, 0.4).into(),
+                                border_color: Color::WHITE.into(),
+                                ..default()
+                            },
+                            RestockShopButton { cost: restock_cost },
+                        ))
+                        .with_children(|b| {
+                            b.spawn(TextBundle::from_section(
+                                format!("Restock\n{}g", restock_cost),
+                                TextStyle {
+                                    font: Handle::default(),
+                                    font_size: 20.0,
+                                    color: Color::WHITE,
+                                },
+                            ));
+                        });
+
+                    // Haggle, once per visit
+                    if !shop_store.haggled {
+                        items
+                            .spawn((
+                                ButtonBundle {
+                                    style: Style {
+                                        width: Val::Px(120.0),
+                                        height: Val::Px(160.0),
+                                        margin: UiRect::all(Val::Px(10.0)),
+                                        justify_content: JustifyContent::Center,
+                                        align_items: AlignItems::Center,
+                                        flex_direction: FlexDirection::Column,
+                                        border: UiRect::all(Val::Px(2.0)),
+                                        ..default()
+                                    },
+                                    background_color: Color::srgb(0.5, 0.4, 0.1).into(),
+                                    border_color: Color::WHITE.into(),
+                                    ..default()
+                                },
+                                HaggleButton,
+                            ))
+                            .with_children(|b| {
+                                b.spawn(TextBundle::from_section(
+                                    "Haggle",
+                                    TextStyle {
+                                        font: Handle::default(),
+                                        font_size: 20.0,
+                                        color: Color::WHITE,
+                                    },
+                                ));
+                            });
+                    }
+                });
+
+            // Sell relics and potions back to the merchant
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        flex_wrap: FlexWrap::Wrap,
+                        justify_content: JustifyContent::Center,
+                        margin: UiRect::all(Val::Px(10.0)),
+                        column_gap: Val::Px(10.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    for (index, relic) in relics.iter().enumerate() {
+                        let price = relic_sell_price(relic);
+                        spawn_sell_button(
+                            row,
+                            format!("Sell {} ({}g)", get_relic_name(relic), price),
+                            SellRelicButton { index, price },
+                        );
+                    }
+                    for (index, potion) in potions.iter().enumerate() {
+                        let price = potion_sell_price();
+                        spawn_sell_button(
+                            row,
+                            format!("Sell {} ({}g)", get_potion_name(potion), price),
+                            SellPotionButton { index, price },
+                        );
+                    }
                 });
 
             // Leave Button
@@ -273,190 +418,182 @@ pub fn setup_shop_screen(
         });
 }
 
+fn spawn_sell_button(parent: &mut ChildBuilder, label: String, button: impl Bundle) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    padding: UiRect::all(Val::Px(6.0)),
+                    ..default()
+                },
+                background_color: Color::srgb(0.25, 0.25, 0.3).into(),
+                ..default()
+            },
+            button,
+        ))
+        .with_children(|b| {
+            b.spawn(TextBundle::from_section(
+                label,
+                TextStyle {
+                    font: Handle::default(),
+                    font_size: 16.0,
+                    color: Color::WHITE,
+                },
+            ));
+        });
+}
+
 pub fn shop_interaction_system(
     mut commands: Commands,
     mut next_state: ResMut<NextState<GameState>>,
     mut player_query: Query<(&mut Gold, &mut RelicStore, &mut PotionStore), With<Player>>,
     mut shop_store: ResMut<ShopStore>,
     mut deck: ResMut<Deck>,
+    run_state: Res<RunState>,
+    mut run_stats: ResMut<RunStats>,
     mut card_interaction: Query<
-        (Entity, &Interaction, &BuyCardButton),
+        (&Interaction, &BuyCardButton),
         (Changed<Interaction>, With<BuyCardButton>),
     >,
     mut relic_interaction: Query<
-        (Entity, &Interaction, &BuyRelicButton),
+        (&Interaction, &BuyRelicButton),
         (Changed<Interaction>, With<BuyRelicButton>),
     >,
     mut potion_interaction: Query<
-        (Entity, &Interaction, &BuyPotionButton),
+        (&Interaction, &BuyPotionButton),
         (Changed<Interaction>, With<BuyPotionButton>),
     >,
     mut remove_service_interaction: Query<
         (&Interaction, &RemoveCardServiceButton),
         (Changed<Interaction>, With<RemoveCardServiceButton>),
     >,
+    mut restock_interaction: Query<
+        (&Interaction, &RestockShopButton),
+        (Changed<Interaction>, With<RestockShopButton>),
+    >,
 ) {
     let (mut gold, mut relics, mut potions) = player_query.single_mut();
 
-    for (entity, interaction, button) in &mut card_interaction {
+    // Emptying a slot changes the stock, which rebuilds the screen
+    for (interaction, button) in &mut card_interaction {
         if *interaction == Interaction::Pressed && gold.amount >= button.cost {
             gold.amount -= button.cost;
             deck.cards.push(button.card.clone());
             println!("Bought {}", button.card.name);
+            run_stats.record_purchase(run_state.act, &button.card.name, button.cost);
             shop_store.cards[button.index] = None;
-            commands.entity(entity).despawn_recursive();
         }
     }
 
-    for (entity, interaction, button) in &mut relic_interaction {
+    for (interaction, button) in &mut relic_interaction {
         if *interaction == Interaction::Pressed && gold.amount >= button.cost {
             gold.amount -= button.cost;
             relics.relics.push(button.relic);
-            println!("Bought Relic");
+            println!("Bought {}", get_relic_name(&button.relic));
+            run_stats.record_purchase(run_state.act, get_relic_name(&button.relic), button.cost);
             shop_store.relics[button.index] = None;
-            commands.entity(entity).despawn_recursive();
+            // The pick is already paid for with the relic; cancelling forfeits it
+            if let Some(service) = relic_pickup_service(&button.relic) {
+                commands.insert_resource(CardPicker {
+                    service,
+                    cost: 0,
+                    hp_cost: 0,
+                    return_state: GameState::Shop,
+                    cancel_state: GameState::Shop,
+                });
+                next_state.set(GameState::CardPicker);
+            }
         }
     }
 
-    for (entity, interaction, button) in &mut potion_interaction {
+    for (interaction, button) in &mut potion_interaction {
         if *interaction == Interaction::Pressed && gold.amount >= button.cost {
             gold.amount -= button.cost;
             potions.potions.push(button.potion);
             println!("Bought Potion");
+            run_stats.record_purchase(run_state.act, get_potion_name(&button.potion), button.cost);
             shop_store.potions[button.index] = None;
-            commands.entity(entity).despawn_recursive();
         }
     }
 
-    for (interaction, button) in &mut remove_service_interaction {
+    for (interaction, button) in &mut restock_interaction {
         if *interaction == Interaction::Pressed && gold.amount >= button.cost {
-            next_state.set(GameState::ShopRemoveCard);
+            gold.amount -= button.cost;
+            stock_shop(
+                &mut shop_store,
+                run_state.character_class,
+                &relics.relics,
+                &mut thread_rng(),
+            );
+            shop_store.restocks += 1;
+            run_stats.record_purchase(run_state.act, "Restock", button.cost);
+            println!("The merchant restocks the shelves");
         }
     }
-}
 
-pub fn update_shop_gold_ui(
-    player_gold_query: Query<&Gold, (With<Player>, Changed<Gold>)>,
-    mut shop_gold_text_query: Query<&mut Text, With<ShopGoldText>>,
-) {
-    if let Ok(gold) = player_gold_query.get_single() {
-        for mut text in &mut shop_gold_text_query {
-            text.sections[0].value = format!("Shop - Gold: {}", gold.amount);
+    for (interaction, button) in &mut remove_service_interaction {
+        if *interaction == Interaction::Pressed && gold.amount >= button.cost {
+            commands.insert_resource(CardPicker {
+                service: DeckService::Remove,
+                cost: button.cost,
+                hp_cost: 0,
+                return_state: GameState::Shop,
+                cancel_state: GameState::Shop,
+            });
+            next_state.set(GameState::CardPicker);
         }
     }
 }
 
-pub fn setup_shop_remove_screen(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    deck: Res<Deck>,
+pub fn merchant_interaction_system(
+    mut player_query: Query<(&mut Gold, &mut RelicStore, &mut PotionStore), With<Player>>,
+    mut shop_store: ResMut<ShopStore>,
+    run_state: Res<RunState>,
+    mut run_stats: ResMut<RunStats>,
+    haggle_query: Query<&Interaction, (Changed<Interaction>, With<HaggleButton>)>,
+    sell_relic_query: Query<(&Interaction, &SellRelicButton), Changed<Interaction>>,
+    sell_potion_query: Query<(&Interaction, &SellPotionButton), Changed<Interaction>>,
 ) {
-    commands
-        .spawn((
-            NodeBundle {
-                style: Style {
-                    width: Val::Percent(100.0),
-                    height: Val::Percent(100.0),
-                    flex_direction: FlexDirection::Column,
-                    align_items: AlignItems::Center,
-                    padding: UiRect::all(Val::Px(20.0)),
-                    ..default()
-                },
-                background_color: Color::srgba(0.05, 0.05, 0.05, 0.98).into(),
-                z_index: ZIndex::Global(100),
-                ..default()
-            },
-            ShopRemoveUI,
-        ))
-        .with_children(|parent| {
-            parent.spawn(TextBundle::from_section(
-                "Select Card to Remove (Cost: 75g)",
-                TextStyle {
-                    font: Handle::default(),
-                    font_size: 40.0,
-                    color: Color::WHITE,
-                },
-            ));
+    let (mut gold, mut relics, mut potions) = player_query.single_mut();
 
-            parent
-                .spawn(NodeBundle {
-                    style: Style {
-                        width: Val::Percent(100.0),
-                        flex_wrap: FlexWrap::Wrap,
-                        justify_content: JustifyContent::Center,
-                        margin: UiRect::top(Val::Px(20.0)),
-                        row_gap: Val::Px(10.0),
-                        column_gap: Val::Px(10.0),
-                        ..default()
-                    },
-                    ..default()
-                })
-                .with_children(|grid| {
-                    for (index, card) in deck.cards.iter().enumerate() {
-                        spawn_card_visual(
-                            grid,
-                            &asset_server,
-                            card,
-                            (Button, Interaction::default(), CardToRemoveButton { index }),
-                            |_| {},
-                        );
-                    }
-                });
+    for interaction in &haggle_query {
+        if *interaction == Interaction::Pressed && !shop_store.haggled {
+            let success = haggle(&mut shop_store, &mut thread_rng());
+            println!("Haggle {}", if success { "succeeded" } else { "failed" });
+        }
+    }
 
-            parent
-                .spawn((
-                    ButtonBundle {
-                        style: Style {
-                            margin: UiRect::top(Val::Px(30.0)),
-                            padding: UiRect::all(Val::Px(15.0)),
-                            ..default()
-                        },
-                        background_color: Color::srgb(0.3, 0.3, 0.3).into(),
-                        ..default()
-                    },
-                    CancelRemoveButton,
-                ))
-                .with_children(|p| {
-                    p.spawn(TextBundle::from_section(
-                        "Cancel",
-                        TextStyle {
-                            font: Handle::default(),
-                            font_size: 25.0,
-                            color: Color::WHITE,
-                        },
-                    ));
-                });
-        });
-}
+    // Selling doesn't touch the stock, so mark it changed to rebuild the sell row
+    for (interaction, button) in &sell_relic_query {
+        if *interaction == Interaction::Pressed && button.index < relics.relics.len() {
+            let relic = relics.relics.remove(button.index);
+            gold.amount += button.price;
+            println!("Sold {} for {}g", get_relic_name(&relic), button.price);
+            run_stats.record_sale(run_state.act, get_relic_name(&relic), button.price);
+            shop_store.set_changed();
+            return;
+        }
+    }
 
-pub fn shop_remove_system(
-    mut next_state: ResMut<NextState<GameState>>,
-    mut deck: ResMut<Deck>,
-    mut player_query: Query<&mut Gold, With<Player>>,
-    card_query: Query<
-        (&Interaction, &CardToRemoveButton),
-        (Changed<Interaction>, With<CardToRemoveButton>),
-    >,
-    cancel_query: Query<&Interaction, (Changed<Interaction>, With<CancelRemoveButton>)>,
-) {
-    for (interaction, button) in &card_query {
-        if *interaction == Interaction::Pressed {
-            if let Ok(mut gold) = player_query.get_single_mut() {
-                if gold.amount >= 75 {
-                    gold.amount -= 75;
-                    if button.index < deck.cards.len() {
-                        let removed = deck.cards.remove(button.index);
-                        println!("Removed {} from deck.", removed.name);
-                    }
-                    next_state.set(GameState::Shop);
-                }
-            }
+    for (interaction, button) in &sell_potion_query {
+        if *interaction == Interaction::Pressed && button.index < potions.potions.len() {
+            let potion = potions.potions.remove(button.index);
+            gold.amount += button.price;
+            println!("Sold {} for {}g", get_potion_name(&potion), button.price);
+            run_stats.record_sale(run_state.act, get_potion_name(&potion), button.price);
+            shop_store.set_changed();
+            return;
         }
     }
+}
 
-    for interaction in &cancel_query {
-        if *interaction == Interaction::Pressed {
-            next_state.set(GameState::Shop);
+pub fn update_shop_gold_ui(
+    player_gold_query: Query<&Gold, (With<Player>, Changed<Gold>)>,
+    mut shop_gold_text_query: Query<&mut Text, With<ShopGoldText>>,
+) {
+    if let Ok(gold) = player_gold_query.get_single() {
+        for mut text in &mut shop_gold_text_query {
+            text.sections[0].value = format!("Shop - Gold: {}", gold.amount);
         }
     }
 }
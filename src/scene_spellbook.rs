@@ -0,0 +1,129 @@
+use crate::components::*;
+use crate::resources::*;
+use crate::spell_recipes::{describe_effect, describe_pattern};
+use crate::states::*;
+use bevy::prelude::*;
+
+pub fn setup_spellbook_screen(mut commands: Commands, spellbook: Res<Spellbook>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    padding: UiRect::all(Val::Px(20.0)),
+                    row_gap: Val::Px(10.0),
+                    ..default()
+                },
+                background_color: Color::srgba(0.05, 0.03, 0.1, 0.98).into(),
+                z_index: ZIndex::Global(100),
+                ..default()
+            },
+            SpellbookUI,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                format!(
+                    "Spellbook ({}/{} discovered)",
+                    spellbook.discovered.len(),
+                    spellbook.recipes.len()
+                ),
+                TextStyle {
+                    font: Handle::default(),
+                    font_size: 40.0,
+                    color: Color::WHITE,
+                },
+            ));
+
+            for recipe in &spellbook.recipes {
+                // Undiscovered recipes keep their secrets until triggered once
+                let (name, detail, color) = if spellbook.discovered.contains(&recipe.id) {
+                    (
+                        recipe.name.clone(),
+                        format!(
+                            "{}: {}",
+                            describe_pattern(&recipe.pattern),
+                            describe_effect(&recipe.effect)
+                        ),
+                        Color::srgb(0.8, 0.6, 1.0),
+                    )
+                } else {
+                    (
+                        "???".to_string(),
+                        "???".to_string(),
+                        Color::srgb(0.4, 0.4, 0.4),
+                    )
+                };
+
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            width: Val::Px(600.0),
+                            flex_direction: FlexDirection::Column,
+                            padding: UiRect::all(Val::Px(8.0)),
+                            border: UiRect::all(Val::Px(1.0)),
+                            ..default()
+                        },
+                        border_color: color.into(),
+                        background_color: Color::srgba(0.0, 0.0, 0.0, 0.5).into(),
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        row.spawn(TextBundle::from_section(
+                            name,
+                            TextStyle {
+                                font: Handle::default(),
+                                font_size: 24.0,
+                                color,
+                            },
+                        ));
+                        row.spawn(TextBundle::from_section(
+                            detail,
+                            TextStyle {
+                                font: Handle::default(),
+                                font_size: 16.0,
+                                color: Color::srgb(0.8, 0.8, 0.8),
+                            },
+                        ));
+                    });
+            }
+
+            // Return Button
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            margin: UiRect::top(Val::Px(20.0)),
+                            padding: UiRect::all(Val::Px(15.0)),
+                            ..default()
+                        },
+                        background_color: Color::srgb(0.3, 0.3, 0.3).into(),
+                        ..default()
+                    },
+                    ReturnFromSpellbookButton,
+                ))
+                .with_children(|p| {
+                    p.spawn(TextBundle::from_section(
+                        "Back",
+                        TextStyle {
+                            font: Handle::default(),
+                            font_size: 25.0,
+                            color: Color::WHITE,
+                        },
+                    ));
+                });
+        });
+}
+
+pub fn spellbook_interaction_system(
+    mut next_game_state: ResMut<NextState<GameState>>,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<ReturnFromSpellbookButton>)>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            next_game_state.set(GameState::Map);
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+use rand::{Rng, thread_rng};
+
+use crate::components::*;
+use crate::item_relics::{droppable_relics, get_relic_name, get_relic_visuals};
+use crate::states::*;
+
+pub fn setup_treasure_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    player_query: Query<&RelicStore, With<Player>>,
+) {
+    let mut rng = thread_rng();
+
+    // Chests never hold a relic the player already owns
+    let owned = player_query
+        .get_single()
+        .map(|store| store.relics.clone())
+        .unwrap_or_default();
+    let relic = droppable_relics()
+        .into_iter()
+        .filter(|relic| !owned.contains(relic))
+        .collect::<Vec<_>>()
+        .choose(&mut rng)
+        .copied();
+    let gold = rng.gen_range(25..=50);
+
+    commands.spawn((
+        SpriteBundle {
+            texture: asset_server.load("images/backgrounds/RuinedForest.jpg"),
+            transform: Transform::from_xyz(0.0, 0.0, -100.0),
+            ..default()
+        },
+        TreasureUI,
+        SceneBackground,
+    ));
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                background_color: Color::srgba(0.1, 0.1, 0.1, 0.7).into(),
+                ..default()
+            },
+            TreasureUI,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Treasure Chest",
+                TextStyle {
+                    font: Handle::default(),
+                    font_size: 50.0,
+                    color: Color::srgb(1.0, 0.84, 0.0),
+                },
+            ));
+
+            // Relic
+            if let Some(relic) = relic {
+                let (_, description, color) = get_relic_visuals(&relic);
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(400.0),
+                                height: Val::Px(60.0),
+                                margin: UiRect::top(Val::Px(30.0)),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                border: UiRect::all(Val::Px(2.0)),
+                                ..default()
+                            },
+                            background_color: Color::srgb(0.2, 0.2, 0.2).into(),
+                            border_color: color.into(),
+                            ..default()
+                        },
+                        TreasureRelicButton { relic },
+                        Tooltip { text: description },
+                    ))
+                    .with_children(|p| {
+                        p.spawn(TextBundle::from_section(
+                            format!("Relic: {}", get_relic_name(&relic)),
+                            TextStyle {
+                                font: Handle::default(),
+                                font_size: 25.0,
+                                color: Color::WHITE,
+                            },
+                        ));
+                    });
+            }
+
+            // Gold
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(400.0),
+                            height: Val::Px(60.0),
+                            margin: UiRect::top(Val::Px(10.0)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            border: UiRect::all(Val::Px(2.0)),
+                            ..default()
+                        },
+                        background_color: Color::srgb(0.2, 0.2, 0.2).into(),
+                        border_color: Color::srgb(1.0, 0.84, 0.0).into(),
+                        ..default()
+                    },
+                    TreasureGoldButton { amount: gold },
+                ))
+                .with_children(|p| {
+                    p.spawn(TextBundle::from_section(
+                        format!("Gold: {}", gold),
+                        TextStyle {
+                            font: Handle::default(),
+                            font_size: 25.0,
+                            color: Color::srgb(1.0, 0.84, 0.0),
+                        },
+                    ));
+                });
+
+            // Leave Button
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(150.0),
+                            height: Val::Px(50.0),
+                            margin: UiRect::top(Val::Px(40.0)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        background_color: Color::srgb(0.3, 0.3, 0.3).into(),
+                        ..default()
+                    },
+                    LeaveTreasureButton,
+                ))
+                .with_children(|p| {
+                    p.spawn(TextBundle::from_section(
+                        "Leave",
+                        TextStyle {
+                            font: Handle::default(),
+                            font_size: 25.0,
+                            color: Color::WHITE,
+                        },
+                    ));
+                });
+        });
+}
+
+pub fn treasure_interaction_system(
+    mut commands: Commands,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut player_query: Query<(&mut Gold, &mut RelicStore), With<Player>>,
+    relic_query: Query<(Entity, &Interaction, &TreasureRelicButton), Changed<Interaction>>,
+    gold_query: Query<(Entity, &Interaction, &TreasureGoldButton), Changed<Interaction>>,
+    leave_query: Query<&Interaction, (Changed<Interaction>, With<LeaveTreasureButton>)>,
+) {
+    let Ok((mut gold, mut relics)) = player_query.get_single_mut() else {
+        return;
+    };
+
+    for (entity, interaction, button) in &relic_query {
+        if *interaction == Interaction::Pressed {
+            relics.relics.push(button.relic);
+            println!("Found {}!", get_relic_name(&button.relic));
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+
+    for (entity, interaction, button) in &gold_query {
+        if *interaction == Interaction::Pressed {
+            gold.amount += button.amount;
+            println!("Found {} gold!", button.amount);
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+
+    for interaction in &leave_query {
+        if *interaction == Interaction::Pressed {
+            next_game_state.set(GameState::Map);
+        }
+    }
+}
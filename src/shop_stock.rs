@@ -0,0 +1,214 @@
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+use crate::components::*;
+use crate::deck_services::shop_removal_price;
+use crate::item_cards::{colorless_cards, generate_random_card};
+use crate::item_potions::{Potion, all_potions};
+use crate::item_relics::{Relic, RelicTier, all_relics, droppable_relics, relic_tier};
+use crate::resources::ShopStore;
+
+pub const CLASS_CARD_SLOTS: usize = 4;
+pub const COLORLESS_CARD_SLOTS: usize = 2;
+// Tier-rolled relics; one shop-only relic is stocked on top of these
+pub const RELIC_SLOTS: usize = 2;
+pub const POTION_SLOTS: usize = 3;
+
+// Prices wander up to 10% either side of the base price
+pub const PRICE_JITTER: f32 = 0.1;
+pub const SALE_DISCOUNT: f32 = 0.5;
+pub const COLORLESS_MARKUP: f32 = 1.2;
+pub const POTION_BASE_PRICE: i32 = 30;
+
+pub const RESTOCK_BASE_PRICE: i32 = 40;
+pub const RESTOCK_PRICE_STEP: i32 = 20;
+
+// Haggling: a good haggle knocks prices down, a bad one offends the merchant
+pub const HAGGLE_SUCCESS_CHANCE: f64 = 0.6;
+pub const HAGGLE_DISCOUNT: f32 = 0.8;
+pub const HAGGLE_PENALTY: f32 = 1.1;
+
+// Fraction of the list price the merchant pays for things sold back
+pub const SELL_RATIO: f32 = 0.5;
+
+pub const MEMBERSHIP_DISCOUNT: f32 = 0.5;
+pub const SMILING_MASK_REMOVAL_PRICE: i32 = 50;
+
+pub fn card_base_price(rarity: Rarity) -> i32 {
+    match rarity {
+        Rarity::Common => 45,
+        Rarity::Rare => 70,
+        Rarity::Legendary => 140,
+    }
+}
+
+pub fn relic_base_price(tier: RelicTier) -> i32 {
+    match tier {
+        RelicTier::Common => 120,
+        RelicTier::Uncommon => 160,
+        RelicTier::Rare => 220,
+        RelicTier::Shop => 140,
+        RelicTier::Starter => 100,
+    }
+}
+
+pub fn jitter_price(base: i32, rng: &mut impl Rng) -> i32 {
+    (base as f32 * rng.gen_range(1.0 - PRICE_JITTER..=1.0 + PRICE_JITTER)).round() as i32
+}
+
+// Price after relic discounts. Stock is stored at list price so buying a discount
+// relic mid-visit reprices everything else on the shelf.
+pub fn shop_price(price: i32, relics: &[Relic]) -> i32 {
+    if relics.contains(&Relic::MembershipCard) {
+        (price as f32 * MEMBERSHIP_DISCOUNT).round() as i32
+    } else {
+        price
+    }
+}
+
+pub fn card_removal_price(removals: u32, relics: &[Relic]) -> i32 {
+    if relics.contains(&Relic::SmilingMask) {
+        return SMILING_MASK_REMOVAL_PRICE;
+    }
+    shop_price(shop_removal_price(removals), relics)
+}
+
+pub fn restock_price(restocks: u32, relics: &[Relic]) -> i32 {
+    if restocks == 0 && relics.contains(&Relic::Courier) {
+        return 0;
+    }
+    shop_price(
+        RESTOCK_BASE_PRICE + RESTOCK_PRICE_STEP * restocks as i32,
+        relics,
+    )
+}
+
+pub fn roll_relic_tier(rng: &mut impl Rng) -> RelicTier {
+    match rng.gen_range(0..100) {
+        0..50 => RelicTier::Common,
+        50..83 => RelicTier::Uncommon,
+        _ => RelicTier::Rare,
+    }
+}
+
+// A relic of the given tier the player doesn't own yet, falling back to any droppable tier
+pub fn pick_shop_relic(tier: RelicTier, excluded: &[Relic], rng: &mut impl Rng) -> Option<Relic> {
+    let unowned: Vec<Relic> = all_relics()
+        .into_iter()
+        .filter(|relic| !excluded.contains(relic))
+        .collect();
+    let of_tier: Vec<Relic> = unowned
+        .iter()
+        .copied()
+        .filter(|relic| relic_tier(relic) == tier)
+        .collect();
+    if !of_tier.is_empty() || tier == RelicTier::Shop {
+        return of_tier.choose(rng).copied();
+    }
+    unowned
+        .into_iter()
+        .filter(|relic| droppable_relics().contains(relic))
+        .collect::<Vec<_>>()
+        .choose(rng)
+        .copied()
+}
+
+// Fills every shelf with fresh stock at list price
+pub fn stock_shop(
+    store: &mut ShopStore,
+    class: CharacterClass,
+    owned_relics: &[Relic],
+    rng: &mut impl Rng,
+) {
+    store.cards.clear();
+    for _ in 0..CLASS_CARD_SLOTS {
+        let card = generate_random_card(class);
+        let cost = jitter_price(card_base_price(card.rarity), rng);
+        store.cards.push(Some((card, cost)));
+    }
+    for card in colorless_cards().choose_multiple(rng, COLORLESS_CARD_SLOTS) {
+        let base = card_base_price(card.rarity) as f32 * COLORLESS_MARKUP;
+        let cost = jitter_price(base.round() as i32, rng);
+        store.cards.push(Some((card.clone(), cost)));
+    }
+
+    // One class card is on sale
+    let sale_index = rng.gen_range(0..CLASS_CARD_SLOTS);
+    if let Some((_, cost)) = &mut store.cards[sale_index] {
+        *cost = (*cost as f32 * SALE_DISCOUNT).round() as i32;
+    }
+    store.sale_index = Some(sale_index);
+
+    store.relics.clear();
+    let mut excluded = owned_relics.to_vec();
+    let tiers = (0..RELIC_SLOTS)
+        .map(|_| roll_relic_tier(rng))
+        .chain(std::iter::once(RelicTier::Shop))
+        .collect::<Vec<_>>();
+    for tier in tiers {
+        if let Some(relic) = pick_shop_relic(tier, &excluded, rng) {
+            excluded.push(relic);
+            let cost = jitter_price(relic_base_price(relic_tier(&relic)), rng);
+            store.relics.push(Some((relic, cost)));
+        }
+    }
+
+    store.potions.clear();
+    let potions: Vec<Potion> = all_potions();
+    for _ in 0..POTION_SLOTS {
+        if let Some(potion) = potions.choose(rng) {
+            let cost = jitter_price(POTION_BASE_PRICE, rng);
+            store.potions.push(Some((*potion, cost)));
+        }
+    }
+}
+
+pub fn relic_sell_price(relic: &Relic) -> i32 {
+    (relic_base_price(relic_tier(relic)) as f32 * SELL_RATIO).round() as i32
+}
+
+pub fn potion_sell_price() -> i32 {
+    (POTION_BASE_PRICE as f32 * SELL_RATIO).round() as i32
+}
+
+fn scale_stock_prices(store: &mut ShopStore, factor: f32) {
+    let scale = |cost: &mut i32| *cost = (*cost as f32 * factor).round() as i32;
+    store
+        .cards
+        .iter_mut()
+        .flatten()
+        .for_each(|(_, cost)| scale(cost));
+    store
+        .relics
+        .iter_mut()
+        .flatten()
+        .for_each(|(_, cost)| scale(cost));
+    store
+        .potions
+        .iter_mut()
+        .flatten()
+        .for_each(|(_, cost)| scale(cost));
+}
+
+// One haggle per visit. Returns whether the merchant gave in.
+pub fn haggle(store: &mut ShopStore, rng: &mut impl Rng) -> bool {
+    store.haggled = true;
+    let success = rng.gen_bool(HAGGLE_SUCCESS_CHANCE);
+    if success {
+        scale_stock_prices(store, HAGGLE_DISCOUNT);
+        store.merchant_line = Some("\"Fine, fine! You drive a hard bargain.\"".to_string());
+    } else {
+        scale_stock_prices(store, HAGGLE_PENALTY);
+        store.merchant_line = Some("\"Insulting! Prices just went up.\"".to_string());
+    }
+    success
+}
+
+pub fn merchant_greeting(gold: i32) -> &'static str {
+    match gold {
+        ..50 => "\"Just looking, are we? Don't touch what you can't afford.\"",
+        50..150 => "\"Browse all you like, friend.\"",
+        150..300 => "\"Ah, a customer with coin! Take your time.\"",
+        _ => "\"My favourite customer! Everything is for sale, for you.\"",
+    }
+}
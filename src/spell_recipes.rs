@@ -0,0 +1,248 @@
+use serde::Deserialize;
+
+use crate::combat_math::ESSENCE_ELEMENTS;
+use crate::components::*;
+use crate::resources::Spellbook;
+
+const RECIPE_DATA: &str = include_str!("../assets/data/spell_recipes.json");
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SpellRecipe {
+    pub id: String,
+    pub name: String,
+    pub pattern: RecipePattern,
+    pub effect: RecipeEffect,
+}
+
+// The tail of the essence history a recipe reacts to
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecipePattern {
+    // These elements, in this order
+    Sequence(Vec<SpellElement>),
+    // This many different elements in a row, none of them Neutral
+    Distinct(usize),
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct RecipeEffect {
+    #[serde(default)]
+    pub bonus_damage: i32,
+    #[serde(default)]
+    pub bonus_block: i32,
+    // Applied to every enemy as soon as the recipe triggers
+    #[serde(default)]
+    pub status: Option<RecipeStatus>,
+    #[serde(default)]
+    pub draw: u32,
+    #[serde(default)]
+    pub mana_refund: i32,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecipeStatusKind {
+    Burning,
+    Frozen,
+    Poison,
+    Weak,
+    Vulnerable,
+    Stun,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct RecipeStatus {
+    pub kind: RecipeStatusKind,
+    pub amount: i32,
+}
+
+#[derive(Deserialize)]
+struct RecipeFile {
+    opposites: Vec<(SpellElement, SpellElement)>,
+    recipes: Vec<SpellRecipe>,
+}
+
+pub fn load_spellbook() -> Spellbook {
+    let file: RecipeFile = match serde_json::from_str(RECIPE_DATA) {
+        Ok(file) => file,
+        Err(error) => {
+            println!("Failed to parse spell recipes: {}", error);
+            RecipeFile {
+                opposites: Vec::new(),
+                recipes: Vec::new(),
+            }
+        }
+    };
+
+    let recipes = file
+        .recipes
+        .into_iter()
+        .filter(|recipe| match validate_recipe(recipe) {
+            Ok(()) => true,
+            Err(error) => {
+                println!("Skipping recipe {}: {}", recipe.id, error);
+                false
+            }
+        })
+        .collect();
+    Spellbook {
+        recipes,
+        opposites: file.opposites,
+        discovered: Vec::new(),
+    }
+}
+
+pub fn validate_recipe(recipe: &SpellRecipe) -> Result<(), String> {
+    match &recipe.pattern {
+        RecipePattern::Sequence(elements) => {
+            if elements.len() < 2 {
+                return Err("sequence needs at least two elements".to_string());
+            }
+            if elements.contains(&SpellElement::Neutral) {
+                return Err("sequence can't contain Neutral".to_string());
+            }
+        }
+        RecipePattern::Distinct(count) => {
+            if *count < 2 {
+                return Err("distinct needs at least two elements".to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn pattern_len(pattern: &RecipePattern) -> usize {
+    match pattern {
+        RecipePattern::Sequence(elements) => elements.len(),
+        RecipePattern::Distinct(count) => *count,
+    }
+}
+
+fn pattern_matches(pattern: &RecipePattern, history: &[SpellElement]) -> bool {
+    let len = pattern_len(pattern);
+    if history.len() < len {
+        return false;
+    }
+    let tail = &history[history.len() - len..];
+    match pattern {
+        RecipePattern::Sequence(elements) => tail == elements.as_slice(),
+        RecipePattern::Distinct(_) => tail
+            .iter()
+            .enumerate()
+            .all(|(i, element)| *element != SpellElement::Neutral && !tail[..i].contains(element)),
+    }
+}
+
+// The essence that cancels this one out, if any
+pub fn opposite_element(book: &Spellbook, element: SpellElement) -> Option<SpellElement> {
+    book.opposites.iter().find_map(|(a, b)| {
+        if *a == element {
+            Some(*b)
+        } else if *b == element {
+            Some(*a)
+        } else {
+            None
+        }
+    })
+}
+
+// Checks the newest essences against the book, earlier recipes first. On a match the
+// history starts over; otherwise it keeps only as much as the longest recipe can use.
+pub fn check_recipes(book: &Spellbook, history: &mut Vec<SpellElement>) -> Option<SpellRecipe> {
+    if let Some(recipe) = book
+        .recipes
+        .iter()
+        .find(|recipe| pattern_matches(&recipe.pattern, history))
+    {
+        history.clear();
+        return Some(recipe.clone());
+    }
+    let longest = book
+        .recipes
+        .iter()
+        .map(|recipe| pattern_len(&recipe.pattern))
+        .max()
+        .unwrap_or(0);
+    while history.len() >= longest && !history.is_empty() {
+        history.remove(0);
+    }
+    None
+}
+
+// Recipes a single extra essence would complete, with the essence that completes them
+pub fn recipes_one_away<'a>(
+    book: &'a Spellbook,
+    history: &[SpellElement],
+) -> Vec<(SpellElement, &'a SpellRecipe)> {
+    let mut found = Vec::new();
+    for element in ESSENCE_ELEMENTS {
+        let mut next = history.to_vec();
+        next.push(element);
+        if let Some(recipe) = book
+            .recipes
+            .iter()
+            .find(|recipe| pattern_matches(&recipe.pattern, &next))
+        {
+            found.push((element, recipe));
+        }
+    }
+    found
+}
+
+pub fn element_name(element: SpellElement) -> &'static str {
+    match element {
+        SpellElement::Neutral => "Neutral",
+        SpellElement::Fire => "Fire",
+        SpellElement::Ice => "Ice",
+        SpellElement::Wind => "Wind",
+        SpellElement::Stone => "Stone",
+        SpellElement::Lightning => "Lightning",
+        SpellElement::Shadow => "Shadow",
+        SpellElement::Water => "Water",
+    }
+}
+
+pub fn describe_pattern(pattern: &RecipePattern) -> String {
+    match pattern {
+        RecipePattern::Sequence(elements) => elements
+            .iter()
+            .map(|element| element_name(*element))
+            .collect::<Vec<_>>()
+            .join(" -> "),
+        RecipePattern::Distinct(count) => format!("{} different elements in a row", count),
+    }
+}
+
+pub fn describe_effect(effect: &RecipeEffect) -> String {
+    let mut parts = Vec::new();
+    if effect.bonus_damage > 0 {
+        parts.push(format!("+{} Damage", effect.bonus_damage));
+    }
+    if effect.bonus_block > 0 {
+        parts.push(format!("+{} Block", effect.bonus_block));
+    }
+    if let Some(status) = effect.status {
+        parts.push(format!(
+            "{} {:?} to all enemies",
+            status.amount, status.kind
+        ));
+    }
+    if effect.draw > 0 {
+        parts.push(format!("Draw {}", effect.draw));
+    }
+    if effect.mana_refund > 0 {
+        parts.push(format!("+{} Mana", effect.mana_refund));
+    }
+    parts.join(", ")
+}
+
+pub fn apply_recipe_status(status: RecipeStatus, target: &mut StatusStore) {
+    let field = match status.kind {
+        RecipeStatusKind::Burning => &mut target.burning,
+        RecipeStatusKind::Frozen => &mut target.frozen,
+        RecipeStatusKind::Poison => &mut target.poison,
+        RecipeStatusKind::Weak => &mut target.weak,
+        RecipeStatusKind::Vulnerable => &mut target.vulnerable,
+        RecipeStatusKind::Stun => &mut target.stun,
+    };
+    *field += status.amount;
+}
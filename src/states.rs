@@ -11,11 +11,16 @@ pub enum GameState {
     Map,
     GameOver,
     ViewDeck,
-    ShopRemoveCard,
+    Spellbook,
+    Settings,
+    Calibration,
+    CardPicker,
     RewardSelectCard,
     Event,
     CharacterSelect,
     BonusSelect,
+    RunVictory,
+    Treasure,
 }
 
 #[derive(States, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
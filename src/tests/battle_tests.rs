@@ -1,7 +1,10 @@
 use crate::cli::PlayCardRequest;
+use crate::combat_math::vulnerable_damage;
 use crate::components::*;
-use crate::resources::{DiscardPile, GameMap, RunState};
+use crate::enemies::enemy_turn_system;
+use crate::resources::{DiscardPile, RunState};
 use crate::scene_battle::{CardAnimating, process_play_card_requests};
+use crate::spell_recipes::{RecipeStatus, RecipeStatusKind, apply_recipe_status, load_spellbook};
 use crate::states::{GameState, TurnState};
 use bevy::prelude::*;
 
@@ -24,8 +27,9 @@ fn test_cli_play_card_request() {
     // Provide Resource Dependencies
     app.insert_resource(RunState {
         character_class: CharacterClass::Spellweaver,
+        act: 1,
     });
-    app.insert_resource(GameMap::default());
+    app.insert_resource(load_spellbook());
     app.insert_resource(DiscardPile::default());
 
     // Mock standard systems & events
@@ -55,6 +59,10 @@ fn test_cli_play_card_request() {
         is_spell_source: false,
         combo_points_granted: 0,
         finisher_combo_cost: 0,
+        finisher: Finisher::None,
+        unplayable: false,
+        missing_hp_scaling: 0,
+        reflex: ReflexVisualType::LinearSlider,
     };
 
     let card_entity = app
@@ -82,6 +90,9 @@ fn test_cli_play_card_request() {
             frozen: 0,
             stun: 0,
             strength: 0,
+            vulnerable: 0,
+            frail: 0,
+            vulnerable_fresh: false,
         },
         RelicStore { relics: vec![] },
         ActiveSpell {
@@ -112,6 +123,9 @@ fn test_cli_play_card_request() {
                 frozen: 0,
                 stun: 0,
                 strength: 0,
+                vulnerable: 0,
+                frail: 0,
+                vulnerable_fresh: false,
             },
             GlobalTransform::default(),
         ))
@@ -147,3 +161,45 @@ fn test_cli_play_card_request() {
     let mana = query.single(app.world());
     assert_eq!(mana.current, 2, "Mana should be reduced by 1");
 }
+
+#[test]
+fn test_vulnerable_enemies_take_more_until_their_turns_end() {
+    let mut status = StatusStore::default();
+    apply_recipe_status(
+        RecipeStatus {
+            kind: RecipeStatusKind::Vulnerable,
+            amount: 2,
+        },
+        &mut status,
+    );
+    assert_eq!(vulnerable_damage(10, &status), 15);
+
+    let mut app = App::new();
+    app.add_plugins(bevy::state::app::StatesPlugin);
+    app.init_state::<TurnState>();
+    app.add_systems(Update, enemy_turn_system);
+    app.world_mut().spawn((Player, StatusStore::default()));
+    let enemy = app
+        .world_mut()
+        .spawn((
+            Enemy {
+                kind: EnemyKind::Goblin,
+            },
+            Health {
+                current: 20,
+                max: 20,
+            },
+            status,
+        ))
+        .id();
+
+    // Each enemy turn that finishes takes one stack away
+    for expected in [1, 0] {
+        app.world_mut().spawn(EnemyQueue(Vec::new()));
+        app.update();
+        let status = app.world().get::<StatusStore>(enemy).unwrap();
+        assert_eq!(status.vulnerable, expected);
+    }
+    let status = app.world().get::<StatusStore>(enemy).unwrap();
+    assert_eq!(vulnerable_damage(10, status), 10);
+}
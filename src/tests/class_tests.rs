@@ -0,0 +1,113 @@
+use crate::classes::duelist::{combo_after_turn, combo_gained, spend_combo};
+use crate::classes::{PLAYABLE_CLASSES, missing_hp_damage, player_class};
+use crate::components::*;
+use crate::item_cards;
+use crate::item_relics::{Relic, RelicTier, droppable_relics, relic_tier};
+
+#[test]
+fn test_berserker_pays_missing_energy_in_blood() {
+    let mut energy = Energy { current: 1, max: 3 };
+    let mut health = Health {
+        current: 40,
+        max: 70,
+    };
+
+    // 1 energy, then 2 missing energy at 3 HP each
+    assert!(
+        player_class(CharacterClass::Berserker)
+            .pay_card_cost(3, &mut energy, None, &mut health)
+            .is_ok()
+    );
+    assert_eq!(energy.current, 0);
+    assert_eq!(health.current, 34);
+
+    // Paying can never kill the player
+    health.current = 9;
+    assert!(
+        player_class(CharacterClass::Berserker)
+            .pay_card_cost(3, &mut energy, None, &mut health)
+            .is_err()
+    );
+    assert_eq!(health.current, 9);
+
+    // Other classes never spend HP
+    let mut energy = Energy { current: 0, max: 3 };
+    assert!(
+        player_class(CharacterClass::Duelist)
+            .pay_card_cost(1, &mut energy, None, &mut health)
+            .is_err()
+    );
+    assert_eq!(health.current, 9);
+}
+
+#[test]
+fn test_missing_hp_scaling() {
+    let health = Health {
+        current: 30,
+        max: 70,
+    };
+    assert_eq!(missing_hp_damage(&item_cards::last_stand(), &health), 40);
+    assert_eq!(
+        missing_hp_damage(&item_cards::reckless_swing(), &health),
+        10
+    );
+    assert_eq!(missing_hp_damage(&item_cards::strike(), &health), 0);
+    assert_eq!(
+        player_class(CharacterClass::Berserker).bonus_damage(&health),
+        4
+    );
+    assert_eq!(
+        player_class(CharacterClass::Duelist).bonus_damage(&health),
+        0
+    );
+    assert_eq!(
+        player_class(CharacterClass::Berserker).starter_deck().len(),
+        10
+    );
+}
+
+#[test]
+fn test_every_class_starts_with_a_signature_relic() {
+    for class in PLAYABLE_CLASSES {
+        let relics = player_class(class).definition().starting_relics;
+        assert_eq!(relics.len(), 1);
+        assert_eq!(relic_tier(&relics[0]), RelicTier::Starter);
+        assert!(!droppable_relics().contains(&relics[0]));
+    }
+
+    // Dueling Gloves only hold on to Combo the player already has
+    let gloves = [Relic::DuelingGloves];
+    let gloves_and_metronome = [Relic::DuelingGloves, Relic::Metronome];
+    for (combo, kept, kept_with_metronome) in [(0, 0, 0), (1, 1, 1), (4, 4, 2)] {
+        assert_eq!(combo_after_turn(combo, &gloves), kept);
+        assert_eq!(
+            combo_after_turn(combo, &gloves_and_metronome),
+            kept_with_metronome
+        );
+    }
+}
+
+#[test]
+fn test_combo_decay_and_finisher_spending() {
+    // Combo only decays with the Metronome
+    assert_eq!(combo_after_turn(5, &[]), 5);
+    assert_eq!(combo_after_turn(5, &[Relic::Metronome]), 3);
+    assert_eq!(combo_after_turn(2, &[Relic::Metronome]), 0);
+    assert_eq!(
+        combo_after_turn(2, &[Relic::Metronome, Relic::DuelingGloves]),
+        1
+    );
+    assert_eq!(combo_gained(1, &[Relic::Metronome]), 2);
+    assert_eq!(combo_gained(0, &[Relic::Metronome]), 0);
+
+    // Double damage spends exactly its cost, scaling finishers spend everything
+    let mut combo = 2;
+    assert_eq!(spend_combo(&item_cards::heavy_blade(), &mut combo), None);
+    combo = 5;
+    assert_eq!(spend_combo(&item_cards::heavy_blade(), &mut combo), Some(3));
+    assert_eq!(combo, 2);
+    assert_eq!(spend_combo(&item_cards::flurry(), &mut combo), Some(2));
+    assert_eq!(combo, 0);
+    assert_eq!(spend_combo(&item_cards::riposte(), &mut combo), None);
+    assert_eq!(spend_combo(&item_cards::strike(), &mut combo), None);
+}
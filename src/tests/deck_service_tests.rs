@@ -0,0 +1,75 @@
+use crate::components::*;
+use crate::deck_services::{apply_deck_service, card_class, shop_removal_price, transform_options};
+use crate::item_cards;
+use crate::resources::Deck;
+
+#[test]
+fn test_deck_services_edit_the_chosen_card() {
+    let mut deck = Deck {
+        cards: vec![
+            item_cards::strike(),
+            item_cards::heavy_blade(),
+            item_cards::fire_essence(),
+            item_cards::wound(),
+        ],
+    };
+
+    assert!(
+        apply_deck_service(&mut deck, 0, DeckService::Upgrade, CharacterClass::Duelist).is_some()
+    );
+    assert_eq!(deck.cards[0].name, "Strike+");
+    // Upgraded and unplayable cards can't be upgraded
+    assert!(
+        apply_deck_service(&mut deck, 0, DeckService::Upgrade, CharacterClass::Duelist).is_none()
+    );
+    assert!(
+        apply_deck_service(&mut deck, 3, DeckService::Upgrade, CharacterClass::Duelist).is_none()
+    );
+
+    apply_deck_service(
+        &mut deck,
+        0,
+        DeckService::Duplicate,
+        CharacterClass::Duelist,
+    );
+    assert_eq!(deck.cards.len(), 5);
+    assert_eq!(deck.cards[4].name, "Strike+");
+
+    apply_deck_service(&mut deck, 4, DeckService::Remove, CharacterClass::Duelist);
+    assert_eq!(deck.cards.len(), 4);
+    assert!(
+        apply_deck_service(&mut deck, 9, DeckService::Remove, CharacterClass::Duelist).is_none()
+    );
+
+    // Transforms stay within the card's own class and rarity, even for the other class
+    for _ in 0..20 {
+        apply_deck_service(
+            &mut deck,
+            1,
+            DeckService::Transform,
+            CharacterClass::Spellweaver,
+        );
+        assert_eq!(card_class(&deck.cards[1]), Some(CharacterClass::Duelist));
+        assert_eq!(deck.cards[1].rarity, Rarity::Legendary);
+    }
+    assert!(
+        transform_options(&item_cards::heavy_blade(), CharacterClass::Duelist)
+            .iter()
+            .all(|card| card.name != "Heavy Blade")
+    );
+    // Status cards become a card of the player's class
+    apply_deck_service(
+        &mut deck,
+        3,
+        DeckService::Transform,
+        CharacterClass::Spellweaver,
+    );
+    assert_eq!(
+        card_class(&deck.cards[3]),
+        Some(CharacterClass::Spellweaver)
+    );
+
+    assert_eq!(shop_removal_price(0), 75);
+    assert!(shop_removal_price(1) > shop_removal_price(0));
+    assert!(shop_removal_price(3) > shop_removal_price(2));
+}
@@ -0,0 +1,249 @@
+use crate::combat_math::tick_player_vulnerable;
+use crate::components::*;
+use crate::enemies::{
+    AiPattern, EnemyAiContext, ai_pattern, apply_enemy_move_to_player, defended_damage,
+    enemy_attack_damage, enemy_move, enemy_turn_system, plan_enemy_intents_system, plan_enemy_move,
+    summon_enemies,
+};
+use crate::reflex::multi_tap_beats;
+use crate::resources::DiscardPile;
+use crate::states::TurnState;
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+
+fn ctx() -> EnemyAiContext {
+    EnemyAiContext {
+        hp_ratio: 1.0,
+        phase: 0,
+        player_block: 0,
+        player_weak: 0,
+        free_slots: 2,
+        ally_hp_ratio: 1.0,
+        self_debuffed: false,
+    }
+}
+
+fn history(moves: &[&str]) -> Vec<String> {
+    moves.iter().map(|m| m.to_string()).collect()
+}
+
+#[test]
+fn test_orc_never_defends_three_times_in_a_row() {
+    let past = history(&["Defends", "Defends"]);
+    for _ in 0..100 {
+        let next = plan_enemy_move(EnemyKind::Orc, &past, &ctx());
+        assert_ne!(next.name, "Defends");
+    }
+}
+
+#[test]
+fn test_every_move_list_name_resolves() {
+    let kinds = [
+        EnemyKind::Goblin,
+        EnemyKind::GoblinShaman,
+        EnemyKind::Orc,
+        EnemyKind::Dragon,
+        EnemyKind::Whelp,
+        EnemyKind::DarkKnight,
+    ];
+    // enemy_move asserts on names it doesn't know
+    for kind in kinds {
+        for phase in 0..3 {
+            let names: Vec<&str> = match ai_pattern(kind, phase) {
+                AiPattern::Cycle(moves) => moves.to_vec(),
+                AiPattern::Weighted { moves, .. } => moves.iter().map(|(name, _)| *name).collect(),
+            };
+            for name in names {
+                assert_eq!(enemy_move(name).name, name);
+            }
+
+            // Situational overrides and swaps
+            for (hp_ratio, player_block, player_weak, free_slots) in
+                [(1.0, 0, 0, 2), (0.2, 12, 2, 0), (0.25, 0, 0, 3)]
+            {
+                let situation = EnemyAiContext {
+                    hp_ratio,
+                    phase,
+                    player_block,
+                    player_weak,
+                    free_slots,
+                    ally_hp_ratio: 0.3,
+                    self_debuffed: hp_ratio < 0.5,
+                };
+                for turn in 0..4 {
+                    plan_enemy_move(kind, &history(&["Stabs"; 4][..turn]), &situation);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_conditional_enemy_moves() {
+    // Low HP orc raises its guard
+    let low_hp = EnemyAiContext {
+        hp_ratio: 0.2,
+        ..ctx()
+    };
+    let next = plan_enemy_move(EnemyKind::Orc, &history(&["Smashes"]), &low_hp);
+    assert_eq!(next.name, "Defends");
+
+    // Shaman doesn't hex a player who is already weak
+    let weak_player = EnemyAiContext {
+        player_weak: 2,
+        ..ctx()
+    };
+    let next = plan_enemy_move(EnemyKind::GoblinShaman, &[], &weak_player);
+    assert_eq!(next.name, "Zap");
+
+    // No reinforcements without room for them
+    let full = EnemyAiContext {
+        free_slots: 0,
+        ..ctx()
+    };
+    let next = plan_enemy_move(EnemyKind::GoblinShaman, &history(&["Hex"]), &full);
+    assert!(next.summon.is_empty());
+
+    // A charged breath is always released
+    let next = plan_enemy_move(EnemyKind::Dragon, &history(&["Deep Breath"]), &ctx());
+    assert_eq!(next.name, "Fire Breath");
+}
+
+#[test]
+fn test_intent_damage_matches_resolution() {
+    let next = plan_enemy_move(EnemyKind::Dragon, &[], &ctx());
+    assert_eq!(next.name, "Incinerates");
+
+    let status = StatusStore {
+        weak: 1,
+        ..Default::default()
+    };
+    assert_eq!(
+        enemy_attack_damage(&next, &status, &StatusStore::default()),
+        18
+    );
+
+    // Enemy strength is added before weak, vulnerable targets take 50% more
+    let status = StatusStore {
+        strength: 3,
+        weak: 1,
+        ..Default::default()
+    };
+    let vulnerable = StatusStore {
+        vulnerable: 1,
+        ..Default::default()
+    };
+    assert_eq!(enemy_attack_damage(&next, &status, &vulnerable), 31);
+}
+
+#[test]
+fn test_support_moves_react_to_allies() {
+    let wounded_ally = EnemyAiContext {
+        ally_hp_ratio: 0.3,
+        ..ctx()
+    };
+    let next = plan_enemy_move(EnemyKind::GoblinShaman, &[], &wounded_ally);
+    assert_eq!(next.heal_ally, 10);
+
+    let debuffed = EnemyAiContext {
+        self_debuffed: true,
+        ..ctx()
+    };
+    let next = plan_enemy_move(EnemyKind::DarkKnight, &[], &debuffed);
+    assert!(next.cleanse);
+
+    // Multi-hit attacks keep their per-hit damage
+    let next = plan_enemy_move(EnemyKind::Dragon, &history(&["Incinerates"]), &ctx());
+    assert_eq!((next.damage, next.hits), (6, 3));
+}
+
+#[test]
+fn test_roar_leaves_the_player_vulnerable_for_the_next_enemy_turn() {
+    let mut player = StatusStore::default();
+    let mut gold = Gold { amount: 0 };
+    let mut discard = DiscardPile::default();
+    let smash = enemy_move("Smashes");
+    let enemy = StatusStore::default();
+
+    apply_enemy_move_to_player(&enemy_move("Roar"), &mut player, &mut gold, &mut discard);
+    tick_player_vulnerable(&mut player);
+    assert_eq!(player.vulnerable, 1);
+    assert_eq!(enemy_attack_damage(&smash, &enemy, &player), 18);
+
+    // Worn off after that turn
+    tick_player_vulnerable(&mut player);
+    assert_eq!(enemy_attack_damage(&smash, &enemy, &player), 12);
+}
+
+#[test]
+fn test_summoned_minion_performs_the_intent_it_shows() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()));
+    app.add_plugins(bevy::state::app::StatesPlugin);
+    app.init_asset::<Image>();
+    app.init_state::<TurnState>();
+    app.world_mut()
+        .spawn((Player, Block { value: 0 }, StatusStore::default()));
+
+    let minions = app.world_mut().run_system_once(
+        |mut commands: Commands, asset_server: Res<AssetServer>| {
+            summon_enemies(&mut commands, &asset_server, &[EnemyKind::Whelp], &[0])
+        },
+    );
+    let minion = minions[0];
+    // Nothing is committed until the player's turn starts
+    assert_eq!(
+        app.world().get::<NextEnemyMove>(minion).unwrap().name,
+        "Planning..."
+    );
+
+    app.world_mut().run_system_once(plan_enemy_intents_system);
+    let shown = app
+        .world()
+        .get::<NextEnemyMove>(minion)
+        .unwrap()
+        .name
+        .clone();
+    assert_ne!(shown, "Planning...");
+
+    // Queue the enemy turn, then hand the minion its attack
+    app.world_mut().run_system_once(enemy_turn_system);
+    app.world_mut().run_system_once(enemy_turn_system);
+    assert!(app.world().get::<AttackingEnemy>(minion).is_some());
+    assert_eq!(
+        app.world().get::<NextEnemyMove>(minion).unwrap().name,
+        shown
+    );
+}
+
+#[test]
+fn test_enemy_multi_hits_are_defended_beat_by_beat() {
+    for name in ["Flurry", "Rake"] {
+        let next_move = enemy_move(name);
+        assert!(next_move.hits > 1);
+        assert_eq!(next_move.reflex, ReflexVisualType::MultiTap);
+    }
+
+    // Each hit is blocked by its own tap; an untapped beat lands in full
+    let reflex = ReflexState {
+        started_at: None,
+        timer: Timer::from_seconds(1.6, TimerMode::Once),
+        perfect_window_start: 0.7,
+        perfect_window_end: 0.9,
+        result: Some(ReflexSuccess::Good),
+        is_defensive: true,
+        source_entity: None,
+        target_entity: None,
+        base_damage: 10,
+        visual_type: ReflexVisualType::MultiTap,
+        good_margin: 0.1,
+        auto: false,
+        cue_played: false,
+        tolerance: 1.0,
+        beats: multi_tap_beats(3),
+        tap_results: vec![ReflexSuccess::Perfect, ReflexSuccess::Good],
+        prompt: None,
+        charge_started_at: None,
+    };
+    assert_eq!(defended_damage(&reflex), 1 + 5 + 10);
+}
@@ -0,0 +1,250 @@
+use crate::components::*;
+use crate::event_engine::{
+    EventContext, EventFight, Outcome, RandomOutcome, Requirement, fight_encounter,
+    load_event_library, pick_event, pick_random_outcome, split_picker_costs,
+};
+use crate::item_cards;
+use crate::item_relics::Relic;
+use crate::resources::{
+    ActiveEvent, CardPicker, Deck, EncounterRewards, EventHistory, RunState, RunStats, ShopServices,
+};
+use crate::scene_deck::card_picker_interaction_system;
+use crate::states::GameState;
+use bevy::prelude::*;
+
+#[test]
+fn test_bundled_events_load_and_validate() {
+    let library = load_event_library();
+    // Invalid events are dropped while loading, so every authored event should survive
+    let authored: Vec<serde_json::Value> =
+        serde_json::from_str(include_str!("../../assets/data/events.json")).unwrap();
+    assert_eq!(library.events.len(), authored.len());
+
+    for act in 1..=3 {
+        assert!(pick_event(&library, act, &EventHistory::default()).is_some());
+    }
+
+    // Once-per-run events are not offered again
+    let history = EventHistory {
+        seen: vec!["cursed_chest".to_string()],
+    };
+    for _ in 0..50 {
+        let event = pick_event(&library, 1, &history).unwrap();
+        assert_ne!(event.id, "cursed_chest");
+    }
+}
+
+#[test]
+fn test_event_requirements_and_outcomes() {
+    let mut health = Health {
+        current: 40,
+        max: 50,
+    };
+    let mut gold = Gold { amount: 20 };
+    let mut deck = Deck {
+        cards: vec![item_cards::strike(), item_cards::defend()],
+    };
+    let mut relics = RelicStore::default();
+    let mut potions = PotionStore::default();
+    let mut context = EventContext {
+        class: CharacterClass::Duelist,
+        health: &mut health,
+        gold: &mut gold,
+        deck: &mut deck,
+        relics: &mut relics,
+        potions: &mut potions,
+    };
+
+    assert!(context.meets_all(&[
+        Requirement::MinGold { amount: 20 },
+        Requirement::HasCard {
+            card: "Strike".to_string()
+        },
+        Requirement::Class {
+            class: CharacterClass::Duelist
+        },
+    ]));
+    assert!(!context.meets_all(&[Requirement::MinGold { amount: 21 }]));
+    assert!(!context.meets_all(&[Requirement::HasRelic {
+        relic: Relic::Vajra
+    }]));
+
+    context.apply(&Outcome::Heal { amount: 30 });
+    context.apply(&Outcome::Gold { amount: -10 });
+    context.apply(&Outcome::RemoveCard {
+        card: Some("Strike".to_string()),
+    });
+    context.apply(&Outcome::UpgradeCard { card: None });
+    context.apply(&Outcome::GainRelic {
+        relic: Some(Relic::Anchor),
+    });
+    context.apply(&Outcome::GainPotion { potion: None });
+
+    assert_eq!(health.current, 50);
+    assert_eq!(gold.amount, 10);
+    assert_eq!(deck.cards.len(), 1);
+    assert_eq!(deck.cards[0].name, "Defend+");
+    assert_eq!(relics.relics, vec![Relic::Anchor]);
+    assert_eq!(potions.potions.len(), 1);
+}
+
+#[test]
+fn test_random_outcomes_follow_weights() {
+    let random = vec![
+        RandomOutcome {
+            weight: 7,
+            outcomes: vec![],
+            next: Some("robbed".to_string()),
+        },
+        RandomOutcome {
+            weight: 3,
+            outcomes: vec![Outcome::Fight(EventFight::default())],
+            next: None,
+        },
+    ];
+
+    assert_eq!(
+        pick_random_outcome(&random, 6).unwrap().next.as_deref(),
+        Some("robbed")
+    );
+    assert_eq!(
+        pick_random_outcome(&random, 7).unwrap().outcomes,
+        vec![Outcome::Fight(EventFight::default())]
+    );
+}
+
+#[test]
+fn test_event_fights_override_the_encounter() {
+    let library = load_event_library();
+    let ambush = library
+        .events
+        .iter()
+        .find(|event| event.id == "goblin_ambush")
+        .unwrap();
+    let fight = ambush.pages["start"].choices[0]
+        .outcomes
+        .iter()
+        .find_map(|outcome| match outcome {
+            Outcome::Fight(fight) => Some(fight),
+            _ => None,
+        })
+        .unwrap();
+
+    let encounter = fight_encounter(fight, 1, 0);
+    assert_eq!(
+        encounter.enemies,
+        vec![
+            EnemyKind::Goblin,
+            EnemyKind::Goblin,
+            EnemyKind::GoblinShaman
+        ]
+    );
+    assert_eq!(encounter.rewards.gold, (35, 60));
+    assert!(encounter.burning.is_none());
+
+    // A bare fight falls back to the act's normal pool and rewards
+    let plain = fight_encounter(&EventFight::default(), 1, 0);
+    assert!(!plain.enemies.is_empty());
+    assert_eq!(plain.rewards, EncounterRewards::default());
+}
+
+fn smith_picker_app() -> App {
+    let library = load_event_library();
+    let smith = library
+        .events
+        .iter()
+        .find(|event| event.id == "wandering_smith")
+        .unwrap();
+    let choice = &smith.pages["start"].choices[0];
+    let (rest, cost, hp_cost) = split_picker_costs(&choice.outcomes);
+    assert_eq!((cost, hp_cost), (40, 0));
+    assert!(
+        !rest
+            .iter()
+            .any(|outcome| matches!(outcome, Outcome::Gold { .. }))
+    );
+
+    let mut app = App::new();
+    app.add_plugins(bevy::state::app::StatesPlugin);
+    app.init_state::<GameState>();
+    app.init_resource::<ShopServices>();
+    app.init_resource::<RunStats>();
+    app.init_resource::<RunState>();
+    app.insert_resource(Deck {
+        cards: vec![item_cards::strike()],
+    });
+    app.insert_resource(CardPicker {
+        service: DeckService::Upgrade,
+        cost,
+        hp_cost,
+        return_state: GameState::Event,
+        cancel_state: GameState::Event,
+    });
+    app.insert_resource(ActiveEvent {
+        id: smith.id.clone(),
+        page: "start".to_string(),
+        after_pick: choice.next.clone(),
+    });
+    app.world_mut().spawn((
+        Player,
+        Gold { amount: 100 },
+        Health {
+            current: 50,
+            max: 50,
+        },
+    ));
+    app.add_systems(Update, card_picker_interaction_system);
+    app
+}
+
+fn gold_and_page(app: &mut App) -> (i32, String) {
+    let gold = app
+        .world_mut()
+        .query_filtered::<&Gold, With<Player>>()
+        .single(app.world())
+        .amount;
+    (gold, app.world().resource::<ActiveEvent>().page.clone())
+}
+
+#[test]
+fn test_cancelling_an_event_card_pick_costs_nothing() {
+    let mut app = smith_picker_app();
+    app.world_mut()
+        .spawn((Interaction::Pressed, CancelCardPickerButton));
+    app.update();
+    // Back on the choice page with the gold still there
+    assert_eq!(gold_and_page(&mut app), (100, "start".to_string()));
+    assert_eq!(app.world().resource::<ActiveEvent>().after_pick, None);
+    assert_eq!(app.world().resource::<Deck>().cards[0].name, "Strike");
+
+    let mut app = smith_picker_app();
+    app.world_mut()
+        .spawn((Interaction::Pressed, CardPickerButton { index: 0 }));
+    app.update();
+    assert_eq!(gold_and_page(&mut app), (60, "upgraded".to_string()));
+    assert_eq!(app.world().resource::<Deck>().cards[0].name, "Strike+");
+
+    // A choice that ends the event still returns to it when cancelled
+    let mut app = smith_picker_app();
+    app.world_mut().resource_mut::<ActiveEvent>().after_pick = None;
+    app.world_mut().resource_mut::<CardPicker>().return_state = GameState::Map;
+    app.world_mut()
+        .spawn((Interaction::Pressed, CancelCardPickerButton));
+    app.update();
+    assert_eq!(gold_and_page(&mut app), (100, "start".to_string()));
+    assert_eq!(pending_state(&app), Some(GameState::Event));
+
+    // ...and only leaves for the map once a card is picked
+    app.world_mut()
+        .spawn((Interaction::Pressed, CardPickerButton { index: 0 }));
+    app.update();
+    assert!(app.world().get_resource::<ActiveEvent>().is_none());
+    assert_eq!(pending_state(&app), Some(GameState::Map));
+}
+
+fn pending_state(app: &App) -> Option<GameState> {
+    match app.world().resource::<NextState<GameState>>() {
+        NextState::Pending(state) => Some(*state),
+        _ => None,
+    }
+}
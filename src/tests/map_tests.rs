@@ -1,7 +1,11 @@
 use crate::cli::MapNodeSelectRequest;
-use crate::components::NodeType;
-use crate::resources::GameMap;
-use crate::scene_map::process_map_node_select_requests;
+use crate::components::{BurningBuff, NodeType, RouteCriterion};
+use crate::map_generator::{MAP_LEVELS, MapError, fallback_map, generate_map, validate_map};
+use crate::map_planner::{plan_route, reachable_from};
+use crate::resources::{GameMap, MapNodeData, RunState, UnknownOdds};
+use crate::scene_map::{
+    MIN_LEVEL_SPACING, level_spacing, process_map_node_select_requests, resolve_unknown_node,
+};
 use crate::states::GameState;
 use bevy::prelude::*;
 
@@ -23,12 +27,16 @@ fn test_cli_map_node_selection() {
                 node_type: NodeType::Battle,
                 next_indices: vec![0],
                 visible: true,
+                resolved: None,
+                burning: None,
             }], // Lvl 0
         ],
         current_node: None,
         visited_path: vec![],
     };
     app.insert_resource(mock_map);
+    app.init_resource::<UnknownOdds>();
+    app.init_resource::<RunState>();
 
     // Register Event and Systems
     app.add_event::<MapNodeSelectRequest>();
@@ -59,3 +67,206 @@ fn test_cli_map_node_selection() {
     let map = app.world().resource::<GameMap>();
     assert_eq!(map.current_node, Some((0, 0)));
 }
+
+fn node(node_type: NodeType, next_indices: Vec<usize>) -> MapNodeData {
+    MapNodeData {
+        node_type,
+        next_indices,
+        visible: false,
+        resolved: None,
+        burning: None,
+    }
+}
+
+// Smallest map that satisfies every rule, used as a base for the rejection tests
+fn valid_map() -> Vec<Vec<MapNodeData>> {
+    let mut levels = vec![
+        vec![
+            node(NodeType::Battle, vec![0]),
+            node(NodeType::Battle, vec![1]),
+        ],
+        vec![
+            node(NodeType::Elite, vec![0]),
+            node(NodeType::Shop, vec![1]),
+        ],
+        vec![
+            node(NodeType::Event, vec![0]),
+            node(NodeType::Unknown, vec![1]),
+        ],
+        vec![
+            node(NodeType::Unknown, vec![0]),
+            node(NodeType::Rest, vec![1]),
+        ],
+        vec![
+            node(NodeType::Treasure, vec![0]),
+            node(NodeType::Treasure, vec![1]),
+        ],
+        vec![
+            node(NodeType::Elite, vec![0]),
+            node(NodeType::Battle, vec![1]),
+        ],
+        vec![node(NodeType::Rest, vec![0]), node(NodeType::Rest, vec![0])],
+        vec![node(NodeType::Boss, vec![])],
+    ];
+    levels[1][0].burning = Some(BurningBuff::Vigor);
+    levels
+}
+
+#[test]
+fn test_generated_maps_pass_validation() {
+    for _ in 0..50 {
+        let levels = generate_map();
+        assert_eq!(validate_map(&levels), Ok(()));
+        assert_eq!(levels.len(), MAP_LEVELS);
+        assert!(levels.iter().any(|level| level.len() != levels[1].len()));
+    }
+
+    // What the generator falls back on when every roll is rejected
+    let fallback = fallback_map();
+    assert_eq!(validate_map(&fallback), Ok(()));
+    assert_eq!(fallback.len(), MAP_LEVELS);
+}
+
+#[test]
+fn test_validator_rejects_rule_violations() {
+    assert_eq!(validate_map(&valid_map()), Ok(()));
+
+    let mut crossing = valid_map();
+    crossing[1][0].next_indices = vec![1];
+    crossing[1][1].next_indices = vec![0];
+    assert_eq!(
+        validate_map(&crossing),
+        Err(MapError::CrossingPaths { level: 1 })
+    );
+
+    let mut no_rest = valid_map();
+    no_rest[6][1].node_type = NodeType::Battle;
+    assert_eq!(
+        validate_map(&no_rest),
+        Err(MapError::NoRestBeforeBoss { index: 1 })
+    );
+
+    let mut back_to_back = valid_map();
+    back_to_back[2][1].node_type = NodeType::Shop;
+    assert_eq!(
+        validate_map(&back_to_back),
+        Err(MapError::ConsecutiveNodes {
+            level: 2,
+            index: 1,
+            node_type: NodeType::Shop,
+        })
+    );
+
+    let mut few_elites = valid_map();
+    few_elites[5][0].node_type = NodeType::Battle;
+    assert_eq!(
+        validate_map(&few_elites),
+        Err(MapError::TooFew {
+            node_type: NodeType::Elite,
+            found: 1,
+            required: 2,
+        })
+    );
+
+    let mut no_treasure = valid_map();
+    no_treasure[4][0].node_type = NodeType::Battle;
+    assert_eq!(
+        validate_map(&no_treasure),
+        Err(MapError::MissingTreasure { index: 0 })
+    );
+
+    let mut two_burning = valid_map();
+    two_burning[5][0].burning = Some(BurningBuff::Thorns);
+    assert_eq!(
+        validate_map(&two_burning),
+        Err(MapError::BurningElites { found: 2 })
+    );
+
+    let mut burning_battle = valid_map();
+    burning_battle[5][1].burning = Some(BurningBuff::Strength);
+    assert_eq!(
+        validate_map(&burning_battle),
+        Err(MapError::BurningNonElite { level: 5, index: 1 })
+    );
+
+    let mut unreachable = valid_map();
+    unreachable[0][1].next_indices = vec![0];
+    assert_eq!(
+        validate_map(&unreachable),
+        Err(MapError::Unreachable { level: 1, index: 1 })
+    );
+}
+
+#[test]
+fn test_unknown_odds_shift_as_unknowns_resolve() {
+    let mut odds = UnknownOdds::default();
+
+    // A high roll lands on an event and makes every other outcome likelier
+    assert_eq!(resolve_unknown_node(&mut odds, 99), NodeType::Event);
+    assert_eq!(odds.battle, 20);
+    assert_eq!(odds.shop, 6);
+    assert_eq!(odds.treasure, 4);
+
+    // Rolling a battle resets only the battle odds
+    assert_eq!(resolve_unknown_node(&mut odds, 5), NodeType::Battle);
+    assert_eq!(odds.battle, 10);
+    assert_eq!(odds.shop, 9);
+    assert_eq!(odds.treasure, 6);
+}
+
+#[test]
+fn test_hover_reachability_and_map_spacing() {
+    let levels = valid_map();
+
+    // The right-hand column never crosses over to the left
+    let reachable = reachable_from(&levels, (3, 1));
+    assert_eq!(reachable.len(), 5);
+    assert!(reachable.contains(&(7, 0)));
+    assert!(!reachable.contains(&(4, 0)));
+
+    // Small windows keep a minimum spacing and scroll instead of squashing the map
+    assert_eq!(level_spacing(MAP_LEVELS, 800.0, 1.0), MIN_LEVEL_SPACING);
+    assert!(level_spacing(3, 1280.0, 1.0) > MIN_LEVEL_SPACING);
+}
+
+#[test]
+fn test_route_planner_follows_criterion_and_waypoints() {
+    let levels = valid_map();
+    let left_lane: Vec<(usize, usize)> = (0..7).map(|level| (level, 0)).chain([(7, 0)]).collect();
+    let right_lane: Vec<(usize, usize)> = (0..7).map(|level| (level, 1)).chain([(7, 0)]).collect();
+
+    // Both elites are on the left lane; the right lane has the extra rest and the shop
+    assert_eq!(
+        plan_route(&levels, None, RouteCriterion::MostElites, &[]),
+        left_lane
+    );
+    assert_eq!(
+        plan_route(&levels, None, RouteCriterion::MostRests, &[]),
+        right_lane
+    );
+    assert_eq!(
+        plan_route(&levels, None, RouteCriterion::FewestBattles, &[]),
+        right_lane
+    );
+
+    // A waypoint on the right lane wins over the criterion
+    assert_eq!(
+        plan_route(&levels, None, RouteCriterion::MostElites, &[(3, 1)]),
+        right_lane
+    );
+
+    // Planning from the middle of the map starts at the next level and skips waypoints behind
+    assert_eq!(
+        plan_route(&levels, Some((2, 0)), RouteCriterion::MostShops, &[(1, 1)]),
+        left_lane[3..].to_vec()
+    );
+}
+
+#[test]
+fn test_boss_rewards_offer_a_relic_choice() {
+    let boss = crate::acts::encounter_for_node(1, NodeType::Boss, None, 0);
+    let hallway = crate::acts::encounter_for_node(1, NodeType::Battle, None, 1);
+    assert!(boss.rewards.relic_choice);
+    assert_eq!(boss.rewards.relics, 3);
+    assert!(boss.rewards.gold.0 > hallway.rewards.gold.1);
+}
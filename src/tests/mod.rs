@@ -1,2 +1,9 @@
 pub mod battle_tests;
+pub mod class_tests;
+pub mod deck_service_tests;
+pub mod enemy_tests;
+pub mod event_tests;
 pub mod map_tests;
+pub mod reflex_tests;
+pub mod shop_tests;
+pub mod spellbook_tests;
@@ -0,0 +1,207 @@
+use bevy::prelude::*;
+use std::time::{Duration, Instant};
+
+use crate::cli::{ReflexInput, TriggerReflexRequest};
+use crate::components::*;
+use crate::reflex::{
+    apply_reflex_settings, average_offset_ms, beat_offset, handle_reflex_input, judge_reflex,
+    moving_target_center, multi_tap_beats, reflex_ratio,
+};
+use crate::resources::{ReflexDifficulty, ReflexSettings};
+
+fn attack_reflex() -> ReflexState {
+    ReflexState {
+        started_at: None,
+        timer: Timer::from_seconds(1.0, TimerMode::Once),
+        perfect_window_start: 0.7,
+        perfect_window_end: 0.8,
+        result: None,
+        is_defensive: false,
+        source_entity: None,
+        target_entity: None,
+        base_damage: 10,
+        visual_type: ReflexVisualType::LinearSlider,
+        good_margin: 0.1,
+        auto: false,
+        cue_played: false,
+        tolerance: 1.0,
+        beats: Vec::new(),
+        tap_results: Vec::new(),
+        prompt: None,
+        charge_started_at: None,
+    }
+}
+
+#[test]
+fn test_difficulty_presets_scale_the_reflex_windows() {
+    let normal = ReflexSettings::default();
+    let mut reflex = attack_reflex();
+    apply_reflex_settings(&mut reflex, &normal);
+    assert_eq!(judge_reflex(&reflex, 0.75), ReflexSuccess::Perfect);
+    assert_eq!(judge_reflex(&reflex, 0.65), ReflexSuccess::Good);
+    assert_eq!(judge_reflex(&reflex, 0.5), ReflexSuccess::Miss);
+
+    // Relaxed widens both windows around the same centre
+    let relaxed = ReflexSettings {
+        difficulty: ReflexDifficulty::Relaxed,
+        slow_motion: true,
+        ..default()
+    };
+    let mut reflex = attack_reflex();
+    apply_reflex_settings(&mut reflex, &relaxed);
+    assert_eq!(judge_reflex(&reflex, 0.68), ReflexSuccess::Perfect);
+    assert_eq!(judge_reflex(&reflex, 0.58), ReflexSuccess::Good);
+    assert_eq!(reflex.timer.duration().as_secs_f32(), 1.5);
+
+    let hard = ReflexSettings {
+        difficulty: ReflexDifficulty::Hard,
+        ..default()
+    };
+    let mut reflex = attack_reflex();
+    apply_reflex_settings(&mut reflex, &hard);
+    assert_eq!(judge_reflex(&reflex, 0.71), ReflexSuccess::Good);
+    assert_eq!(judge_reflex(&reflex, 0.65), ReflexSuccess::Miss);
+}
+
+#[test]
+fn test_settings_survive_a_save_round_trip() {
+    let settings = ReflexSettings {
+        difficulty: ReflexDifficulty::Hard,
+        auto_reflex: true,
+        ..default()
+    };
+    let text = serde_json::to_string(&settings).unwrap();
+    assert_eq!(
+        serde_json::from_str::<ReflexSettings>(&text).unwrap(),
+        settings
+    );
+    // Options added later fall back to their defaults in older files
+    let old: ReflexSettings = serde_json::from_str(r#"{"difficulty":"Relaxed"}"#).unwrap();
+    assert_eq!(old.difficulty, ReflexDifficulty::Relaxed);
+    assert!(old.visual_cue);
+}
+
+#[test]
+fn test_input_timestamps_and_calibration_offset() {
+    let mut reflex = attack_reflex();
+    let started_at = Instant::now();
+    reflex.started_at = Some(started_at);
+
+    // A press 750ms in is Perfect, however late in the frame it gets processed
+    let pressed_at = started_at + Duration::from_millis(750);
+    let ratio = reflex_ratio(&reflex, pressed_at, 0.0);
+    assert_eq!(judge_reflex(&reflex, ratio), ReflexSuccess::Perfect);
+
+    // A player measured at 100ms late gets that much taken off each press
+    let pressed_at = started_at + Duration::from_millis(850);
+    assert_eq!(
+        judge_reflex(&reflex, reflex_ratio(&reflex, pressed_at, 0.0)),
+        ReflexSuccess::Good
+    );
+    assert_eq!(
+        judge_reflex(&reflex, reflex_ratio(&reflex, pressed_at, 100.0)),
+        ReflexSuccess::Perfect
+    );
+
+    // Metronome taps are measured against the nearest beat
+    assert_eq!(beat_offset(0.1, 0.8), None);
+    let early = beat_offset(1.55, 0.8).unwrap();
+    let late = beat_offset(0.85, 0.8).unwrap();
+    assert!((early + 0.05).abs() < 1e-4);
+    assert!((late - 0.05).abs() < 1e-4);
+    assert!((average_offset_ms(&[early, late, 0.03]) - 10.0).abs() < 0.1);
+}
+
+#[test]
+fn test_minigames_judge_their_own_inputs() {
+    let started_at = Instant::now();
+    let request = |input, millis, cursor| TriggerReflexRequest {
+        input,
+        pressed_at: started_at + Duration::from_millis(millis),
+        cursor,
+    };
+    let window = Vec2::new(1000.0, 800.0);
+
+    // Multi-tap: one tap per beat, combined once the last beat is tapped
+    let mut reflex = ReflexState {
+        visual_type: ReflexVisualType::MultiTap,
+        beats: multi_tap_beats(3),
+        started_at: Some(started_at),
+        ..attack_reflex()
+    };
+    assert_eq!(reflex.beats.len(), 3);
+    for millis in [300, 575, 700] {
+        handle_reflex_input(
+            &mut reflex,
+            &request(ReflexInput::Press, millis, None),
+            0.0,
+            window,
+        );
+    }
+    assert_eq!(
+        reflex.tap_results,
+        vec![
+            ReflexSuccess::Perfect,
+            ReflexSuccess::Perfect,
+            ReflexSuccess::Miss
+        ]
+    );
+    assert_eq!(reflex.result, Some(ReflexSuccess::Good));
+
+    // Directional prompt: the wrong arrow misses even on time
+    let prompted = || ReflexState {
+        visual_type: ReflexVisualType::DirectionalPrompt,
+        prompt: Some(ReflexDirection::Left),
+        started_at: Some(started_at),
+        ..attack_reflex()
+    };
+    let mut reflex = prompted();
+    let left = ReflexInput::Direction(ReflexDirection::Left);
+    handle_reflex_input(&mut reflex, &request(left, 750, None), 0.0, window);
+    assert_eq!(reflex.result, Some(ReflexSuccess::Perfect));
+    let mut reflex = prompted();
+    let up = ReflexInput::Direction(ReflexDirection::Up);
+    handle_reflex_input(&mut reflex, &request(up, 750, None), 0.0, window);
+    assert_eq!(reflex.result, Some(ReflexSuccess::Miss));
+
+    // Charge meter: judged on how long it was held, not when it was pressed
+    let mut reflex = ReflexState {
+        visual_type: ReflexVisualType::ChargeMeter,
+        started_at: Some(started_at),
+        ..attack_reflex()
+    };
+    handle_reflex_input(
+        &mut reflex,
+        &request(ReflexInput::Press, 2000, None),
+        0.0,
+        window,
+    );
+    assert_eq!(reflex.result, None);
+    handle_reflex_input(
+        &mut reflex,
+        &request(ReflexInput::Release, 2750, None),
+        0.0,
+        window,
+    );
+    assert_eq!(reflex.result, Some(ReflexSuccess::Perfect));
+
+    // Moving target: judged on how close the click is to the target
+    let target_reflex = || ReflexState {
+        visual_type: ReflexVisualType::MovingTarget,
+        started_at: Some(started_at),
+        ..attack_reflex()
+    };
+    let target = moving_target_center(0.5, window);
+    let mut reflex = target_reflex();
+    let click = request(ReflexInput::Press, 500, Some(target + Vec2::new(10.0, 0.0)));
+    handle_reflex_input(&mut reflex, &click, 0.0, window);
+    assert_eq!(reflex.result, Some(ReflexSuccess::Perfect));
+    let mut reflex = target_reflex();
+    let click = request(
+        ReflexInput::Press,
+        500,
+        Some(target + Vec2::new(200.0, 0.0)),
+    );
+    handle_reflex_input(&mut reflex, &click, 0.0, window);
+    assert_eq!(reflex.result, Some(ReflexSuccess::Miss));
+}
@@ -0,0 +1,111 @@
+use rand::thread_rng;
+
+use crate::components::*;
+use crate::item_relics::{Relic, RelicTier, droppable_relics, relic_pickup_service, relic_tier};
+use crate::resources::{RunStats, ShopStore};
+use crate::shop_stock::*;
+
+#[test]
+fn test_shop_stock_prices_and_relic_hooks() {
+    let mut rng = thread_rng();
+
+    for _ in 0..20 {
+        let mut store = ShopStore::default();
+        stock_shop(
+            &mut store,
+            CharacterClass::Duelist,
+            &[Relic::Vajra],
+            &mut rng,
+        );
+
+        assert_eq!(store.cards.len(), CLASS_CARD_SLOTS + COLORLESS_CARD_SLOTS);
+        let sale_index = store.sale_index.unwrap();
+        assert!(sale_index < CLASS_CARD_SLOTS);
+        for (index, (card, cost)) in store.cards.iter().flatten().enumerate() {
+            let base = card_base_price(card.rarity) as f32;
+            let list = if index >= CLASS_CARD_SLOTS {
+                base * COLORLESS_MARKUP
+            } else {
+                base
+            };
+            let expected = if index == sale_index {
+                list * SALE_DISCOUNT
+            } else {
+                list
+            };
+            // Jitter plus rounding
+            assert!((*cost as f32 - expected).abs() <= expected * PRICE_JITTER + 1.0);
+        }
+
+        // Owned relics are never restocked, and the last relic slot is shop-only
+        assert!(
+            !store
+                .relics
+                .iter()
+                .flatten()
+                .any(|(relic, _)| *relic == Relic::Vajra)
+        );
+        let (last, _) = store.relics.last().unwrap().unwrap();
+        assert_eq!(relic_tier(&last), RelicTier::Shop);
+    }
+
+    assert!(card_base_price(Rarity::Common) < card_base_price(Rarity::Rare));
+    assert!(card_base_price(Rarity::Rare) < card_base_price(Rarity::Legendary));
+    assert!(
+        droppable_relics()
+            .iter()
+            .all(|relic| relic_tier(relic) != RelicTier::Shop)
+    );
+
+    assert_eq!(shop_price(100, &[Relic::MembershipCard]), 50);
+    assert_eq!(shop_price(100, &[Relic::Anchor]), 100);
+    assert!(card_removal_price(1, &[]) > card_removal_price(0, &[]));
+    assert_eq!(card_removal_price(4, &[Relic::SmilingMask]), 50);
+    assert!(restock_price(1, &[]) > restock_price(0, &[]));
+
+    // Buying the Astrolabe opens the card picker on a transform
+    assert_eq!(
+        relic_pickup_service(&Relic::Astrolabe),
+        Some(DeckService::Transform)
+    );
+    assert_eq!(relic_pickup_service(&Relic::Anchor), None);
+}
+
+#[test]
+fn test_haggling_selling_and_run_stats() {
+    let mut rng = thread_rng();
+    let mut saw_success = false;
+    let mut saw_failure = false;
+    for _ in 0..100 {
+        let mut store = ShopStore::default();
+        stock_shop(&mut store, CharacterClass::Spellweaver, &[], &mut rng);
+        let before: i32 = store.relics.iter().flatten().map(|(_, cost)| cost).sum();
+        let success = haggle(&mut store, &mut rng);
+        let after: i32 = store.relics.iter().flatten().map(|(_, cost)| cost).sum();
+
+        assert!(store.haggled);
+        assert!(store.merchant_line.is_some());
+        if success {
+            assert!(after < before);
+            saw_success = true;
+        } else {
+            assert!(after > before);
+            saw_failure = true;
+        }
+    }
+    assert!(saw_success && saw_failure);
+
+    // The merchant pays less than it charges
+    assert!(relic_sell_price(&Relic::Vajra) < relic_base_price(RelicTier::Uncommon));
+    assert!(potion_sell_price() < POTION_BASE_PRICE);
+    assert_ne!(merchant_greeting(10), merchant_greeting(500));
+    assert_eq!(restock_price(0, &[Relic::Courier]), 0);
+    assert!(restock_price(1, &[Relic::Courier]) > 0);
+
+    let mut stats = RunStats::default();
+    stats.record_purchase(1, "Strike", 40);
+    stats.record_purchase(2, "Card Removal", 100);
+    stats.record_sale(2, "Vajra", 80);
+    assert_eq!(stats.gold_spent(), 140);
+    assert_eq!(stats.gold_from_sales(), 80);
+}
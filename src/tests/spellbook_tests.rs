@@ -0,0 +1,179 @@
+use bevy::prelude::default;
+
+use crate::classes::spellweaver::{PreviewTarget, spell_preview};
+use crate::combat_math::{
+    ESSENCE_CAP, apply_spell_elements, cleanse_debuffs, shadow_lifesteal, would_overload,
+};
+use crate::components::EnemyKind::*;
+use crate::components::SpellElement::*;
+use crate::components::{ActiveSpell, EssenceInfo, Health, StatusStore};
+use crate::enemies::element_affinity;
+use crate::item_cards;
+use crate::spell_recipes::{apply_recipe_status, check_recipes, load_spellbook, opposite_element};
+
+#[test]
+fn test_bundled_recipes_load_and_match_sequences() {
+    let book = load_spellbook();
+    assert_eq!(book.recipes.len(), 9);
+    assert_eq!(opposite_element(&book, Ice), Some(Fire));
+    assert_eq!(opposite_element(&book, Wind), Some(Stone));
+    assert_eq!(opposite_element(&book, Neutral), None);
+
+    let mut history = vec![Fire, Ice, Wind];
+    let recipe = check_recipes(&book, &mut history).unwrap();
+    assert_eq!(recipe.id, "thermal_updraft");
+    assert!(history.is_empty());
+
+    // Exact sequences are listed before the generic Convergence
+    let mut history = vec![Stone, Fire, Ice];
+    assert_eq!(
+        check_recipes(&book, &mut history).unwrap().id,
+        "convergence"
+    );
+
+    // No match: only the last two essences are kept for the next check
+    let mut history = vec![Fire, Fire, Ice];
+    assert!(check_recipes(&book, &mut history).is_none());
+    assert_eq!(history, vec![Fire, Ice]);
+}
+
+#[test]
+fn test_spell_preview_matches_resolution() {
+    let mut book = load_spellbook();
+    let spell = ActiveSpell {
+        bonus_damage: 3,
+        bonus_block: 0,
+        element: Stone,
+        essences: vec![EssenceInfo {
+            element: Stone,
+            damage: 3,
+            block: 0,
+        }],
+        essence_history: vec![Stone, Stone],
+    };
+    let health = Health {
+        current: 50,
+        max: 50,
+    };
+    let frozen = StatusStore {
+        frozen: 1,
+        ..default()
+    };
+
+    // The frozen target shatters for double damage in both the preview and the cast
+    let mut target = frozen.clone();
+    let outcome = apply_spell_elements(10, &[Stone], &element_affinity(Goblin), &mut target);
+    assert_eq!(outcome.damage, 20);
+    assert_eq!(target.stun, 1);
+
+    let preview = spell_preview(
+        &item_cards::magic_bolt(),
+        &spell,
+        &[],
+        &StatusStore::default(),
+        &health,
+        &[PreviewTarget {
+            name: "Goblin".to_string(),
+            kind: Goblin,
+            status: frozen,
+            selected: true,
+        }],
+        &book,
+    );
+    assert!(preview.contains("Shattered"));
+    // Geode is one Stone away but stays hidden until discovered
+    assert!(preview.contains("Stone -> ???"));
+
+    book.discovered.push("geode".to_string());
+    let preview = spell_preview(
+        &item_cards::magic_bolt(),
+        &spell,
+        &[],
+        &StatusStore::default(),
+        &health,
+        &[],
+        &book,
+    );
+    assert!(preview.contains("Stone -> Geode"));
+}
+
+#[test]
+fn test_new_elements_and_affinities() {
+    // Dragons take extra Ice damage and shrug off Fire
+    let dragon = element_affinity(Dragon);
+    let mut target = StatusStore::default();
+    assert_eq!(
+        apply_spell_elements(10, &[Ice], &dragon, &mut target).damage,
+        15
+    );
+    assert_eq!(
+        apply_spell_elements(10, &[Fire], &dragon, &mut target).damage,
+        5
+    );
+    // Exploiting one element while another is resisted cancels out
+    assert_eq!(
+        apply_spell_elements(10, &[Fire, Ice], &dragon, &mut target).damage,
+        10
+    );
+
+    let mut target = StatusStore::default();
+    apply_spell_elements(4, &[Shadow], &element_affinity(Goblin), &mut target);
+    assert_eq!(target.weak, 1);
+    assert_eq!(shadow_lifesteal(&[Shadow], 8), 2);
+    assert_eq!(shadow_lifesteal(&[Fire], 8), 0);
+
+    let mut player = StatusStore {
+        weak: 2,
+        poison: 3,
+        strength: 1,
+        ..default()
+    };
+    assert!(cleanse_debuffs(&mut player));
+    assert_eq!((player.weak, player.poison, player.strength), (0, 0, 1));
+
+    let mut spell = ActiveSpell::default();
+    for _ in 0..ESSENCE_CAP {
+        assert!(!would_overload(&spell));
+        spell.essences.push(EssenceInfo {
+            element: Water,
+            damage: 0,
+            block: 3,
+        });
+    }
+    assert!(would_overload(&spell));
+}
+
+#[test]
+fn test_eclipse_leaves_enemies_vulnerable_to_the_next_spell() {
+    let book = load_spellbook();
+    let mut history = vec![Shadow, Fire, Shadow];
+    let eclipse = check_recipes(&book, &mut history).unwrap();
+    assert_eq!(eclipse.id, "eclipse");
+
+    let mut target = StatusStore::default();
+    apply_recipe_status(eclipse.effect.status.unwrap(), &mut target);
+    let health = Health {
+        current: 50,
+        max: 50,
+    };
+    let preview = |status: StatusStore| {
+        spell_preview(
+            &item_cards::magic_bolt(),
+            &ActiveSpell::default(),
+            &[],
+            &StatusStore::default(),
+            &health,
+            &[PreviewTarget {
+                name: "Orc".to_string(),
+                kind: Orc,
+                status,
+                selected: true,
+            }],
+            &book,
+        )
+    };
+
+    let damage = item_cards::magic_bolt().damage;
+    assert!(preview(StatusStore::default()).contains(&format!("Orc: {}", damage)));
+    assert!(preview(target).contains(&format!("Orc: {} (Vulnerable)", damage * 3 / 2)));
+}